@@ -0,0 +1,551 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+// 임의 정밀도 정수. 부호와 크기로 나타내고 크기는 2^32진법으로 아랫자리부터 저장함.
+// mag의 맨 윗자리는 0이 아니고, 0은 mag가 비어 있고 negative가 false
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    mag: Vec<u32>,
+}
+
+impl BigInt {
+    fn new(negative: bool, mut mag: Vec<u32>) -> BigInt {
+        trim(&mut mag);
+        let negative = negative && !mag.is_empty();
+        BigInt { negative, mag }
+    }
+
+    pub fn from_i64(n: i64) -> BigInt {
+        let abs = n.unsigned_abs();
+        BigInt::new(n < 0, vec![abs as u32, (abs >> 32) as u32])
+    }
+
+    // 정수로 딱 떨어지는 유한한 실수만 받음
+    pub fn from_f64(n: f64) -> BigInt {
+        let mut rest = n.abs().trunc();
+        let mut mag = Vec::new();
+        while rest >= 1.0 {
+            mag.push((rest % 4294967296.0) as u32);
+            rest = (rest / 4294967296.0).floor();
+        }
+        BigInt::new(n < 0.0, mag)
+    }
+
+    // 부호가 붙을 수 있는 10진수
+    pub fn parse(text: &str) -> Option<BigInt> {
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let mut mag = Vec::new();
+        for chunk in digits.as_bytes().chunks(9) {
+            let chunk = std::str::from_utf8(chunk).unwrap();
+            mul_small_add(&mut mag, 10u32.pow(chunk.len() as u32), chunk.parse().unwrap());
+        }
+        Some(BigInt::new(negative, mag))
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.mag.len() > 2 {
+            return None;
+        }
+        let abs = self
+            .mag
+            .iter()
+            .rev()
+            .fold(0u64, |acc, limb| (acc << 32) | *limb as u64);
+        if !self.negative {
+            i64::try_from(abs).ok()
+        } else if abs <= i64::MIN.unsigned_abs() {
+            Some((abs as i64).wrapping_neg())
+        } else {
+            None
+        }
+    }
+
+    // 너무 크면 inf가 됨
+    pub fn to_f64(&self) -> f64 {
+        let abs = self
+            .mag
+            .iter()
+            .rev()
+            .fold(0.0, |acc, limb| acc * 4294967296.0 + *limb as f64);
+        if self.negative {
+            -abs
+        } else {
+            abs
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn is_odd(&self) -> bool {
+        self.mag.first().is_some_and(|limb| limb & 1 == 1)
+    }
+
+    // 크기의 비트 수. 0은 0비트
+    fn bits(&self) -> usize {
+        match self.mag.last() {
+            Some(top) => self.mag.len() * 32 - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    pub fn neg(&self) -> BigInt {
+        BigInt::new(!self.negative, self.mag.clone())
+    }
+
+    pub fn add(&self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::new(self.negative, add_mag(&self.mag, &other.mag));
+        }
+        match cmp_mag(&self.mag, &other.mag) {
+            Ordering::Less => BigInt::new(other.negative, sub_mag(&other.mag, &self.mag)),
+            _ => BigInt::new(self.negative, sub_mag(&self.mag, &other.mag)),
+        }
+    }
+
+    pub fn sub(&self, other: &BigInt) -> BigInt {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &BigInt) -> BigInt {
+        BigInt::new(self.negative != other.negative, mul_mag(&self.mag, &other.mag))
+    }
+
+    pub fn pow(&self, mut exp: u32) -> BigInt {
+        let mut base = self.clone();
+        let mut res = BigInt::from_i64(1);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                res = res.mul(&base);
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.mul(&base);
+            }
+        }
+        res
+    }
+
+    // 파이썬처럼 몫은 내림하고 나머지의 부호는 나누는 수를 따름. 0으로 나누면 None
+    pub fn div_mod_floor(&self, other: &BigInt) -> Option<(BigInt, BigInt)> {
+        if other.mag.is_empty() {
+            return None;
+        }
+        let (quot, rem) = div_mod_mag(&self.mag, &other.mag);
+        let quot = BigInt::new(self.negative != other.negative, quot);
+        let rem = BigInt::new(self.negative, rem);
+        if !rem.mag.is_empty() && rem.negative != other.negative {
+            Some((quot.sub(&BigInt::from_i64(1)), rem.add(other)))
+        } else {
+            Some((quot, rem))
+        }
+    }
+
+    pub fn shl(&self, bits: usize) -> BigInt {
+        let (limbs, bits) = (bits / 32, bits % 32);
+        let mut mag = vec![0; limbs];
+        let mut carry = 0;
+        for limb in &self.mag {
+            mag.push((limb << bits) | carry);
+            carry = if bits == 0 { 0 } else { limb >> (32 - bits) };
+        }
+        mag.push(carry);
+        BigInt::new(self.negative, mag)
+    }
+
+    // 내림 시프트라서 음수는 -1로 수렴함
+    pub fn shr(&self, bits: usize) -> BigInt {
+        if bits >= self.mag.len() * 32 {
+            return BigInt::from_i64(if self.negative { -1 } else { 0 });
+        }
+        self.div_mod_floor(&BigInt::from_i64(1).shl(bits)).unwrap().0
+    }
+
+    // 실수 나눗셈. to_f64끼리 나누면 큰 수에서 inf/inf가 되므로 몫을 정수로 구한 뒤 한 번만 반올림함.
+    // 0으로 나누면 None, 결과가 너무 크면 inf
+    pub fn div_f64(&self, other: &BigInt) -> Option<f64> {
+        if other.mag.is_empty() {
+            return None;
+        }
+        let negative = self.negative != other.negative;
+        if self.mag.is_empty() {
+            return Some(if negative { -0.0 } else { 0.0 });
+        }
+        // 몫이 65비트 이상이 되도록 나눠지는 수를 키움
+        let shift = (other.bits() + 65).saturating_sub(self.bits());
+        let (quot, rem) = div_mod_mag(&self.shl(shift).mag, &other.mag);
+        let quot = BigInt::new(false, quot);
+        // 윗 64비트만 남기고, 버린 비트나 나머지가 있으면 맨 아랫비트를 켜서 반올림이 맞게 함
+        let dropped = quot.bits() - 64;
+        let top = quot.shr(dropped);
+        let inexact = !rem.iter().all(|limb| *limb == 0) || top.shl(dropped) != quot;
+        let top = top.mag.iter().rev().fold(0u64, |acc, limb| (acc << 32) | *limb as u64);
+        let abs = ldexp((top | inexact as u64) as f64, dropped as i64 - shift as i64);
+        Some(if negative { -abs } else { abs })
+    }
+
+    // ~x == -x - 1
+    pub fn not(&self) -> BigInt {
+        self.neg().sub(&BigInt::from_i64(1))
+    }
+
+    // 2의 보수로 바꿔서 자리별로 계산함. 음수는 윗자리가 끝없이 1인 것으로 봄
+    pub fn bitwise(&self, other: &BigInt, op: fn(u32, u32) -> u32) -> BigInt {
+        let len = self.mag.len().max(other.mag.len()) + 1;
+        let (xs, ys) = (self.to_twos(len), other.to_twos(len));
+        let mut limbs: Vec<u32> = xs.iter().zip(&ys).map(|(x, y)| op(*x, *y)).collect();
+        let negative = limbs.last().is_some_and(|top| top >> 31 == 1);
+        if negative {
+            negate_twos(&mut limbs);
+        }
+        BigInt::new(negative, limbs)
+    }
+
+    fn to_twos(&self, len: usize) -> Vec<u32> {
+        let mut limbs = self.mag.clone();
+        limbs.resize(len, 0);
+        if self.negative {
+            negate_twos(&mut limbs);
+        }
+        limbs
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.mag, &other.mag),
+            (true, true) => cmp_mag(&other.mag, &self.mag),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // 10^9씩 나눠서 아랫자리부터 모음
+        let mut rest = self.mag.clone();
+        let mut chunks = Vec::new();
+        while !rest.is_empty() {
+            chunks.push(div_small(&mut rest, 1_000_000_000));
+        }
+        if self.negative {
+            write!(f, "-")?;
+        }
+        match chunks.split_last() {
+            None => write!(f, "0"),
+            Some((top, lower)) => {
+                write!(f, "{}", top)?;
+                lower.iter().rev().try_for_each(|chunk| write!(f, "{:09}", chunk))
+            }
+        }
+    }
+}
+
+// x * 2^exp. 2^exp만 따로 구하면 x를 곱하기 전에 0이나 inf가 될 수 있어서 반씩 나눠 곱함
+fn ldexp(x: f64, exp: i64) -> f64 {
+    let exp = exp.clamp(-4000, 4000) as i32;
+    x * 2f64.powi(exp / 2) * 2f64.powi(exp - exp / 2)
+}
+
+fn trim(mag: &mut Vec<u32>) {
+    while mag.last() == Some(&0) {
+        mag.pop();
+    }
+}
+
+fn cmp_mag(xs: &[u32], ys: &[u32]) -> Ordering {
+    xs.len()
+        .cmp(&ys.len())
+        .then_with(|| xs.iter().rev().cmp(ys.iter().rev()))
+}
+
+fn add_mag(xs: &[u32], ys: &[u32]) -> Vec<u32> {
+    let len = xs.len().max(ys.len());
+    let mut res = Vec::with_capacity(len + 1);
+    let mut carry = 0u64;
+    for i in 0..len {
+        let sum = *xs.get(i).unwrap_or(&0) as u64 + *ys.get(i).unwrap_or(&0) as u64 + carry;
+        res.push(sum as u32);
+        carry = sum >> 32;
+    }
+    res.push(carry as u32);
+    res
+}
+
+// xs >= ys 여야 함
+fn sub_mag(xs: &[u32], ys: &[u32]) -> Vec<u32> {
+    let mut res = Vec::with_capacity(xs.len());
+    let mut borrow = 0i64;
+    for (i, x) in xs.iter().enumerate() {
+        let mut diff = *x as i64 - *ys.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if diff < 0 {
+            diff += 1 << 32;
+            borrow = 1;
+        }
+        res.push(diff as u32);
+    }
+    trim(&mut res);
+    res
+}
+
+fn mul_mag(xs: &[u32], ys: &[u32]) -> Vec<u32> {
+    let mut res = vec![0u32; xs.len() + ys.len()];
+    for (i, x) in xs.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, y) in ys.iter().enumerate() {
+            let cur = res[i + j] as u64 + *x as u64 * *y as u64 + carry;
+            res[i + j] = cur as u32;
+            carry = cur >> 32;
+        }
+        res[i + ys.len()] = carry as u32;
+    }
+    res
+}
+
+fn mul_small_add(mag: &mut Vec<u32>, mul: u32, add: u32) {
+    let mut carry = add as u64;
+    for limb in mag.iter_mut() {
+        let cur = *limb as u64 * mul as u64 + carry;
+        *limb = cur as u32;
+        carry = cur >> 32;
+    }
+    if carry > 0 {
+        mag.push(carry as u32);
+    }
+}
+
+// mag를 몫으로 바꾸고 나머지를 돌려줌
+fn div_small(mag: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut rem = 0u64;
+    for limb in mag.iter_mut().rev() {
+        let cur = (rem << 32) | *limb as u64;
+        *limb = (cur / divisor as u64) as u32;
+        rem = cur % divisor as u64;
+    }
+    trim(mag);
+    rem as u32
+}
+
+// 한 비트씩 내려오는 나눗셈. 나누는 수가 한 자리면 div_small을 씀
+fn div_mod_mag(xs: &[u32], ys: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if let [divisor] = ys {
+        let mut quot = xs.to_vec();
+        let rem = div_small(&mut quot, *divisor);
+        return (quot, vec![rem]);
+    }
+    let mut quot = vec![0u32; xs.len()];
+    let mut rem = Vec::new();
+    for i in (0..xs.len() * 32).rev() {
+        shl1_or(&mut rem, (xs[i / 32] >> (i % 32)) & 1);
+        if cmp_mag(&rem, ys) != Ordering::Less {
+            rem = sub_mag(&rem, ys);
+            quot[i / 32] |= 1 << (i % 32);
+        }
+    }
+    (quot, rem)
+}
+
+fn shl1_or(mag: &mut Vec<u32>, bit: u32) {
+    let mut carry = bit;
+    for limb in mag.iter_mut() {
+        let next = *limb >> 31;
+        *limb = (*limb << 1) | carry;
+        carry = next;
+    }
+    if carry > 0 {
+        mag.push(carry);
+    }
+}
+
+// 2의 보수 부호 바꾸기: 비트를 모두 뒤집고 1을 더함
+fn negate_twos(limbs: &mut [u32]) {
+    let mut carry = 1u64;
+    for limb in limbs.iter_mut() {
+        let sum = (!*limb) as u64 + carry;
+        *limb = sum as u32;
+        carry = sum >> 32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(text: &str) -> BigInt {
+        BigInt::parse(text).unwrap()
+    }
+
+    fn pow2(bits: usize) -> BigInt {
+        BigInt::from_i64(1).shl(bits)
+    }
+
+    #[test]
+    fn parse_and_display_round_trip() {
+        for text in ["0", "-1", "4294967296", "-1000000000000000000000000000001"] {
+            assert_eq!(big(text).to_string(), text);
+        }
+        assert_eq!(big("-0").to_string(), "0");
+        assert_eq!(big("+12").to_string(), "12");
+        assert_eq!(BigInt::parse(""), None);
+        assert_eq!(BigInt::parse("1_000"), None);
+    }
+
+    #[test]
+    fn carry_and_borrow_across_limbs() {
+        let max64 = big("18446744073709551615");
+        assert_eq!(max64.add(&big("1")), pow2(64));
+        assert_eq!(pow2(64).sub(&big("1")), max64);
+        assert_eq!(
+            pow2(96).sub(&big("1")).to_string(),
+            "79228162514264337593543950335"
+        );
+        assert_eq!(big("1").sub(&pow2(64)).to_string(), "-18446744073709551615");
+        assert_eq!(
+            max64.mul(&max64).to_string(),
+            "340282366920938463426481119284349108225"
+        );
+        assert_eq!(pow2(64).sub(&pow2(64)), big("0"));
+    }
+
+    #[test]
+    fn floor_div_mod_in_every_sign_combination() {
+        let x = big("8264141345021879123971");
+        let y = big("2199023255552");
+        let cases = [
+            (&x, &y, "3758096384", "3"),
+            (&x.neg(), &y, "-3758096385", "2199023255549"),
+            (&x, &y.neg(), "-3758096385", "-2199023255549"),
+            (&x.neg(), &y.neg(), "3758096384", "-3"),
+        ];
+        for (x, y, quot, rem) in cases {
+            let (q, r) = x.div_mod_floor(y).unwrap();
+            assert_eq!(
+                (q.to_string(), r.to_string()),
+                (quot.to_string(), rem.to_string())
+            );
+        }
+        assert_eq!(x.div_mod_floor(&big("0")), None);
+    }
+
+    #[test]
+    fn single_limb_divisors() {
+        let (q, r) = big("-18446744073709551617")
+            .div_mod_floor(&big("10"))
+            .unwrap();
+        assert_eq!(
+            (q.to_string(), r.to_string()),
+            ("-1844674407370955162".into(), "3".into())
+        );
+    }
+
+    #[test]
+    fn shifts_of_negative_numbers_floor() {
+        let x = pow2(70).neg();
+        assert_eq!(x.shr(3).to_string(), "-147573952589676412928");
+        assert_eq!(x.sub(&big("1")).shr(70).to_string(), "-2");
+        assert_eq!(x.shr(200).to_string(), "-1");
+        assert_eq!(pow2(70).shr(200).to_string(), "0");
+        assert_eq!(pow2(70).shr(69).to_string(), "2");
+        assert_eq!(big("-3").shl(64).to_string(), "-55340232221128654848");
+    }
+
+    #[test]
+    fn bitwise_ops_use_twos_complement() {
+        let x = pow2(70).neg();
+        let and = |a: &BigInt, b: &BigInt| a.bitwise(b, |x, y| x & y);
+        let or = |a: &BigInt, b: &BigInt| a.bitwise(b, |x, y| x | y);
+        let xor = |a: &BigInt, b: &BigInt| a.bitwise(b, |x, y| x ^ y);
+        assert_eq!(and(&x, &pow2(64).sub(&big("1"))).to_string(), "0");
+        assert_eq!(or(&x, &big("1")).to_string(), "-1180591620717411303423");
+        assert_eq!(xor(&x, &big("-1")).to_string(), "1180591620717411303423");
+        assert_eq!(
+            and(&x, &pow2(69).neg()).to_string(),
+            "-1180591620717411303424"
+        );
+        assert_eq!(
+            and(&pow2(64).neg().add(&big("5")), &pow2(70).sub(&big("1"))).to_string(),
+            "1162144876643701751813"
+        );
+        assert_eq!(pow2(70).not().to_string(), "-1180591620717411303425");
+        assert_eq!(x.not().to_string(), "1180591620717411303423");
+    }
+
+    #[test]
+    fn true_division_rounds_once() {
+        assert_eq!(big("10").pow(400).div_f64(&big("10").pow(399)), Some(10.0));
+        // 2^53 + 1은 실수로 바꾸는 순간 2^53이 되지만 몫은 제대로 반올림됨
+        assert_eq!(pow2(53).add(&big("1")).div_f64(&big("2")), Some(4503599627370496.5));
+        assert_eq!(pow2(200).add(&big("1")).div_f64(&pow2(200)), Some(1.0));
+        assert_eq!(big("1").div_f64(&pow2(1074)), Some(5e-324));
+        assert_eq!(big("-7").div_f64(&big("2")), Some(-3.5));
+        assert_eq!(big("0").div_f64(&big("-3")).map(f64::is_sign_negative), Some(true));
+        assert_eq!(pow2(1024).div_f64(&big("1")), Some(f64::INFINITY));
+        assert_eq!(big("1").div_f64(&big("0")), None);
+    }
+
+    #[test]
+    fn i64_boundaries() {
+        for n in [i64::MIN, i64::MIN + 1, -1, 0, 1, i64::MAX] {
+            let b = BigInt::from_i64(n);
+            assert_eq!(b.to_i64(), Some(n));
+            assert_eq!(b.to_string(), n.to_string());
+        }
+        let max = BigInt::from_i64(i64::MAX);
+        let min = BigInt::from_i64(i64::MIN);
+        assert_eq!(max.add(&big("1")).to_i64(), None);
+        assert_eq!(min.sub(&big("1")).to_i64(), None);
+        assert_eq!(min.neg().to_i64(), None);
+        assert_eq!(min.neg().to_string(), "9223372036854775808");
+        assert_eq!(max.add(&big("1")).sub(&big("1")).to_i64(), Some(i64::MAX));
+        assert_eq!(big("-9223372036854775808").to_i64(), Some(i64::MIN));
+        assert_eq!(pow2(64).to_i64(), None);
+    }
+
+    #[test]
+    fn float_conversions() {
+        assert_eq!(BigInt::from_f64(2f64.powi(70)), pow2(70));
+        assert_eq!(BigInt::from_f64(-3.0).to_string(), "-3");
+        assert_eq!(pow2(70).neg().to_f64(), -(2f64.powi(70)));
+        assert_eq!(pow2(2000).to_f64(), f64::INFINITY);
+    }
+
+    #[test]
+    fn ordering() {
+        let mut xs = [
+            pow2(64),
+            big("-1"),
+            pow2(64).neg(),
+            big("0"),
+            big("4294967296"),
+        ];
+        xs.sort();
+        let texts: Vec<String> = xs.iter().map(|x| x.to_string()).collect();
+        assert_eq!(
+            texts,
+            [
+                "-18446744073709551616",
+                "-1",
+                "0",
+                "4294967296",
+                "18446744073709551616"
+            ]
+        );
+    }
+}
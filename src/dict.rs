@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+
+use crate::bigint::BigInt;
+use crate::interpreter::{
+    float_to_int, type_of, Arity, ErrorKind, Interpreter, MethodDef, RuntimeError, Value,
+};
+
+// 딕셔너리 키. 해시할 수 있는 값만 키가 될 수 있음
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Key {
+    Int(i64),
+    BigInt(BigInt),
+    Number(u64),
+    String(String),
+    Bool(bool),
+    Nil,
+    Tuple(Vec<Key>),
+}
+
+impl Key {
+    pub fn from_value(val: &Value) -> Result<Key, RuntimeError> {
+        match val {
+            Value::Int(n) => Ok(Key::Int(*n)),
+            Value::BigInt(n) => Ok(Key::BigInt(n.clone())),
+            // 1.0은 1과 같은 키. 0.0과 -0.0도 같은 키
+            Value::Number(n) => Ok(match float_to_int(*n) {
+                Some(i) => Key::Int(i),
+                None if n.fract() == 0.0 => Key::BigInt(BigInt::from_f64(*n)),
+                None => Key::Number(n.to_bits()),
+            }),
+            Value::String(s) => Ok(Key::String(s.clone())),
+            Value::Bool(b) => Ok(Key::Bool(*b)),
+            Value::Nil => Ok(Key::Nil),
+            Value::Tuple(elts) => Ok(Key::Tuple(
+                elts.iter().map(Key::from_value).collect::<Result<_, _>>()?,
+            )),
+            _ => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!("unhashable type: {:?}", type_of(val)),
+            )),
+        }
+    }
+}
+
+// 삽입 순서를 유지하는 딕셔너리. index는 키에서 entries의 위치로
+#[derive(Debug, Default, Clone)]
+pub struct Dict {
+    entries: Vec<(Value, Value)>,
+    index: HashMap<Key, usize>,
+}
+
+impl Dict {
+    pub fn entries(&self) -> &[(Value, Value)] {
+        &self.entries
+    }
+
+    pub fn get(&self, key: &Value) -> Result<Option<&Value>, RuntimeError> {
+        let key = Key::from_value(key)?;
+        Ok(self.index.get(&key).map(|pos| &self.entries[*pos].1))
+    }
+
+    pub fn contains(&self, key: &Value) -> Result<bool, RuntimeError> {
+        Ok(self.index.contains_key(&Key::from_value(key)?))
+    }
+
+    // 이미 있는 키면 값만 바꾸고 순서는 그대로 둠
+    pub fn insert(&mut self, key: Value, value: Value) -> Result<(), RuntimeError> {
+        match self.index.get(&Key::from_value(&key)?) {
+            Some(pos) => self.entries[*pos].1 = value,
+            None => {
+                self.index.insert(Key::from_value(&key)?, self.entries.len());
+                self.entries.push((key, value));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, key: &Value) -> Result<Option<Value>, RuntimeError> {
+        let pos = match self.index.remove(&Key::from_value(key)?) {
+            Some(pos) => pos,
+            None => return Ok(None),
+        };
+        let (_, value) = self.entries.remove(pos);
+        for later in self.index.values_mut().filter(|later| **later > pos) {
+            *later -= 1;
+        }
+        Ok(Some(value))
+    }
+}
+
+// 파이썬 딕셔너리 메소드
+pub static METHODS: &[MethodDef] = &[
+    MethodDef {
+        name: "keys",
+        arity: Arity::exactly(0),
+        keywords: &[],
+        callable: keys,
+    },
+    MethodDef {
+        name: "values",
+        arity: Arity::exactly(0),
+        keywords: &[],
+        callable: values,
+    },
+    MethodDef {
+        name: "items",
+        arity: Arity::exactly(0),
+        keywords: &[],
+        callable: items,
+    },
+    MethodDef {
+        name: "get",
+        arity: Arity::range(1, 2),
+        keywords: &[],
+        callable: get,
+    },
+    MethodDef {
+        name: "pop",
+        arity: Arity::range(1, 2),
+        keywords: &[],
+        callable: pop,
+    },
+    MethodDef {
+        name: "update",
+        arity: Arity::exactly(1),
+        keywords: &[],
+        callable: update,
+    },
+];
+
+fn dict_id(receiver: &Value) -> u64 {
+    match receiver {
+        Value::Dict(dict_id) => *dict_id,
+        _ => panic!("Internal interpreter error! dict method called on {:?}.", receiver),
+    }
+}
+
+fn keys(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    _: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    let keys = interpreter
+        .get_dict(dict_id(receiver))
+        .entries()
+        .iter()
+        .map(|(key, _)| key.clone())
+        .collect();
+    Ok(interpreter.create_list(keys))
+}
+
+fn values(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    _: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    let values = interpreter
+        .get_dict(dict_id(receiver))
+        .entries()
+        .iter()
+        .map(|(_, value)| value.clone())
+        .collect();
+    Ok(interpreter.create_list(values))
+}
+
+fn items(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    _: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    let items = interpreter
+        .get_dict(dict_id(receiver))
+        .entries()
+        .iter()
+        .map(|(key, value)| Value::Tuple(vec![key.clone(), value.clone()]))
+        .collect();
+    Ok(interpreter.create_list(items))
+}
+
+fn get(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    args: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    let default = args.get(1).cloned().unwrap_or(Value::Nil);
+    Ok(interpreter
+        .get_dict(dict_id(receiver))
+        .get(&args[0])?
+        .cloned()
+        .unwrap_or(default))
+}
+
+fn pop(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    args: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    match interpreter.get_dict_mut(dict_id(receiver)).remove(&args[0])? {
+        Some(value) => Ok(value),
+        None => match args.get(1) {
+            Some(default) => Ok(default.clone()),
+            None => Err(interpreter.key_error(&args[0])),
+        },
+    }
+}
+
+// 다른 딕셔너리나 (키, 값) 쌍들을 받음
+fn update(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    args: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    let entries = match &args[0] {
+        Value::Dict(other_id) => interpreter.get_dict(*other_id).entries().to_vec(),
+        other => {
+            let mut entries = Vec::new();
+            for pair in interpreter.collect_iterable(other)? {
+                match interpreter.collect_iterable(&pair)?.as_slice() {
+                    [key, value] => entries.push((key.clone(), value.clone())),
+                    elts => {
+                        return Err(RuntimeError::new(
+                            ErrorKind::ValueError,
+                            format!(
+                                "dictionary update sequence element has length {}; 2 is required",
+                                elts.len()
+                            ),
+                        ))
+                    }
+                }
+            }
+            entries
+        }
+    };
+    let dict = interpreter.get_dict_mut(dict_id(receiver));
+    for (key, value) in entries {
+        dict.insert(key, value)?;
+    }
+    Ok(Value::Nil)
+}
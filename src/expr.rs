@@ -1,32 +1,108 @@
+use crate::bigint::BigInt;
+use crate::span::Span;
+
+#[derive(Debug, Clone)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+impl Expr {
+    pub fn new(kind: ExprKind, span: Span) -> Expr {
+        Expr { kind, span }
+    }
+}
+
+// 호출 인자. 키워드 인자는 위치 인자 뒤에만 올 수 있음
 #[derive(Debug, Clone)]
-pub enum Expr {
+pub enum Argument {
+    Positional(Expr),
+    Keyword(Symbol, Expr),
+    // f(*xs): 위치 인자로 펼침
+    Unpack(Expr),
+    // f(**d): 키워드 인자로 펼침
+    UnpackKeywords(Expr),
+}
+
+#[derive(Debug, Clone)]
+pub enum ExprKind {
     Literal(Literal),
     This(SourceLocation),
     Unary(UnaryOp, Box<Expr>),
     Binary(Box<Expr>, BinaryOp, Box<Expr>),
-    Call(Box<Expr>, SourceLocation, Vec<Expr>),
+    // 파이썬 방언의 a < b <= c. 가운데 피연산자는 한번만 평가함
+    Compare(Box<Expr>, Vec<(BinaryOp, Expr)>),
+    Call(Box<Expr>, SourceLocation, Vec<Argument>),
     Get(Box<Expr>, Symbol),
     Grouping(Box<Expr>),
     Variable(Symbol),
-    Assign(Symbol, Box<Expr>),
+    Assign(Symbol, Box<Expr>, Binding),
+    // a, b = b, a 처럼 구조 분해 대입
+    Destructure(Pattern, Box<Expr>, Binding),
     Logical(Box<Expr>, LogicalOp, Box<Expr>),
-    Set(Box<Expr>, Symbol, Box<Expr>),
+    // 조건, 참일 때, 거짓일 때. 고른 쪽만 평가함
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
+    // 복합 대입(+= 등)이면 연산자가 있음
+    Set(Box<Expr>, Symbol, Option<BinaryOp>, Box<Expr>),
     Super(SourceLocation, Symbol),
     List(Vec<Expr>),
+    Dict(Vec<(Expr, Expr)>),
+    Tuple(Vec<Expr>),
+    SetLiteral(Vec<Expr>),
+    // [x for x in xs if p(x)]. 절은 왼쪽부터 바깥 루프
+    ListComp(Box<Expr>, Vec<CompClause>),
+    SetComp(Box<Expr>, Vec<CompClause>),
+    DictComp(Box<Expr>, Box<Expr>, Vec<CompClause>),
+    // 튜플 안의 *xs. 튜플 원소나 대입 대상으로만 나옴
+    Starred(Box<Expr>, SourceLocation),
     Subscript {
         value: Box<Expr>,
         slice: Box<Expr>,
         source_location: SourceLocation,
     },
+    // xs[start:stop:step]. subscript 안에서만 나옴
+    Slice {
+        start: Option<Box<Expr>>,
+        stop: Option<Box<Expr>>,
+        step: Option<Box<Expr>>,
+    },
     SetItem {
         lhs: Box<Expr>,
         slice: Box<Expr>,
+        op: Option<BinaryOp>,
         rhs: Box<Expr>,
         source_location: SourceLocation,
     },
     Lambda(LambdaDecl),
 }
 
+#[derive(Debug, Clone)]
+pub enum CompClause {
+    For(Pattern, Expr),
+    If(Expr),
+}
+
+// 구조 분해 대상. (a, (b, c)), first, *rest 처럼 중첩될 수 있음
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    // 변수, 속성, subscript 중 하나
+    Target(Box<Expr>),
+    // 남은 값들을 리스트로 받음
+    Starred(Box<Expr>),
+    Tuple(Vec<Pattern>),
+}
+
+// 변수에 값을 묶는 방법
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Binding {
+    // for 변수처럼 항상 지금 스코프에 새로 선언함
+    Define,
+    // 선언된 변수에만 대입함. 없으면 NameError (Lox)
+    Assign,
+    // 선언돼 있으면 대입하고 없으면 지금 스코프에 선언함 (파이썬 방언)
+    AssignOrDefine,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct SourceLocation {
     pub line: usize,
@@ -46,16 +122,36 @@ pub struct Symbol {
     pub col: i64,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamKind {
+    // 위치나 키워드로 받음
+    Normal,
+    // *args 뒤에 있어서 키워드로만 받음
+    KeywordOnly,
+    // *args: 남은 위치 인자의 튜플
+    VarArgs,
+    // **kwargs: 남은 키워드 인자의 딕셔너리
+    VarKeywords,
+}
+
+// 기본값은 함수를 정의할 때 한번만 평가함
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub name: Symbol,
+    pub kind: ParamKind,
+    pub default: Option<Expr>,
+}
+
 #[derive(Debug, Clone)]
 pub struct FunDecl {
     pub name: Symbol,
-    pub params: Vec<Symbol>,
+    pub params: Vec<Param>,
     pub body: Vec<Stmt>,
 }
 
 #[derive(Debug, Clone)]
 pub struct LambdaDecl {
-    pub params: Vec<Symbol>,
+    pub params: Vec<Param>,
     pub body: Vec<Stmt>,
 }
 
@@ -66,8 +162,38 @@ pub struct ClassDecl {
     pub methods: Vec<FunDecl>,
 }
 
+// 실패 메세지에 쓰려고 조건식의 소스 텍스트를 가짐
+#[derive(Debug, Clone)]
+pub struct Assertion {
+    pub cond: Expr,
+    pub message: Option<Expr>,
+    pub source: String,
+    pub line: usize,
+    pub col: i64,
+}
+
+// except 절. 클래스식이 없으면 모든 예외를 잡음
 #[derive(Debug, Clone)]
-pub enum Stmt {
+pub struct ExceptHandler {
+    pub class: Option<Expr>,
+    pub name: Option<Symbol>,
+    pub body: Stmt,
+}
+
+#[derive(Debug, Clone)]
+pub struct Stmt {
+    pub kind: StmtKind,
+    pub span: Span,
+}
+
+impl Stmt {
+    pub fn new(kind: StmtKind, span: Span) -> Stmt {
+        Stmt { kind, span }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum StmtKind {
     Expr(Expr),
     FunDecl(FunDecl),
     ClassDecl(ClassDecl),
@@ -75,14 +201,26 @@ pub enum Stmt {
     Print(Expr),
     VarDecl(Symbol, Option<Expr>),
     Block(Vec<Stmt>),
-    Return(SourceLocation, Option<Expr>),
-    While(Expr, Box<Stmt>),
+    Return(Option<Expr>),
+    // 세번째는 C 스타일 for의 증감식. continue 뒤에도 실행됨
+    While(Expr, Box<Stmt>, Option<Expr>),
+    Break,
+    Continue,
+    ForIn(Pattern, Expr, Box<Stmt>),
+    // del xs[i]. 대상은 항상 Subscript
+    Del(Expr),
+    // try 본문, except 절들, else 절, finally 절
+    Try(Box<Stmt>, Vec<ExceptHandler>, Option<Box<Stmt>>, Option<Box<Stmt>>),
+    // 식이 없으면 처리 중인 예외를 다시 던짐
+    Raise(SourceLocation, Option<Expr>),
+    Assert(Assertion),
 }
 
 #[derive(Debug, Copy, Clone)]
 pub enum UnaryOpTy {
     Minus,
     Bang,
+    Tilde,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -104,6 +242,18 @@ pub enum BinaryOpTy {
     Minus,
     Star,
     Slash,
+    SlashSlash,
+    Percent,
+    StarStar,
+    Amp,
+    Pipe,
+    Caret,
+    LessLess,
+    GreaterGreater,
+    In,
+    NotIn,
+    Is,
+    IsNot,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -116,22 +266,10 @@ pub struct BinaryOp {
 #[derive(Debug, Clone)]
 pub enum Literal {
     Number(f64),
+    Int(i64),
+    BigInt(BigInt),
     String(String),
     True,
     False,
     Nil,
 }
-
-
-pub trait List {
-    fn append(&self) {}
-    fn extend(&self) {}
-    fn insert(&self) {}
-    fn remove(&self) {}
-    fn pop(&self) {}
-    fn clear(&self) {}
-    fn index(&self) {}
-    fn count(&self) {}
-    fn reverse(&self) {}
-    fn copy(&self) {}
-}
\ No newline at end of file
@@ -1,5 +1,10 @@
+use crate::lexer;
+
 #[derive(Copy, Clone, Default)]
 pub struct Extensions {
     pub lists: bool,
     pub lambdas: bool,
+    pub dialect: lexer::Dialect,
+    // python -O처럼 assert 문을 파싱만 하고 버림
+    pub no_asserts: bool,
 }
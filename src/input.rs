@@ -1,5 +1,4 @@
 pub enum Source {
-    Literal,
     File(String),
 }
 
@@ -7,3 +6,11 @@ pub struct Input {
     pub source: Source,
     pub content: String,
 }
+
+impl Input {
+    pub fn name(&self) -> &str {
+        match &self.source {
+            Source::File(path) => path,
+        }
+    }
+}
@@ -4,23 +4,254 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::bigint::BigInt;
+use crate::dict;
 use crate::expr;
+use crate::list;
+use crate::set;
+use crate::string;
 
 use std::fmt;
 use std::fmt::Write;
 
 static INIT: &str = "init";
 
+// 받을 수 있는 위치 인자 개수의 범위
+#[derive(Debug, Clone, Copy)]
+pub struct Arity {
+    pub min: u8,
+    pub max: u8,
+}
+
+impl Arity {
+    pub const fn exactly(n: u8) -> Arity {
+        Arity { min: n, max: n }
+    }
+
+    pub const fn range(min: u8, max: u8) -> Arity {
+        Arity { min, max }
+    }
+
+    fn accepts(&self, n: usize) -> bool {
+        (usize::from(self.min)..=usize::from(self.max)).contains(&n)
+    }
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.min == self.max {
+            write!(f, "{}", self.min)
+        } else {
+            write!(f, "{} to {}", self.min, self.max)
+        }
+    }
+}
+
+// 호출할 수 있는 값이 받는 인자의 모양
+pub enum Signature {
+    // 네이티브 함수와 메소드는 위치 인자 개수와 허용된 키워드 이름만 검사함
+    Native {
+        arity: Arity,
+        keywords: &'static [&'static str],
+    },
+    // Lox 함수의 매개변수와, 정의할 때 평가해 둔 기본값
+    Params {
+        params: Vec<expr::Param>,
+        defaults: Vec<Option<Value>>,
+    },
+}
+
+// 시그니처에 맞춰 정리된 인자. Lox 함수면 매개변수 순서대로 값이 하나씩 있음
+pub struct BoundArgs {
+    pub args: Vec<Value>,
+    pub kwargs: Vec<(String, Value)>,
+}
+
+impl Signature {
+    fn bind(
+        &self,
+        interpreter: &mut Interpreter,
+        name: &str,
+        args: Vec<Value>,
+        kwargs: Vec<(String, Value)>,
+    ) -> Result<BoundArgs, RuntimeError> {
+        for (i, (keyword, _)) in kwargs.iter().enumerate() {
+            if kwargs[..i].iter().any(|(prev, _)| prev == keyword) {
+                return Err(RuntimeError::new(
+                    ErrorKind::TypeError,
+                    format!(
+                        "{}() got multiple values for keyword argument '{}'",
+                        name, keyword
+                    ),
+                ));
+            }
+        }
+        match self {
+            Signature::Native { arity, keywords } => {
+                if let Some((keyword, _)) = kwargs
+                    .iter()
+                    .find(|(keyword, _)| !keywords.contains(&keyword.as_str()))
+                {
+                    return Err(RuntimeError::new(
+                        ErrorKind::TypeError,
+                        format!(
+                            "{}() got an unexpected keyword argument '{}'",
+                            name, keyword
+                        ),
+                    ));
+                }
+                if !arity.accepts(args.len()) {
+                    return Err(too_many_positional(name, *arity, args.len()));
+                }
+                Ok(BoundArgs { args, kwargs })
+            }
+            Signature::Params { params, defaults } => {
+                bind_params(interpreter, name, params, defaults, args, kwargs)
+            }
+        }
+    }
+}
+
+fn too_many_positional(name: &str, arity: Arity, given: usize) -> RuntimeError {
+    RuntimeError::new(
+        ErrorKind::TypeError,
+        format!(
+            "{}() takes {} positional argument{} but {} {} given",
+            name,
+            arity,
+            if arity.max == 1 { "" } else { "s" },
+            given,
+            if given == 1 { "was" } else { "were" }
+        ),
+    )
+}
+
+// 'a', 'a' and 'b', 'a', 'b' and 'c'
+fn quote_names(names: &[&str]) -> String {
+    let quoted: Vec<_> = names.iter().map(|name| format!("'{}'", name)).collect();
+    match quoted.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        _ => quoted.join(""),
+    }
+}
+
+// 파이썬과 같은 순서로 묶음: 위치 인자, *args, 키워드 인자, **kwargs, 기본값
+fn bind_params(
+    interpreter: &mut Interpreter,
+    name: &str,
+    params: &[expr::Param],
+    defaults: &[Option<Value>],
+    args: Vec<Value>,
+    kwargs: Vec<(String, Value)>,
+) -> Result<BoundArgs, RuntimeError> {
+    let mut bound: Vec<Option<Value>> = vec![None; params.len()];
+    let find = |kind: expr::ParamKind| params.iter().position(|param| param.kind == kind);
+
+    let normal: Vec<usize> = (0..params.len())
+        .filter(|i| params[*i].kind == expr::ParamKind::Normal)
+        .collect();
+    let given = args.len();
+    let mut args = args.into_iter();
+    for (i, arg) in normal.iter().zip(args.by_ref()) {
+        bound[*i] = Some(arg);
+    }
+    let extra: Vec<Value> = args.collect();
+    match find(expr::ParamKind::VarArgs) {
+        Some(i) => bound[i] = Some(Value::Tuple(extra)),
+        None if !extra.is_empty() => {
+            let required = normal.iter().filter(|i| defaults[**i].is_none()).count();
+            let arity = Arity::range(
+                required.try_into().unwrap(),
+                normal.len().try_into().unwrap(),
+            );
+            return Err(too_many_positional(name, arity, given));
+        }
+        None => {}
+    }
+
+    let mut extra_kwargs = dict::Dict::default();
+    for (keyword, value) in kwargs {
+        let param = params.iter().position(|param| {
+            param.name.name == keyword
+                && matches!(
+                    param.kind,
+                    expr::ParamKind::Normal | expr::ParamKind::KeywordOnly
+                )
+        });
+        match param {
+            Some(i) if bound[i].is_some() => {
+                return Err(RuntimeError::new(
+                    ErrorKind::TypeError,
+                    format!("{}() got multiple values for argument '{}'", name, keyword),
+                ))
+            }
+            Some(i) => bound[i] = Some(value),
+            None if find(expr::ParamKind::VarKeywords).is_some() => {
+                extra_kwargs.insert(Value::String(keyword), value)?
+            }
+            None => {
+                return Err(RuntimeError::new(
+                    ErrorKind::TypeError,
+                    format!(
+                        "{}() got an unexpected keyword argument '{}'",
+                        name, keyword
+                    ),
+                ))
+            }
+        }
+    }
+    if let Some(i) = find(expr::ParamKind::VarKeywords) {
+        bound[i] = Some(interpreter.create_dict(extra_kwargs));
+    }
+
+    for (slot, default) in bound.iter_mut().zip(defaults) {
+        if slot.is_none() {
+            *slot = default.clone();
+        }
+    }
+    for (kind, what) in [
+        (expr::ParamKind::Normal, "positional"),
+        (expr::ParamKind::KeywordOnly, "keyword-only"),
+    ] {
+        let missing: Vec<&str> = params
+            .iter()
+            .zip(&bound)
+            .filter(|(param, slot)| param.kind == kind && slot.is_none())
+            .map(|(param, _)| param.name.name.as_str())
+            .collect();
+        if !missing.is_empty() {
+            return Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!(
+                    "{}() missing {} required {} argument{}: {}",
+                    name,
+                    missing.len(),
+                    what,
+                    if missing.len() == 1 { "" } else { "s" },
+                    quote_names(&missing)
+                ),
+            ));
+        }
+    }
+
+    Ok(BoundArgs {
+        args: bound.into_iter().flatten().collect(),
+        kwargs: Vec::new(),
+    })
+}
+
 trait Callable {
-    fn arity(&self, interpreter: &Interpreter) -> u8;
-    fn call(&self, interpreter: &mut Interpreter, args: &[Value]) -> Result<Value, String>;
+    fn name(&self) -> &str;
+    fn signature(&self, interpreter: &Interpreter) -> Signature;
+    // 인자는 signature()에 맞춰 이미 묶인 상태로 들어옴
+    fn call(&self, interpreter: &mut Interpreter, args: BoundArgs) -> Result<Value, RuntimeError>;
 }
 
 #[derive(Clone)]
 pub struct NativeFunction {
     pub name: String,
-    pub arity: u8,
-    pub callable: fn(&mut Interpreter, &[Value]) -> Result<Value, String>,
+    pub arity: Arity,
+    pub callable: fn(&mut Interpreter, &[Value]) -> Result<Value, RuntimeError>,
 }
 
 impl fmt::Debug for NativeFunction {
@@ -30,11 +261,59 @@ impl fmt::Debug for NativeFunction {
 }
 
 impl Callable for NativeFunction {
-    fn arity(&self, _interpreter: &Interpreter) -> u8 {
-        self.arity
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn signature(&self, _interpreter: &Interpreter) -> Signature {
+        Signature::Native {
+            arity: self.arity,
+            keywords: &[],
+        }
+    }
+    fn call(&self, interpreter: &mut Interpreter, args: BoundArgs) -> Result<Value, RuntimeError> {
+        (self.callable)(interpreter, &args.args)
+    }
+}
+
+pub type NativeMethodFn =
+    fn(&mut Interpreter, &Value, &[Value], &[(String, Value)]) -> Result<Value, RuntimeError>;
+
+// 내장 타입 메소드 테이블의 항목. keywords에 있는 이름만 키워드 인자로 받음
+pub struct MethodDef {
+    pub name: &'static str,
+    pub arity: Arity,
+    pub keywords: &'static [&'static str],
+    pub callable: NativeMethodFn,
+}
+
+// 내장 타입(list, string 등)의 메소드. 속성으로 꺼낼 때 receiver에 묶임
+#[derive(Clone)]
+pub struct NativeMethod {
+    pub name: &'static str,
+    pub arity: Arity,
+    pub keywords: &'static [&'static str],
+    pub receiver: Box<Value>,
+    pub callable: NativeMethodFn,
+}
+
+impl fmt::Debug for NativeMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "NativeMethod({})", self.name)
+    }
+}
+
+impl Callable for NativeMethod {
+    fn name(&self) -> &str {
+        self.name
+    }
+    fn signature(&self, _interpreter: &Interpreter) -> Signature {
+        Signature::Native {
+            arity: self.arity,
+            keywords: self.keywords,
+        }
     }
-    fn call(&self, interpreter: &mut Interpreter, args: &[Value]) -> Result<Value, String> {
-        (self.callable)(interpreter, args)
+    fn call(&self, interpreter: &mut Interpreter, args: BoundArgs) -> Result<Value, RuntimeError> {
+        (self.callable)(interpreter, &self.receiver, &args.args, &args.kwargs)
     }
 }
 
@@ -42,7 +321,8 @@ impl Callable for NativeFunction {
 pub struct LoxFunction {
     pub id: u64,
     pub name: expr::Symbol,
-    pub parameters: Vec<expr::Symbol>,
+    pub params: Vec<expr::Param>,
+    pub defaults: Vec<Option<Value>>,
     pub body: Vec<expr::Stmt>,
     pub closure: Environment,
     pub this_binding: Option<Box<Value>>,
@@ -51,22 +331,28 @@ pub struct LoxFunction {
 }
 
 impl Callable for LoxFunction {
-    fn arity(&self, _interpreter: &Interpreter) -> u8 {
-        self.parameters.len().try_into().unwrap()
+    fn name(&self) -> &str {
+        &self.name.name
     }
-    fn call(&self, interpreter: &mut Interpreter, args: &[Value]) -> Result<Value, String> {
+    fn signature(&self, _interpreter: &Interpreter) -> Signature {
+        Signature::Params {
+            params: self.params.clone(),
+            defaults: self.defaults.clone(),
+        }
+    }
+    fn call(&self, interpreter: &mut Interpreter, args: BoundArgs) -> Result<Value, RuntimeError> {
         let args_env: HashMap<_, _> = self
-            .parameters
+            .params
             .iter()
-            .zip(args.iter())
+            .zip(args.args)
             .map(|(param, arg)| {
                 (
-                    param.name.clone(),
+                    param.name.name.clone(),
                     (
-                        Some(arg.clone()),
+                        Some(arg),
                         SourceLocation {
-                            line: param.line,
-                            col: param.col,
+                            line: param.name.line,
+                            col: param.name.col,
                         },
                     ),
                 )
@@ -113,21 +399,31 @@ impl Callable for LoxFunction {
         interpreter.env = env;
         interpreter.enclosing_function = Some(self.id);
         interpreter.backtrace.push((0, self.name.name.clone()));
-        interpreter.interpret(&self.body)?;
+        let mut res = interpreter.execute_stmts(&self.body);
+        // 에러로 빠져나와도 호출한 쪽의 상태를 되돌림. except로 잡을 수 있으므로
+        if let Err(err) = &mut res {
+            if err.traceback.is_none() {
+                err.traceback = Some(interpreter.backtrace.clone());
+            }
+        }
 
         let retval = interpreter.retval.clone();
         interpreter.backtrace.pop();
         interpreter.enclosing_function = saved_enclosing_function;
         interpreter.env = saved_env;
         interpreter.retval = saved_retval;
+        res?;
 
         match retval {
             Some(val) => {
                 let val_type = type_of(&val);
                 if self.is_initializer && val_type != Type::Nil {
-                    Err(format!(
-                        "TypeError: init should only return nil (perhaps implicitly), not {:?}",
-                        val_type
+                    Err(RuntimeError::new(
+                        ErrorKind::TypeError,
+                        format!(
+                            "init should only return nil (perhaps implicitly), not {:?}",
+                            val_type
+                        ),
                     ))
                 } else {
                     Ok(val)
@@ -158,18 +454,37 @@ pub struct LoxClass {
 }
 
 impl Callable for LoxClass {
-    fn arity(&self, interpreter: &Interpreter) -> u8 {
+    fn name(&self) -> &str {
+        &self.name.name
+    }
+    // 클래스를 호출하면 init의 시그니처를 따름
+    fn signature(&self, interpreter: &Interpreter) -> Signature {
         match self.init(interpreter) {
-            Some(initializer) => initializer.parameters.len().try_into().unwrap(),
-            None => 0,
+            Some(initializer) => initializer.signature(interpreter),
+            // init이 없는 예외 클래스는 메세지 하나를 받을 수 있음
+            None if interpreter.is_exception_class(self.id) => Signature::Native {
+                arity: Arity::range(0, 1),
+                keywords: &[],
+            },
+            None => Signature::Params {
+                params: Vec::new(),
+                defaults: Vec::new(),
+            },
         }
     }
-    fn call(&self, interpreter: &mut Interpreter, args: &[Value]) -> Result<Value, String> {
+    fn call(&self, interpreter: &mut Interpreter, args: BoundArgs) -> Result<Value, RuntimeError> {
         let instance = interpreter.create_instance(&self.name, self.id);
 
         if let Some(mut initializer) = self.init(interpreter) {
             initializer.this_binding = Some(Box::new(instance.clone()));
             initializer.call(interpreter, args)?;
+        } else if interpreter.is_exception_class(self.id) {
+            let message = args
+                .args
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| Value::String(String::new()));
+            interpreter.store_attr(&instance, "message", message)?;
         }
 
         Ok(instance)
@@ -209,7 +524,7 @@ pub struct LoxInstance {
 }
 
 impl LoxInstance {
-    fn getattr(&self, attr: &str, interpreter: &Interpreter) -> Result<Value, String> {
+    fn getattr(&self, attr: &str, interpreter: &Interpreter) -> Result<Value, RuntimeError> {
         match self.fields.get(attr) {
             Some(val) => Ok(val.clone()),
             None => {
@@ -224,9 +539,12 @@ impl LoxInstance {
                         ))),
                     ));
                 }
-                Err(format!(
-                    "AttributeError: '{}' instance has no '{}' attribute.",
-                    self.class_name.name, attr
+                Err(RuntimeError::new(
+                    ErrorKind::AttributeError,
+                    format!(
+                        "'{}' instance has no '{}' attribute.",
+                        self.class_name.name, attr
+                    ),
                 ))
             }
         }
@@ -236,10 +554,14 @@ impl LoxInstance {
 #[derive(Debug, Clone)]
 pub enum Value {
     Number(f64),
+    Int(i64),
+    // i64를 넘는 정수. i64에 들어가는 값은 항상 Int로 둠
+    BigInt(BigInt),
     String(String),
     Bool(bool),
     Nil,
     NativeFunction(NativeFunction),
+    NativeMethod(NativeMethod),
     LoxFunction(
         expr::Symbol,
         /*id*/ u64,
@@ -248,11 +570,391 @@ pub enum Value {
     LoxClass(expr::Symbol, /*id*/ u64),
     LoxInstance(expr::Symbol, /*id*/ u64),
     List(/*id*/ u64),
+    Dict(/*id*/ u64),
+    Set(/*id*/ u64),
+    // 튜플은 바뀌지 않으므로 힙에 두지 않고 값으로 가짐
+    Tuple(Vec<Value>),
+    Range(/*start*/ i64, /*stop*/ i64, /*step*/ i64),
+}
+
+// for 루프 등에서 쓰는 반복 상태
+pub(crate) enum Iteration {
+    // 리스트는 매번 다시 읽으므로 반복 중 변경이 보임
+    List(/*id*/ u64, /*next index*/ usize),
+    // 딕셔너리는 키를 삽입 순서대로 돌려줌
+    Dict(/*id*/ u64, /*next index*/ usize),
+    Set(/*id*/ u64, /*next index*/ usize),
+    Chars(std::vec::IntoIter<char>),
+    Tuple(std::vec::IntoIter<Value>),
+    Range(/*next*/ i64, /*stop*/ i64, /*step*/ i64),
+    // __next__가 nil을 돌려주면 끝
+    Instance(Value),
+}
+
+// range의 원소 개수. i64 양끝에 걸친 range는 개수가 Int에 들어가지 않으므로 i128로 셈
+fn range_len(start: i64, stop: i64, step: i64) -> Result<i64, RuntimeError> {
+    let (start, stop, step) = (start as i128, stop as i128, step as i128);
+    let len = if step > 0 && start < stop {
+        (stop - start - 1) / step + 1
+    } else if step < 0 && start > stop {
+        (start - stop - 1) / -step + 1
+    } else {
+        0
+    };
+    i64::try_from(len).map_err(|_| {
+        RuntimeError::new(ErrorKind::OverflowError, "range has too many elements for len()")
+    })
+}
+
+// n이 range의 원소인지. 하나씩 훑지 않고 바로 계산함
+fn range_contains(start: i64, stop: i64, step: i64, n: i64) -> bool {
+    let in_bounds = if step > 0 {
+        start <= n && n < stop
+    } else {
+        stop < n && n <= start
+    };
+    in_bounds && (n as i128 - start as i128) % step as i128 == 0
+}
+
+// 평가된 subscript. 슬라이스의 생략된 부분은 None
+enum SubscriptKey {
+    Index(Value),
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+}
+
+// 파이썬 slice.indices()와 같음. 음수는 뒤에서부터 세고 범위 밖은 양끝으로 맞춤
+fn slice_bounds(
+    len: usize,
+    start: Option<i64>,
+    stop: Option<i64>,
+    step: Option<i64>,
+) -> Result<(i64, i64, i64), RuntimeError> {
+    let len = len as i64;
+    let step = step.unwrap_or(1);
+    if step == 0 {
+        return Err(RuntimeError::new(
+            ErrorKind::ValueError,
+            "slice step cannot be zero",
+        ));
+    }
+    // 역방향이면 -1이 "맨 앞보다 앞"을 뜻함
+    let (lower, upper) = if step > 0 { (0, len) } else { (-1, len - 1) };
+    let adjust = |index: i64| {
+        let index = if index < 0 { index + len } else { index };
+        index.clamp(lower, upper)
+    };
+    let start = start.map_or(if step > 0 { lower } else { upper }, adjust);
+    let stop = stop.map_or(if step > 0 { upper } else { lower }, adjust);
+    Ok((start, stop, step))
+}
+
+fn slice_positions(start: i64, stop: i64, step: i64) -> Vec<usize> {
+    let mut positions = Vec::new();
+    let mut pos = start;
+    while (step > 0 && pos < stop) || (step < 0 && pos > stop) {
+        positions.push(pos as usize);
+        // xs[1::2**63 - 1]처럼 step이 크면 다음 위치가 i64를 넘어감
+        match pos.checked_add(step) {
+            Some(next) => pos = next,
+            None => break,
+        }
+    }
+    positions
+}
+
+fn is_numeric_op(op: expr::BinaryOpTy) -> bool {
+    matches!(
+        op,
+        expr::BinaryOpTy::Less
+            | expr::BinaryOpTy::LessEqual
+            | expr::BinaryOpTy::Greater
+            | expr::BinaryOpTy::GreaterEqual
+            | expr::BinaryOpTy::Plus
+            | expr::BinaryOpTy::Minus
+            | expr::BinaryOpTy::Star
+            | expr::BinaryOpTy::Slash
+            | expr::BinaryOpTy::SlashSlash
+            | expr::BinaryOpTy::Percent
+            | expr::BinaryOpTy::StarStar
+    ) || is_bitwise_op(op)
+}
+
+fn is_bitwise_op(op: expr::BinaryOpTy) -> bool {
+    matches!(
+        op,
+        expr::BinaryOpTy::Amp
+            | expr::BinaryOpTy::Pipe
+            | expr::BinaryOpTy::Caret
+            | expr::BinaryOpTy::LessLess
+            | expr::BinaryOpTy::GreaterGreater
+    )
+}
+
+pub(crate) fn as_float(val: &Value) -> f64 {
+    match val {
+        Value::Int(n) => *n as f64,
+        Value::BigInt(n) => n.to_f64(),
+        Value::Number(n) => *n,
+        _ => panic!("Internal interpreter error! {:?} is not a number.", val),
+    }
+}
+
+// 정수와 실수를 반올림하지 않고 비교함. 실수가 nan이면 None
+fn compare_int_float(n: &BigInt, f: f64) -> Option<std::cmp::Ordering> {
+    if f.is_nan() {
+        return None;
+    }
+    if f.is_infinite() {
+        return Some(if f > 0.0 {
+            std::cmp::Ordering::Less
+        } else {
+            std::cmp::Ordering::Greater
+        });
+    }
+    let floor = f.floor();
+    match n.cmp(&BigInt::from_f64(floor)) {
+        // 정수 부분이 같으면 소수 부분이 있는 쪽이 큼
+        std::cmp::Ordering::Equal if floor != f => Some(std::cmp::Ordering::Less),
+        ord => Some(ord),
+    }
+}
+
+// 두 숫자 값의 정확한 대소 비교. 정수가 크면 실수로 바꿀 때 반올림되므로 따로 비교함
+pub(crate) fn compare_numbers(lhs: &Value, rhs: &Value) -> Option<std::cmp::Ordering> {
+    match (lhs, rhs) {
+        (Value::Int(n1), Value::Int(n2)) => Some(n1.cmp(n2)),
+        (Value::Number(n1), Value::Number(n2)) => n1.partial_cmp(n2),
+        (Value::Number(n), _) => compare_int_float(&as_bigint(rhs)?, *n).map(|ord| ord.reverse()),
+        (_, Value::Number(n)) => compare_int_float(&as_bigint(lhs)?, *n),
+        _ => Some(as_bigint(lhs)?.cmp(&as_bigint(rhs)?)),
+    }
+}
+
+pub(crate) fn as_bigint(val: &Value) -> Option<BigInt> {
+    match val {
+        Value::Int(n) => Some(BigInt::from_i64(*n)),
+        Value::BigInt(n) => Some(n.clone()),
+        _ => None,
+    }
+}
+
+// 정수 값이면서 i64에 들어가는 실수를 Int로. -2^63은 들어가고 2^63은 안 들어감
+pub(crate) fn float_to_int(n: f64) -> Option<i64> {
+    if n.fract() == 0.0 && (-(2f64.powi(63))..2f64.powi(63)).contains(&n) {
+        Some(n as i64)
+    } else {
+        None
+    }
+}
+
+// 계산 결과가 i64에 들어가면 Int로
+fn int_value(n: BigInt) -> Value {
+    match n.to_i64() {
+        Some(n) => Value::Int(n),
+        None => Value::BigInt(n),
+    }
+}
+
+fn int_binary_op(n1: i64, op: expr::BinaryOp, n2: i64) -> Result<Value, RuntimeError> {
+    // i64를 넘으면 BigInt로 다시 계산함
+    let checked = |res: Option<i64>| match res {
+        Some(n) => Ok(Value::Int(n)),
+        None => big_binary_op(&BigInt::from_i64(n1), op, &BigInt::from_i64(n2)),
+    };
+    match op.toktype {
+        expr::BinaryOpTy::Less => Ok(Value::Bool(n1 < n2)),
+        expr::BinaryOpTy::LessEqual => Ok(Value::Bool(n1 <= n2)),
+        expr::BinaryOpTy::Greater => Ok(Value::Bool(n1 > n2)),
+        expr::BinaryOpTy::GreaterEqual => Ok(Value::Bool(n1 >= n2)),
+        expr::BinaryOpTy::Plus => checked(n1.checked_add(n2)),
+        expr::BinaryOpTy::Minus => checked(n1.checked_sub(n2)),
+        expr::BinaryOpTy::Star => checked(n1.checked_mul(n2)),
+        // /는 항상 실수 나눗셈
+        expr::BinaryOpTy::Slash => float_binary_op(n1 as f64, op, n2 as f64),
+        expr::BinaryOpTy::SlashSlash | expr::BinaryOpTy::Percent if n2 == 0 => {
+            float_binary_op(n1 as f64, op, n2 as f64)
+        }
+        // 파이썬처럼 몫은 내림하고 나머지의 부호는 나누는 수를 따름
+        expr::BinaryOpTy::SlashSlash => checked(n1.checked_div(n2).map(|quot| {
+            if quot * n2 != n1 && (n1 < 0) != (n2 < 0) {
+                quot - 1
+            } else {
+                quot
+            }
+        })),
+        expr::BinaryOpTy::Percent => {
+            let rem = n1.wrapping_rem(n2);
+            if rem != 0 && (rem < 0) != (n2 < 0) {
+                Ok(Value::Int(rem + n2))
+            } else {
+                Ok(Value::Int(rem))
+            }
+        }
+        // 음수 지수는 실수가 됨
+        expr::BinaryOpTy::StarStar if n2 < 0 => float_binary_op(n1 as f64, op, n2 as f64),
+        expr::BinaryOpTy::StarStar => {
+            checked(u32::try_from(n2).ok().and_then(|exp| n1.checked_pow(exp)))
+        }
+        expr::BinaryOpTy::Amp => Ok(Value::Int(n1 & n2)),
+        expr::BinaryOpTy::Pipe => Ok(Value::Int(n1 | n2)),
+        expr::BinaryOpTy::Caret => Ok(Value::Int(n1 ^ n2)),
+        expr::BinaryOpTy::LessLess | expr::BinaryOpTy::GreaterGreater if n2 < 0 => Err(
+            RuntimeError::new(ErrorKind::ValueError, "negative shift count").at(op.line, op.col),
+        ),
+        // 왼쪽 시프트는 곱셈과 같음
+        expr::BinaryOpTy::LessLess => checked((n2 < 63).then(|| n1.checked_mul(1 << n2)).flatten()),
+        // 산술 시프트라서 음수는 -1로 수렴함
+        expr::BinaryOpTy::GreaterGreater => Ok(Value::Int(n1 >> n2.min(63))),
+        _ => panic!("Internal interpreter error! {:?} is not a numeric operator.", op.toktype),
+    }
+}
+
+fn big_binary_op(n1: &BigInt, op: expr::BinaryOp, n2: &BigInt) -> Result<Value, RuntimeError> {
+    match op.toktype {
+        expr::BinaryOpTy::Less => Ok(Value::Bool(n1 < n2)),
+        expr::BinaryOpTy::LessEqual => Ok(Value::Bool(n1 <= n2)),
+        expr::BinaryOpTy::Greater => Ok(Value::Bool(n1 > n2)),
+        expr::BinaryOpTy::GreaterEqual => Ok(Value::Bool(n1 >= n2)),
+        expr::BinaryOpTy::Plus => Ok(int_value(n1.add(n2))),
+        expr::BinaryOpTy::Minus => Ok(int_value(n1.sub(n2))),
+        expr::BinaryOpTy::Star => Ok(int_value(n1.mul(n2))),
+        expr::BinaryOpTy::Slash => match n1.div_f64(n2) {
+            Some(quot) if quot.is_infinite() => Err(RuntimeError::new(
+                ErrorKind::OverflowError,
+                "integer division result too large for a float",
+            )
+            .at(op.line, op.col)),
+            Some(quot) => Ok(Value::Number(quot)),
+            None => float_binary_op(n1.to_f64(), op, 0.0),
+        },
+        expr::BinaryOpTy::SlashSlash | expr::BinaryOpTy::Percent => match n1.div_mod_floor(n2) {
+            Some((quot, rem)) => Ok(int_value(match op.toktype {
+                expr::BinaryOpTy::SlashSlash => quot,
+                _ => rem,
+            })),
+            // 0으로 나누면 실수와 같은 에러
+            None => float_binary_op(n1.to_f64(), op, 0.0),
+        },
+        expr::BinaryOpTy::StarStar if n2.is_negative() => {
+            float_binary_op(n1.to_f64(), op, n2.to_f64())
+        }
+        expr::BinaryOpTy::StarStar => match n2.to_i64().and_then(|exp| u32::try_from(exp).ok()) {
+            Some(exp) => Ok(int_value(n1.pow(exp))),
+            // 0, 1, -1은 지수가 아무리 커도 결과를 바로 앎
+            None if matches!(n1.to_i64(), Some(0 | 1)) => Ok(int_value(n1.clone())),
+            None if n1.to_i64() == Some(-1) => Ok(Value::Int(if n2.is_odd() { -1 } else { 1 })),
+            None => Err(
+                RuntimeError::new(ErrorKind::OverflowError, "exponent too large")
+                    .at(op.line, op.col),
+            ),
+        },
+        expr::BinaryOpTy::Amp => Ok(int_value(n1.bitwise(n2, |x, y| x & y))),
+        expr::BinaryOpTy::Pipe => Ok(int_value(n1.bitwise(n2, |x, y| x | y))),
+        expr::BinaryOpTy::Caret => Ok(int_value(n1.bitwise(n2, |x, y| x ^ y))),
+        expr::BinaryOpTy::LessLess | expr::BinaryOpTy::GreaterGreater if n2.is_negative() => Err(
+            RuntimeError::new(ErrorKind::ValueError, "negative shift count").at(op.line, op.col),
+        ),
+        expr::BinaryOpTy::LessLess => match n2.to_i64().and_then(|bits| u32::try_from(bits).ok()) {
+            Some(bits) => Ok(int_value(n1.shl(bits as usize))),
+            None => Err(
+                RuntimeError::new(ErrorKind::OverflowError, "shift count too large")
+                    .at(op.line, op.col),
+            ),
+        },
+        expr::BinaryOpTy::GreaterGreater => {
+            let bits = n2.to_i64().map_or(usize::MAX, |bits| bits as usize);
+            Ok(int_value(n1.shr(bits)))
+        }
+        _ => panic!("Internal interpreter error! {:?} is not a numeric operator.", op.toktype),
+    }
+}
+
+fn float_binary_op(n1: f64, op: expr::BinaryOp, n2: f64) -> Result<Value, RuntimeError> {
+    match op.toktype {
+        expr::BinaryOpTy::Less => Ok(Value::Bool(n1 < n2)),
+        expr::BinaryOpTy::LessEqual => Ok(Value::Bool(n1 <= n2)),
+        expr::BinaryOpTy::Greater => Ok(Value::Bool(n1 > n2)),
+        expr::BinaryOpTy::GreaterEqual => Ok(Value::Bool(n1 >= n2)),
+        expr::BinaryOpTy::Plus => Ok(Value::Number(n1 + n2)),
+        expr::BinaryOpTy::Minus => Ok(Value::Number(n1 - n2)),
+        expr::BinaryOpTy::Star => Ok(Value::Number(n1 * n2)),
+        expr::BinaryOpTy::Slash => {
+            if n2 != 0.0 {
+                Ok(Value::Number(n1 / n2))
+            } else {
+                Err(
+                    RuntimeError::new(ErrorKind::ZeroDivisionError, "division by zero")
+                        .at(op.line, op.col),
+                )
+            }
+        }
+        expr::BinaryOpTy::SlashSlash => {
+            if n2 != 0.0 {
+                Ok(Value::Number((n1 / n2).floor()))
+            } else {
+                Err(
+                    RuntimeError::new(ErrorKind::ZeroDivisionError, "division by zero")
+                        .at(op.line, op.col),
+                )
+            }
+        }
+        expr::BinaryOpTy::Percent => {
+            if n2 != 0.0 {
+                // 파이썬처럼 나머지의 부호는 나누는 수를 따름: -7 % 3 == 2
+                let rem = n1 % n2;
+                if rem != 0.0 && (rem < 0.0) != (n2 < 0.0) {
+                    Ok(Value::Number(rem + n2))
+                } else {
+                    Ok(Value::Number(rem))
+                }
+            } else {
+                Err(
+                    RuntimeError::new(ErrorKind::ZeroDivisionError, "modulo by zero")
+                        .at(op.line, op.col),
+                )
+            }
+        }
+        expr::BinaryOpTy::StarStar => {
+            if n1 == 0.0 && n2 < 0.0 {
+                Err(RuntimeError::new(
+                    ErrorKind::ZeroDivisionError,
+                    "zero cannot be raised to a negative power",
+                )
+                .at(op.line, op.col))
+            } else {
+                Ok(Value::Number(n1.powf(n2)))
+            }
+        }
+        _ => panic!("Internal interpreter error! {:?} is not a float operator.", op.toktype),
+    }
+}
+
+// 파이썬처럼 정수로 딱 떨어지는 실수도 1.0으로 씀
+fn format_float(n: f64) -> String {
+    if n.is_nan() {
+        "nan".to_string()
+    } else if n.is_infinite() {
+        if n > 0.0 { "inf" } else { "-inf" }.to_string()
+    } else {
+        // 지수 표기는 1e+20, 1e-05 꼴로 맞춤
+        let repr = format!("{:?}", n);
+        match repr.split_once('e') {
+            Some((mantissa, exp)) => {
+                let (sign, digits) = match exp.strip_prefix('-') {
+                    Some(digits) => ('-', digits),
+                    None => ('+', exp),
+                };
+                format!("{}e{}{:0>2}", mantissa, sign, digits)
+            }
+            None => repr,
+        }
+    }
 }
 
 fn as_callable(interpreter: &Interpreter, value: &Value) -> Option<Box<dyn Callable>> {
     match value {
         Value::NativeFunction(f) => Some(Box::new(f.clone())),
+        Value::NativeMethod(m) => Some(Box::new(m.clone())),
         Value::LoxFunction(_, id, this_binding) => {
             let f = interpreter.get_lox_function(*id);
             let mut f_copy = f.clone();
@@ -267,27 +969,39 @@ fn as_callable(interpreter: &Interpreter, value: &Value) -> Option<Box<dyn Calla
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum Type {
     Number,
+    Int,
     String,
     Bool,
     Nil,
     NativeFunction,
+    NativeMethod,
     LoxFunction,
     LoxClass,
     LoxInstance,
     List,
+    Dict,
+    Set,
+    Tuple,
+    Range,
 }
 
 pub fn type_of(val: &Value) -> Type {
     match val {
         Value::Number(_) => Type::Number,
+        Value::Int(_) | Value::BigInt(_) => Type::Int,
         Value::String(_) => Type::String,
         Value::Bool(_) => Type::Bool,
         Value::Nil => Type::Nil,
         Value::NativeFunction(_) => Type::NativeFunction,
+        Value::NativeMethod(_) => Type::NativeMethod,
         Value::LoxFunction(_, _, _) => Type::LoxFunction,
         Value::LoxClass(_, _) => Type::LoxClass,
         Value::LoxInstance(_, _) => Type::LoxInstance,
         Value::List(_) => Type::List,
+        Value::Dict(_) => Type::Dict,
+        Value::Set(_) => Type::Set,
+        Value::Tuple(_) => Type::Tuple,
+        Value::Range(..) => Type::Range,
     }
 }
 
@@ -318,6 +1032,14 @@ impl Environment {
         }
     }
 
+    // 전역 환경부터 센 중첩 깊이
+    fn depth(&self) -> usize {
+        match &self.enclosing {
+            Some(enclosing) => enclosing.depth() + 1,
+            None => 0,
+        }
+    }
+
     pub fn define(&mut self, sym: expr::Symbol, maybe_val: Option<Value>) {
         self.venv.insert(
             sym.name,
@@ -331,7 +1053,7 @@ impl Environment {
         );
     }
 
-    pub fn lookup(&self, sym: &expr::Symbol) -> LookupResult {
+    pub fn lookup(&self, sym: &expr::Symbol) -> LookupResult<'_> {
         match self.venv.get(&sym.name) {
             Some((maybe_val, defn_source_location)) => match maybe_val {
                 Some(val) => LookupResult::Ok(val),
@@ -344,26 +1066,38 @@ impl Environment {
         }
     }
 
-    pub fn get(&self, sym: &expr::Symbol) -> Result<&Value, String> {
+    // 이 환경이나 바깥 환경 어디에든 선언되어 있는지
+    pub fn is_declared(&self, name: &str) -> bool {
+        self.venv.contains_key(name)
+            || self
+                .enclosing
+                .as_ref()
+                .is_some_and(|enclosing| enclosing.is_declared(name))
+    }
+
+    pub fn get(&self, sym: &expr::Symbol) -> Result<&Value, RuntimeError> {
         match self.lookup(sym) {
             LookupResult::Ok(val) => Ok(val),
-            LookupResult::UndefButDeclared(source_location) => Err(format!(
-                "Use of undefined variable '{}' at line={},col={}.\
-                \nNote: {} was previously declared at line={},col={}, \
-                but was never defined.",
-                &sym.name, sym.line, sym.col, &sym.name, source_location.line, source_location.col
-            )),
+            LookupResult::UndefButDeclared(source_location) => Err(RuntimeError::new(
+                ErrorKind::NameError,
+                format!(
+                    "Use of undefined variable '{}', declared at line={},col={} but never defined",
+                    &sym.name, source_location.line, source_location.col
+                ),
+            )
+            .at(sym.line, sym.col)),
             LookupResult::UndefAndNotDeclared => match &self.enclosing {
                 Some(enclosing) => enclosing.get(sym),
-                None => Err(format!(
-                    "Use of undefined variable {} at line={},col={}.\nNote: {} was never declared.",
-                    &sym.name, sym.line, sym.col, &sym.name
-                )),
+                None => Err(RuntimeError::new(
+                    ErrorKind::NameError,
+                    format!("Use of undefined variable '{}'", &sym.name),
+                )
+                .at(sym.line, sym.col)),
             },
         }
     }
 
-    pub fn assign(&mut self, sym: expr::Symbol, val: &Value) -> Result<(), String> {
+    pub fn assign(&mut self, sym: expr::Symbol, val: &Value) -> Result<(), RuntimeError> {
         if self.venv.contains_key(&sym.name) {
             self.define(sym, Some(val.clone()));
             return Ok(());
@@ -371,14 +1105,182 @@ impl Environment {
 
         match &mut self.enclosing {
             Some(enclosing) => enclosing.assign(sym, val),
-            None => Err(format!(
-                "attempting to assign to undeclared variable at line={},col={}, {}",
-                sym.line, sym.col, sym.name
-            )),
+            None => Err(RuntimeError::new(
+                ErrorKind::NameError,
+                format!("attempting to assign to undeclared variable '{}'", sym.name),
+            )
+            .at(sym.line, sym.col)),
+        }
+    }
+}
+
+// 실행 중 에러. 에러가 난 시점의 호출 스택을 함께 가짐
+#[derive(Debug)]
+pub struct Error {
+    pub message: String,
+    pub backtrace: Vec<(u64, String)>,
+}
+
+impl Error {
+    pub fn format_backtrace(&self) -> String {
+        let lines: Vec<_> = self
+            .backtrace
+            .iter()
+            .map(|(line, funname)| match line {
+                0 => format!("[line ??] in {}", funname),
+                line => format!("[line {}] in {}", line, funname),
+            })
+            .collect();
+        format!("Backtrace (most recent call last):\n\n{}", lines.join("\n"))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+// 내장 예외 클래스. 런타임 에러는 모두 이 중 하나의 인스턴스로 잡힘
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    BaseException,
+    Exception,
+    ArithmeticError,
+    ZeroDivisionError,
+    OverflowError,
+    LookupError,
+    IndexError,
+    KeyError,
+    AttributeError,
+    NameError,
+    TypeError,
+    ValueError,
+    RuntimeError,
+    AssertionError,
+}
+
+impl ErrorKind {
+    // 부모 클래스보다 뒤에 옴
+    const ALL: [ErrorKind; 14] = [
+        ErrorKind::BaseException,
+        ErrorKind::Exception,
+        ErrorKind::ArithmeticError,
+        ErrorKind::ZeroDivisionError,
+        ErrorKind::OverflowError,
+        ErrorKind::LookupError,
+        ErrorKind::IndexError,
+        ErrorKind::KeyError,
+        ErrorKind::AttributeError,
+        ErrorKind::NameError,
+        ErrorKind::TypeError,
+        ErrorKind::ValueError,
+        ErrorKind::RuntimeError,
+        ErrorKind::AssertionError,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorKind::BaseException => "BaseException",
+            ErrorKind::Exception => "Exception",
+            ErrorKind::ArithmeticError => "ArithmeticError",
+            ErrorKind::ZeroDivisionError => "ZeroDivisionError",
+            ErrorKind::OverflowError => "OverflowError",
+            ErrorKind::LookupError => "LookupError",
+            ErrorKind::IndexError => "IndexError",
+            ErrorKind::KeyError => "KeyError",
+            ErrorKind::AttributeError => "AttributeError",
+            ErrorKind::NameError => "NameError",
+            ErrorKind::TypeError => "TypeError",
+            ErrorKind::ValueError => "ValueError",
+            ErrorKind::RuntimeError => "RuntimeError",
+            ErrorKind::AssertionError => "AssertionError",
+        }
+    }
+
+    fn base(self) -> Option<ErrorKind> {
+        match self {
+            ErrorKind::BaseException => None,
+            ErrorKind::Exception => Some(ErrorKind::BaseException),
+            ErrorKind::ZeroDivisionError | ErrorKind::OverflowError => {
+                Some(ErrorKind::ArithmeticError)
+            }
+            ErrorKind::IndexError | ErrorKind::KeyError => Some(ErrorKind::LookupError),
+            _ => Some(ErrorKind::Exception),
+        }
+    }
+}
+
+// Err로 전파되는 실행 중 에러. except에서 잡히면 예외 인스턴스가 됨
+#[derive(Debug, Clone)]
+pub struct RuntimeError {
+    // exception이 있으면 그 클래스가 대신 쓰임
+    pub kind: ErrorKind,
+    pub message: String,
+    // KeyError의 키처럼 예외의 message 필드에 문자열 대신 넣을 값
+    pub value: Option<Box<Value>>,
+    pub location: Option<expr::SourceLocation>,
+    // raise로 던졌거나 한번 잡혔던 예외 인스턴스
+    pub exception: Option<Box<Value>>,
+    // 에러가 처음 함수를 빠져나갈 때의 호출 스택. 함수를 빠져나오면 프레임이 사라지기 때문
+    pub traceback: Option<Vec<(u64, String)>>,
+}
+
+impl RuntimeError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> RuntimeError {
+        RuntimeError {
+            kind,
+            message: message.into(),
+            value: None,
+            location: None,
+            exception: None,
+            traceback: None,
+        }
+    }
+
+    // 위치가 이미 있으면 더 안쪽의 위치이므로 그대로 둠
+    pub fn at(mut self, line: usize, col: i64) -> RuntimeError {
+        self.location.get_or_insert(expr::SourceLocation { line, col });
+        self
+    }
+
+    pub fn with_value(mut self, value: Value) -> RuntimeError {
+        self.value = Some(Box::new(value));
+        self
+    }
+
+    fn class_name(&self) -> &str {
+        match self.exception.as_deref() {
+            Some(Value::LoxInstance(class_name, _)) => &class_name.name,
+            _ => self.kind.name(),
+        }
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.class_name())?;
+        if !self.message.is_empty() {
+            write!(f, ": {}", self.message)?;
+        }
+        match &self.location {
+            Some(loc) => write!(f, " at line={},col={}", loc.line, loc.col),
+            None => Ok(()),
         }
     }
 }
 
+impl std::error::Error for RuntimeError {}
+
+// 실행 중인 break/continue. retval처럼 루프까지 전달됨
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopControl {
+    Break,
+    Continue,
+}
+
 pub struct Interpreter {
     pub counter: u64,
     pub lambda_counter: u64,
@@ -386,13 +1288,20 @@ pub struct Interpreter {
     pub lox_instances: HashMap<u64, LoxInstance>,
     pub lox_classes: HashMap<u64, LoxClass>,
     pub lists: HashMap<u64, Vec<Value>>,
+    pub dicts: HashMap<u64, dict::Dict>,
+    pub sets: HashMap<u64, set::Set>,
     pub env: Environment,
     pub globals: Environment,
     pub retval: Option<Value>,
+    pub loop_control: Option<LoopControl>,
     pub output: Vec<String>,
     pub enclosing_function: Option<u64>,
     pub interrupted: Arc<AtomicBool>,
+    // 함수 이름과 그 프레임에서 호출 중인 줄. 줄을 모르면 0
     pub backtrace: Vec<(u64, String)>,
+    // except 절에서 처리 중인 예외들. 식 없는 raise가 다시 던짐
+    pub handling: Vec<RuntimeError>,
+    pub exception_classes: HashMap<ErrorKind, u64>,
 }
 
 impl Default for Interpreter {
@@ -403,7 +1312,7 @@ impl Default for Interpreter {
             (
                 Some(Value::NativeFunction(NativeFunction {
                     name: String::from("clock"),
-                    arity: 0,
+                    arity: Arity::exactly(0),
                     callable: |_, _| {
                         let start = SystemTime::now();
                         let since_the_epoch = start.duration_since(UNIX_EPOCH).unwrap();
@@ -422,14 +1331,29 @@ impl Default for Interpreter {
             (
                 Some(Value::NativeFunction(NativeFunction {
                     name: String::from("len"),
-                    arity: 1,
+                    arity: Arity::exactly(1),
                     callable: |interp, values| match &values[0] {
-                        Value::String(s) => Ok(Value::Number(s.len() as f64)),
+                        Value::String(s) => Ok(Value::Int(s.chars().count() as i64)),
                         Value::List(list_id) => {
                             let elts = interp.get_list_elts(*list_id);
-                            Ok(Value::Number(elts.len() as f64))
+                            Ok(Value::Int(elts.len() as i64))
+                        }
+                        Value::Tuple(elts) => Ok(Value::Int(elts.len() as i64)),
+                        Value::Dict(dict_id) => {
+                            let entries = interp.get_dict(*dict_id).entries();
+                            Ok(Value::Int(entries.len() as i64))
+                        }
+                        Value::Set(set_id) => {
+                            let items = interp.get_set(*set_id).items();
+                            Ok(Value::Int(items.count() as i64))
                         }
-                        val => Err(format!("Object of type {:?} has no len.", type_of(val))),
+                        Value::Range(start, stop, step) => {
+                            range_len(*start, *stop, *step).map(Value::Int)
+                        }
+                        val => Err(RuntimeError::new(
+                            ErrorKind::TypeError,
+                            format!("Object of type {:?} has no len.", type_of(val)),
+                        )),
                     },
                 })),
                 SourceLocation {
@@ -443,21 +1367,25 @@ impl Default for Interpreter {
             (
                 Some(Value::NativeFunction(NativeFunction {
                     name: String::from("iota"),
-                    arity: 2,
+                    arity: Arity::exactly(2),
                     callable: |interpreter, values| match (&values[0], &values[1]) {
-                        (Value::Number(low), Value::Number(high)) => {
-                            let elts: Vec<_> = (*low as i64..*high as i64)
-                                .map(|x| Value::Number(x as f64))
-                                .collect();
+                        (Value::Int(low), Value::Int(high)) => {
+                            let elts: Vec<_> = (*low..*high).map(Value::Int).collect();
                             Ok(interpreter.create_list(elts))
                         }
-                        (Value::Number(_), high) => Err(format!(
-                            "invalid high argument of type {:?} in iota expression.",
-                            type_of(high)
+                        (Value::Int(_), high) => Err(RuntimeError::new(
+                            ErrorKind::TypeError,
+                            format!(
+                                "invalid high argument of type {:?} in iota expression.",
+                                type_of(high)
+                            ),
                         )),
-                        (low, _) => Err(format!(
-                            "invalid low argument of type {:?} in iota expression.",
-                            type_of(low)
+                        (low, _) => Err(RuntimeError::new(
+                            ErrorKind::TypeError,
+                            format!(
+                                "invalid low argument of type {:?} in iota expression.",
+                                type_of(low)
+                            ),
                         )),
                     },
                 })),
@@ -473,27 +1401,29 @@ impl Default for Interpreter {
             (
                 Some(Value::NativeFunction(NativeFunction {
                     name: String::from("forEach"),
-                    arity: 2,
+                    arity: Arity::exactly(2),
                     callable: |interpreter, values| match &values[0] {
                         Value::List(list_id) => {
                             let elts = interpreter.get_list_elts(*list_id).clone();
-                            let maybe_callable = as_callable(interpreter, &values[1]);
-                            match maybe_callable {
-                                Some(callable) => {
+                            match as_callable(interpreter, &values[1]) {
+                                Some(_) => {
                                     for elt in elts {
-                                        callable.call(interpreter, &[elt])?;
+                                        interpreter.call_value(&values[1], &[elt])?;
                                     }
                                     Ok(Value::Nil)
                                 }
-                                None => Err(format!(
-                                    "The second argument to for_each must be callable. Found {:?}.",
-                                    type_of(&values[1])
+                                None => Err(RuntimeError::new(
+                                    ErrorKind::TypeError,
+                                    format!(
+                                        "The second argument to for_each must be callable. Found {:?}.",
+                                        type_of(&values[1])
+                                    ),
                                 )),
                             }
                         }
-                        val => Err(format!(
-                            "Can't call forEach on value of type {:?}.",
-                            type_of(val)
+                        val => Err(RuntimeError::new(
+                            ErrorKind::TypeError,
+                            format!("Can't call forEach on value of type {:?}.", type_of(val)),
                         )),
                     },
                 })),
@@ -508,28 +1438,30 @@ impl Default for Interpreter {
             (
                 Some(Value::NativeFunction(NativeFunction {
                     name: String::from("map"),
-                    arity: 2,
+                    arity: Arity::exactly(2),
                     callable: |interpreter, values| match &values[1] {
                         Value::List(list_id) => {
-                            let maybe_callable = as_callable(interpreter, &values[0]);
-                            match maybe_callable {
-                                Some(callable) => {
+                            match as_callable(interpreter, &values[0]) {
+                                Some(_) => {
                                     let mut res_elts = Vec::new();
                                     let elts = interpreter.get_list_elts(*list_id).clone();
                                     for elt in elts {
-                                        res_elts.push(callable.call(interpreter, &[elt.clone()])?);
+                                        res_elts.push(interpreter.call_value(&values[0], &[elt])?);
                                     }
                                     Ok(interpreter.create_list(res_elts))
                                 }
-                                None => Err(format!(
-                                    "The second argument to for_each must be callable. Found {:?}.",
-                                    type_of(&values[1])
+                                None => Err(RuntimeError::new(
+                                    ErrorKind::TypeError,
+                                    format!(
+                                        "The second argument to for_each must be callable. Found {:?}.",
+                                        type_of(&values[1])
+                                    ),
                                 )),
                             }
                         }
-                        val => Err(format!(
-                            "Can't call forEach on value of type {:?}.",
-                            type_of(val)
+                        val => Err(RuntimeError::new(
+                            ErrorKind::TypeError,
+                            format!("Can't call forEach on value of type {:?}.", type_of(val)),
                         )),
                     },
                 })),
@@ -540,32 +1472,470 @@ impl Default for Interpreter {
             ),
         );
 
-        let globals = Environment {
-            enclosing: None,
-            venv: globals_venv,
-        };
-
-        Interpreter {
-            counter: 0,
-            lambda_counter: 0,
-            lox_functions: Default::default(),
-            lox_instances: Default::default(),
-            lox_classes: Default::default(),
-            lists: Default::default(),
-            env: Default::default(),
-            globals,
-            retval: None,
+        globals_venv.insert(
+            String::from("range"),
+            (
+                Some(Value::NativeFunction(NativeFunction {
+                    name: String::from("range"),
+                    arity: Arity::range(1, 3),
+                    callable: |interpreter, values| {
+                        let mut bounds = [0, 0, 1];
+                        for (i, val) in values.iter().enumerate() {
+                            match val {
+                                Value::Int(n) => bounds[i] = *n,
+                                _ => {
+                                    return Err(RuntimeError::new(
+                                        ErrorKind::TypeError,
+                                        format!(
+                                            "range() arguments must be integers. Found {}.",
+                                            interpreter.format_val(val)
+                                        ),
+                                    ))
+                                }
+                            }
+                        }
+                        // range(stop)
+                        if values.len() == 1 {
+                            bounds.swap(0, 1);
+                        }
+                        if bounds[2] == 0 {
+                            return Err(RuntimeError::new(
+                                ErrorKind::ValueError,
+                                "range() arg 3 must not be zero",
+                            ));
+                        }
+                        Ok(Value::Range(bounds[0], bounds[1], bounds[2]))
+                    },
+                })),
+                SourceLocation {
+                    line: 1337,
+                    col: 1337,
+                },
+            ),
+        );
+
+        // enumerate, zip은 튜플의 리스트를 바로 만들어 돌려줌
+        globals_venv.insert(
+            String::from("enumerate"),
+            (
+                Some(Value::NativeFunction(NativeFunction {
+                    name: String::from("enumerate"),
+                    arity: Arity::range(1, 2),
+                    callable: |interpreter, values| {
+                        let start = match values.get(1) {
+                            None => 0,
+                            Some(Value::Int(n)) => *n,
+                            Some(val) => {
+                                return Err(RuntimeError::new(
+                                    ErrorKind::TypeError,
+                                    format!(
+                                        "enumerate() start must be an integer. Found {}.",
+                                        interpreter.format_val(val)
+                                    ),
+                                ))
+                            }
+                        };
+                        let elts = interpreter
+                            .collect_iterable(&values[0])?
+                            .into_iter()
+                            .zip(start..)
+                            .map(|(elt, i)| Value::Tuple(vec![Value::Int(i), elt]))
+                            .collect();
+                        Ok(interpreter.create_list(elts))
+                    },
+                })),
+                SourceLocation {
+                    line: 1337,
+                    col: 1337,
+                },
+            ),
+        );
+
+        globals_venv.insert(
+            String::from("zip"),
+            (
+                Some(Value::NativeFunction(NativeFunction {
+                    name: String::from("zip"),
+                    arity: Arity::range(0, u8::MAX),
+                    callable: |interpreter, values| {
+                        let mut columns = Vec::new();
+                        for val in values {
+                            columns.push(interpreter.collect_iterable(val)?);
+                        }
+                        // 가장 짧은 것에 맞춤
+                        let len = columns.iter().map(|column| column.len()).min().unwrap_or(0);
+                        let elts = (0..len)
+                            .map(|i| Value::Tuple(columns.iter().map(|column| column[i].clone()).collect()))
+                            .collect();
+                        Ok(interpreter.create_list(elts))
+                    },
+                })),
+                SourceLocation {
+                    line: 1337,
+                    col: 1337,
+                },
+            ),
+        );
+
+        globals_venv.insert(
+            String::from("set"),
+            (
+                Some(Value::NativeFunction(NativeFunction {
+                    name: String::from("set"),
+                    arity: Arity::range(0, 1),
+                    callable: |interpreter, values| {
+                        let mut set = set::Set::default();
+                        if let Some(iterable) = values.first() {
+                            for item in interpreter.collect_iterable(iterable)? {
+                                set.insert(item)?;
+                            }
+                        }
+                        Ok(interpreter.create_set(set))
+                    },
+                })),
+                SourceLocation {
+                    line: 1337,
+                    col: 1337,
+                },
+            ),
+        );
+
+        globals_venv.insert(
+            String::from("int"),
+            (
+                Some(Value::NativeFunction(NativeFunction {
+                    name: String::from("int"),
+                    arity: Arity::exactly(1),
+                    callable: |_, values| match &values[0] {
+                        Value::Int(n) => Ok(Value::Int(*n)),
+                        Value::Bool(b) => Ok(Value::Int(*b as i64)),
+                        Value::BigInt(n) => Ok(Value::BigInt(n.clone())),
+                        // 0 쪽으로 버림
+                        Value::Number(n) if n.is_finite() => Ok(int_value(BigInt::from_f64(*n))),
+                        Value::Number(n) => Err(RuntimeError::new(
+                            ErrorKind::ValueError,
+                            format!("cannot convert float {} to integer", format_float(*n)),
+                        )),
+                        Value::String(s) => match BigInt::parse(s.trim()) {
+                            Some(n) => Ok(int_value(n)),
+                            None => Err(RuntimeError::new(
+                                ErrorKind::ValueError,
+                                format!("invalid literal for int() with base 10: '{}'", s),
+                            )),
+                        },
+                        val => Err(RuntimeError::new(
+                            ErrorKind::TypeError,
+                            format!(
+                                "int() argument must be a string or a number, not {:?}",
+                                type_of(val)
+                            ),
+                        )),
+                    },
+                })),
+                SourceLocation {
+                    line: 1337,
+                    col: 1337,
+                },
+            ),
+        );
+        globals_venv.insert(
+            String::from("float"),
+            (
+                Some(Value::NativeFunction(NativeFunction {
+                    name: String::from("float"),
+                    arity: Arity::exactly(1),
+                    callable: |_, values| match &values[0] {
+                        Value::Int(n) => Ok(Value::Number(*n as f64)),
+                        Value::BigInt(n) => Ok(Value::Number(n.to_f64())),
+                        Value::Number(n) => Ok(Value::Number(*n)),
+                        Value::Bool(b) => Ok(Value::Number(*b as i64 as f64)),
+                        Value::String(s) => s.trim().parse().map(Value::Number).map_err(|_| {
+                            RuntimeError::new(
+                                ErrorKind::ValueError,
+                                format!("could not convert string to float: '{}'", s),
+                            )
+                        }),
+                        val => Err(RuntimeError::new(
+                            ErrorKind::TypeError,
+                            format!(
+                                "float() argument must be a string or a number, not {:?}",
+                                type_of(val)
+                            ),
+                        )),
+                    },
+                })),
+                SourceLocation {
+                    line: 1337,
+                    col: 1337,
+                },
+            ),
+        );
+
+        let globals = Environment {
+            enclosing: None,
+            venv: globals_venv,
+        };
+
+        let mut interpreter = Interpreter {
+            counter: 0,
+            lambda_counter: 0,
+            lox_functions: Default::default(),
+            lox_instances: Default::default(),
+            lox_classes: Default::default(),
+            lists: Default::default(),
+            dicts: Default::default(),
+            sets: Default::default(),
+            env: Default::default(),
+            globals,
+            retval: None,
+            loop_control: None,
             output: Default::default(),
             enclosing_function: None,
             interrupted: Arc::new(AtomicBool::new(false)),
             backtrace: vec![(0, "script".to_string())],
-        }
+            handling: Vec::new(),
+            exception_classes: HashMap::new(),
+        };
+        interpreter.define_exception_classes();
+        interpreter
     }
 }
 
 impl Interpreter {
-    pub fn interpret(&mut self, stmts: &[expr::Stmt]) -> Result<(), String> {
+    pub fn interpret(&mut self, stmts: &[expr::Stmt]) -> Result<(), Error> {
         self.interrupted.store(false, Ordering::Release);
+        self.execute_stmts(stmts).map_err(|err| Error {
+            message: err.to_string(),
+            backtrace: err.traceback.unwrap_or_else(|| self.backtrace.clone()),
+        })
+    }
+
+    fn define_exception_classes(&mut self) {
+        for kind in ErrorKind::ALL {
+            let class_id = self.alloc_id();
+            let sym = expr::Symbol {
+                name: kind.name().to_string(),
+                line: 0,
+                col: -1,
+            };
+            let cls = LoxClass {
+                name: sym.clone(),
+                superclass: kind.base().map(|base| self.exception_classes[&base]),
+                id: class_id,
+                methods: HashMap::new(),
+            };
+            self.lox_classes.insert(class_id, cls);
+            self.exception_classes.insert(kind, class_id);
+            self.globals
+                .define(sym.clone(), Some(Value::LoxClass(sym, class_id)));
+        }
+    }
+
+    fn is_subclass(&self, class_id: u64, base_id: u64) -> bool {
+        let mut maybe_class_id = Some(class_id);
+        while let Some(class_id) = maybe_class_id {
+            if class_id == base_id {
+                return true;
+            }
+            maybe_class_id = self.get_lox_class(class_id).superclass;
+        }
+        false
+    }
+
+    fn is_exception_class(&self, class_id: u64) -> bool {
+        self.is_subclass(class_id, self.exception_classes[&ErrorKind::BaseException])
+    }
+
+    // 현재 프레임에서 실행 중인 줄. 백트레이스에 나옴
+    fn set_current_line(&mut self, line: usize) {
+        if let Some(frame) = self.backtrace.last_mut() {
+            frame.0 = line as u64;
+        }
+    }
+
+    // 잡힌 에러. 런타임 에러면 여기서 예외 인스턴스를 만듦
+    fn catch(&mut self, mut err: RuntimeError) -> RuntimeError {
+        let traceback = err
+            .traceback
+            .get_or_insert_with(|| self.backtrace.clone())
+            .clone();
+        if err.exception.is_none() {
+            let class_id = self.exception_classes[&err.kind];
+            let class_sym = self.get_lox_class(class_id).name.clone();
+            let exception = self.create_instance(&class_sym, class_id);
+            let message = match err.value.take() {
+                Some(value) => *value,
+                None => Value::String(err.message.clone()),
+            };
+            self.store_attr(&exception, "message", message).unwrap();
+            err.exception = Some(Box::new(exception));
+        }
+        let frames = traceback
+            .iter()
+            .map(|(line, name)| {
+                let line = match line {
+                    0 => Value::Nil,
+                    line => Value::Int(*line as i64),
+                };
+                Value::Tuple(vec![line, Value::String(name.clone())])
+            })
+            .collect();
+        let traceback = self.create_list(frames);
+        let exception = *err.exception.clone().unwrap();
+        self.store_attr(&exception, "traceback", traceback).unwrap();
+        err
+    }
+
+    // raise의 대상. 클래스면 인자 없이 인스턴스를 만듦
+    fn exception_instance(
+        &mut self,
+        val: Value,
+        loc: &expr::SourceLocation,
+    ) -> Result<Value, RuntimeError> {
+        let val = match val {
+            Value::LoxClass(_, class_id) if self.is_exception_class(class_id) => {
+                self.call_value(&val, &[])?
+            }
+            val => val,
+        };
+        match &val {
+            Value::LoxInstance(_, id) if self.is_exception_class(self.get_lox_instance(*id).class_id) => {
+                Ok(val)
+            }
+            _ => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                "exceptions must derive from BaseException",
+            )
+            .at(loc.line, loc.col)),
+        }
+    }
+
+    // 예외의 message 필드. 문자열이 아니면 repr로 보여줌
+    fn exception_message(&self, exception: &Value) -> String {
+        let inst = match exception {
+            Value::LoxInstance(_, id) => self.get_lox_instance(*id),
+            _ => panic!("Internal interpreter error! exception is not an instance."),
+        };
+        match inst.fields.get("message") {
+            None | Some(Value::Nil) => String::new(),
+            Some(Value::String(message)) => message.clone(),
+            Some(message) => self.format_val(message),
+        }
+    }
+
+    // except 절의 클래스식은 예외 클래스나 예외 클래스의 튜플이어야 함
+    fn handler_matches(
+        &mut self,
+        handler: &expr::ExceptHandler,
+        exception: &Value,
+    ) -> Result<bool, RuntimeError> {
+        let class_expr = match &handler.class {
+            Some(class_expr) => class_expr,
+            None => return Ok(true),
+        };
+        let exception_class_id = match exception {
+            Value::LoxInstance(_, id) => self.get_lox_instance(*id).class_id,
+            _ => panic!("Internal interpreter error! exception is not an instance."),
+        };
+        let classes = match self.interpret_expr(class_expr)? {
+            Value::Tuple(elts) => elts,
+            val => vec![val],
+        };
+        let mut matched = false;
+        for cls in classes {
+            match cls {
+                Value::LoxClass(_, class_id) if self.is_exception_class(class_id) => {
+                    matched |= self.is_subclass(exception_class_id, class_id);
+                }
+                _ => {
+                    return Err(RuntimeError::new(
+                        ErrorKind::TypeError,
+                        format!(
+                            "catching classes that do not inherit from BaseException is not allowed, found {:?}",
+                            type_of(&cls)
+                        ),
+                    ))
+                }
+            }
+        }
+        Ok(matched)
+    }
+
+    // 에러로 빠져나온 블록들의 환경을 버림
+    fn unwind_env(&mut self, depth: usize) {
+        while self.env.depth() > depth {
+            match self.env.enclosing.take() {
+                Some(enclosing) => self.env = *enclosing,
+                None => break,
+            }
+        }
+    }
+
+    fn try_except(
+        &mut self,
+        body: &expr::Stmt,
+        handlers: &[expr::ExceptHandler],
+        maybe_else: &Option<Box<expr::Stmt>>,
+        depth: usize,
+    ) -> Result<(), RuntimeError> {
+        let err = match self.execute(body) {
+            // return이나 break로 빠져나왔으면 execute가 else 절을 건너뜀
+            Ok(()) => {
+                return match maybe_else {
+                    Some(else_branch) => self.execute(else_branch),
+                    None => Ok(()),
+                }
+            }
+            Err(err) => err,
+        };
+        if handlers.is_empty() {
+            return Err(err);
+        }
+
+        self.unwind_env(depth);
+        let err = self.catch(err);
+        let exception = *err.exception.clone().unwrap();
+        for handler in handlers {
+            if !self.handler_matches(handler, &exception)? {
+                continue;
+            }
+            if let Some(name) = &handler.name {
+                self.env.define(name.clone(), Some(exception));
+            }
+            self.handling.push(err);
+            let res = self.execute(&handler.body);
+            self.handling.pop();
+            return res;
+        }
+
+        // 맞는 except 절이 없으면 같은 예외가 계속 전파됨
+        Err(err)
+    }
+
+    // finally 절은 항상 실행함. 그 안의 에러나 return, break가 원래 결과를 대신함
+    fn run_finally(
+        &mut self,
+        finally: &expr::Stmt,
+        res: Result<(), RuntimeError>,
+        depth: usize,
+    ) -> Result<(), RuntimeError> {
+        if res.is_err() {
+            self.unwind_env(depth);
+        }
+        let saved_retval = self.retval.take();
+        let saved_loop_control = self.loop_control.take();
+
+        self.execute(finally)?;
+        if self.retval.is_some() || self.loop_control.is_some() {
+            return Ok(());
+        }
+
+        self.retval = saved_retval;
+        self.loop_control = saved_loop_control;
+        res
+    }
+
+    fn execute_stmts(&mut self, stmts: &[expr::Stmt]) -> Result<(), RuntimeError> {
         for stmt in stmts {
             self.execute(stmt)?
         }
@@ -602,16 +1972,8 @@ impl Interpreter {
         }
     }
 
-    pub fn format_backtrace(&self) -> String {
-        let lines: Vec<_> = self
-            .backtrace
-            .iter()
-            .map(|(_, funname)| format!("[line ??] in {}", funname))
-            .collect();
-        format!("Backtrace (most recent call last):\n\n{}", lines.join("\n"))
-    }
 
-    fn get_list_elts(&self, list_id: u64) -> &Vec<Value> {
+    pub(crate) fn get_list_elts(&self, list_id: u64) -> &Vec<Value> {
         if let Some(elts) = self.lists.get(&list_id) {
             elts
         } else {
@@ -622,7 +1984,7 @@ impl Interpreter {
         }
     }
 
-    fn get_list_elts_mut(&mut self, list_id: u64) -> &mut Vec<Value> {
+    pub(crate) fn get_list_elts_mut(&mut self, list_id: u64) -> &mut Vec<Value> {
         if let Some(elts) = self.lists.get_mut(&list_id) {
             elts
         } else {
@@ -633,18 +1995,74 @@ impl Interpreter {
         }
     }
 
+    pub(crate) fn get_dict(&self, dict_id: u64) -> &dict::Dict {
+        if let Some(dict) = self.dicts.get(&dict_id) {
+            dict
+        } else {
+            panic!(
+                "Internal interpreter error! Couldn't find dict with id {}.",
+                dict_id
+            );
+        }
+    }
+
+    pub(crate) fn get_dict_mut(&mut self, dict_id: u64) -> &mut dict::Dict {
+        if let Some(dict) = self.dicts.get_mut(&dict_id) {
+            dict
+        } else {
+            panic!(
+                "Internal interpreter error! Couldn't find dict with id {}.",
+                dict_id
+            );
+        }
+    }
+
+    pub(crate) fn get_set(&self, set_id: u64) -> &set::Set {
+        if let Some(set) = self.sets.get(&set_id) {
+            set
+        } else {
+            panic!(
+                "Internal interpreter error! Couldn't find set with id {}.",
+                set_id
+            );
+        }
+    }
+
+    pub(crate) fn get_set_mut(&mut self, set_id: u64) -> &mut set::Set {
+        if let Some(set) = self.sets.get_mut(&set_id) {
+            set
+        } else {
+            panic!(
+                "Internal interpreter error! Couldn't find set with id {}.",
+                set_id
+            );
+        }
+    }
+
     fn alloc_id(&mut self) -> u64 {
         let res = self.counter;
         self.counter += 1;
         res
     }
 
-    fn create_list(&mut self, elts: Vec<Value>) -> Value {
+    pub(crate) fn create_list(&mut self, elts: Vec<Value>) -> Value {
         let list_id = self.alloc_id();
         self.lists.insert(list_id, elts);
         Value::List(list_id)
     }
 
+    fn create_dict(&mut self, dict: dict::Dict) -> Value {
+        let dict_id = self.alloc_id();
+        self.dicts.insert(dict_id, dict);
+        Value::Dict(dict_id)
+    }
+
+    fn create_set(&mut self, set: set::Set) -> Value {
+        let set_id = self.alloc_id();
+        self.sets.insert(set_id, set);
+        Value::Set(set_id)
+    }
+
     fn create_instance(&mut self, class_name: &expr::Symbol, class_id: u64) -> Value {
         let inst_id = self.alloc_id();
         let inst = LoxInstance {
@@ -657,17 +2075,17 @@ impl Interpreter {
         Value::LoxInstance(class_name.clone(), inst_id)
     }
 
-    fn execute(&mut self, stmt: &expr::Stmt) -> Result<(), String> {
-        if self.retval.is_some() {
+    fn execute(&mut self, stmt: &expr::Stmt) -> Result<(), RuntimeError> {
+        if self.retval.is_some() || self.loop_control.is_some() {
             return Ok(());
         }
 
-        match stmt {
-            expr::Stmt::Expr(e) => match self.interpret_expr(e) {
+        match &stmt.kind {
+            expr::StmtKind::Expr(e) => match self.interpret_expr(e) {
                 Ok(_) => Ok(()),
                 Err(err) => Err(err),
             },
-            expr::Stmt::ClassDecl(expr::ClassDecl {
+            expr::StmtKind::ClassDecl(expr::ClassDecl {
                                       name: sym,
                                       superclass: maybe_superclass,
                                       methods: stmt_methods,
@@ -678,20 +2096,26 @@ impl Interpreter {
 
                 let superclass_id = if let Some(superclass_var) = maybe_superclass {
                     if superclass_var.name == sym.name {
-                        return Err(format!(
-                            "A class cannot inerit from itself (line={}, col={})",
-                            sym.line, sym.col
-                        ));
+                        return Err(RuntimeError::new(
+                            ErrorKind::TypeError,
+                            "A class cannot inerit from itself",
+                        )
+                        .at(sym.line, sym.col));
                     }
 
-                    let superclass_val =
-                        self.interpret_expr(&expr::Expr::Variable(superclass_var.clone()))?;
+                    let superclass_val = self.interpret_expr(&expr::Expr::new(
+                        expr::ExprKind::Variable(superclass_var.clone()),
+                        stmt.span,
+                    ))?;
                     if let Value::LoxClass(_, id) = superclass_val {
                         Some(id)
                     } else {
-                        return Err(format!(
-                            "Only classes should appear as superclasses. Found {:?}.",
-                            type_of(&superclass_val)
+                        return Err(RuntimeError::new(
+                            ErrorKind::TypeError,
+                            format!(
+                                "Only classes should appear as superclasses. Found {:?}.",
+                                type_of(&superclass_val)
+                            ),
                         ));
                     }
                 } else {
@@ -709,7 +2133,8 @@ impl Interpreter {
                     let lox_function = LoxFunction {
                         id: func_id,
                         name: method.name.clone(),
-                        parameters: method.params.clone(),
+                        params: method.params.clone(),
+                        defaults: self.eval_defaults(&method.params)?,
                         body: method.body.clone(),
                         closure: self.env.clone(),
                         this_binding: None,
@@ -730,11 +2155,12 @@ impl Interpreter {
                 self.lox_classes.insert(class_id, cls);
                 Ok(())
             }
-            expr::Stmt::FunDecl(expr::FunDecl {
+            expr::StmtKind::FunDecl(expr::FunDecl {
                                     name,
-                                    params: parameters,
+                                    params,
                                     body,
                                 }) => {
+                let defaults = self.eval_defaults(params)?;
                 let func_id = self.alloc_id();
                 self.env.define(
                     name.clone(),
@@ -744,7 +2170,8 @@ impl Interpreter {
                 let lox_function = LoxFunction {
                     id: func_id,
                     name: name.clone(),
-                    parameters: parameters.clone(),
+                    params: params.clone(),
+                    defaults,
                     body: body.clone(),
                     closure: self.env.clone(),
                     this_binding: None,
@@ -756,7 +2183,7 @@ impl Interpreter {
 
                 Ok(())
             }
-            expr::Stmt::If(cond, if_true, maybe_if_false) => {
+            expr::StmtKind::If(cond, if_true, maybe_if_false) => {
                 if Interpreter::is_truthy(&self.interpret_expr(cond)?) {
                     return self.execute(if_true);
                 }
@@ -765,7 +2192,7 @@ impl Interpreter {
                 }
                 Ok(())
             }
-            expr::Stmt::Print(e) => match self.interpret_expr(e) {
+            expr::StmtKind::Print(e) => match self.interpret_expr(e) {
                 Ok(val) => {
                     println!("{}", self.format_val(&val));
                     self.output.push(self.format_val(&val));
@@ -773,7 +2200,7 @@ impl Interpreter {
                 }
                 Err(err) => Err(err),
             },
-            expr::Stmt::VarDecl(sym, maybe_expr) => {
+            expr::StmtKind::VarDecl(sym, maybe_expr) => {
                 let maybe_val = match maybe_expr {
                     Some(expr) => Some(self.interpret_expr(expr)?),
                     None => None,
@@ -781,7 +2208,7 @@ impl Interpreter {
                 self.env.define(sym.clone(), maybe_val);
                 Ok(())
             }
-            expr::Stmt::Block(stmts) => {
+            expr::StmtKind::Block(stmts) => {
                 self.env = Environment::with_enclosing(self.env.clone());
 
                 for stmt in stmts.iter() {
@@ -797,13 +2224,103 @@ impl Interpreter {
 
                 Ok(())
             }
-            expr::Stmt::While(cond, body) => {
+            expr::StmtKind::ForIn(target, iterable, body) => {
+                let iterable = self.interpret_expr(iterable)?;
+                let mut iteration = self.iterate(&iterable)?;
+                while let Some(val) = self.next_item(&mut iteration)? {
+                    self.bind_pattern(target, val, expr::Binding::Define)?;
+                    if !self.execute_loop_body(body)? {
+                        break;
+                    }
+                }
+                Ok(())
+            }
+            expr::StmtKind::While(cond, body, maybe_increment) => {
                 while Interpreter::is_truthy(&self.interpret_expr(cond)?) {
-                    self.execute(body)?;
+                    if !self.execute_loop_body(body)? {
+                        break;
+                    }
+                    if let Some(increment) = maybe_increment {
+                        self.interpret_expr(increment)?;
+                    }
                 }
                 Ok(())
             }
-            expr::Stmt::Return(_, maybe_res) => {
+            expr::StmtKind::Break => {
+                self.loop_control = Some(LoopControl::Break);
+                Ok(())
+            }
+            expr::StmtKind::Continue => {
+                self.loop_control = Some(LoopControl::Continue);
+                Ok(())
+            }
+            expr::StmtKind::Del(target) => match &target.kind {
+                expr::ExprKind::Subscript {
+                    value,
+                    slice,
+                    source_location,
+                } => {
+                    let value = self.interpret_expr(value)?;
+                    self.subscript_key(slice)
+                        .and_then(|key| self.delitem(&value, &key, source_location))
+                        .map_err(|err| err.at(source_location.line, source_location.col))
+                }
+                _ => panic!("Internal interpreter error! del target is not a subscript."),
+            },
+            expr::StmtKind::Try(body, handlers, maybe_else, maybe_finally) => {
+                let depth = self.env.depth();
+                let res = self.try_except(body, handlers, maybe_else, depth);
+                match maybe_finally {
+                    Some(finally) => self.run_finally(finally, res, depth),
+                    None => res,
+                }
+            }
+            expr::StmtKind::Raise(loc, maybe_exception) => {
+                self.set_current_line(loc.line);
+                match maybe_exception {
+                    Some(exception) => {
+                        let val = self.interpret_expr(exception)?;
+                        let exception = self.exception_instance(val, loc)?;
+                        let mut err = RuntimeError::new(
+                            ErrorKind::Exception,
+                            self.exception_message(&exception),
+                        )
+                        .at(loc.line, loc.col);
+                        err.exception = Some(Box::new(exception));
+                        Err(err)
+                    }
+                    None => match self.handling.last() {
+                        Some(err) => Err(err.clone()),
+                        None => Err(RuntimeError::new(
+                            ErrorKind::RuntimeError,
+                            "No active exception to reraise",
+                        )
+                        .at(loc.line, loc.col)),
+                    },
+                }
+            }
+            expr::StmtKind::Assert(assertion) => {
+                if Interpreter::is_truthy(&self.interpret_expr(&assertion.cond)?) {
+                    return Ok(());
+                }
+                self.set_current_line(assertion.line);
+                let failure = format!("assertion `{}` failed", assertion.source);
+                let (message, detail) = match &assertion.message {
+                    Some(message_expr) => {
+                        let message = match self.interpret_expr(message_expr)? {
+                            Value::String(message) => message,
+                            val => self.format_val(&val),
+                        };
+                        let detail = format!("{} ({})", message, failure);
+                        (message, detail)
+                    }
+                    None => (String::new(), failure),
+                };
+                Err(RuntimeError::new(ErrorKind::AssertionError, detail)
+                    .with_value(Value::String(message))
+                    .at(assertion.line, assertion.col))
+            }
+            expr::StmtKind::Return(maybe_res) => {
                 self.retval = Some(if let Some(res) = maybe_res {
                     self.interpret_expr(res)?
                 } else {
@@ -814,7 +2331,105 @@ impl Interpreter {
         }
     }
 
-    fn lookup(&self, sym: &expr::Symbol) -> Result<&Value, String> {
+    // 루프 본문을 한번 실행하고, 루프를 계속 돌지 돌려줌. break/continue는 여기서 소비됨
+    fn execute_loop_body(&mut self, body: &expr::Stmt) -> Result<bool, RuntimeError> {
+        self.execute(body)?;
+        if self.loop_control.take() == Some(LoopControl::Break) {
+            return Ok(false);
+        }
+        Ok(self.retval.is_none() && !self.interrupted.load(Ordering::Acquire))
+    }
+
+    // 반복 가능한 값: 리스트, 문자열, range, __iter__가 있는 인스턴스
+    pub(crate) fn iterate(&mut self, val: &Value) -> Result<Iteration, RuntimeError> {
+        match val {
+            Value::List(list_id) => Ok(Iteration::List(*list_id, 0)),
+            Value::Dict(dict_id) => Ok(Iteration::Dict(*dict_id, 0)),
+            Value::Set(set_id) => Ok(Iteration::Set(*set_id, 0)),
+            Value::Tuple(elts) => Ok(Iteration::Tuple(elts.clone().into_iter())),
+            Value::String(s) => Ok(Iteration::Chars(s.chars().collect::<Vec<_>>().into_iter())),
+            Value::Range(start, stop, step) => Ok(Iteration::Range(*start, *stop, *step)),
+            Value::LoxInstance(..) => {
+                let iter_method = self.get_attribute(val, "__iter__").map_err(|_| {
+                    RuntimeError::new(ErrorKind::TypeError, "LoxInstance value is not iterable")
+                })?;
+                let iterator = self.call_value(&iter_method, &[])?;
+                match iterator {
+                    Value::LoxInstance(..) => {
+                        if self.get_attribute(&iterator, "__next__").is_err() {
+                            return Err(RuntimeError::new(
+                                ErrorKind::TypeError,
+                                "__iter__ returned an instance without __next__",
+                            ));
+                        }
+                        Ok(Iteration::Instance(iterator))
+                    }
+                    _ => self.iterate(&iterator),
+                }
+            }
+            _ => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!("{:?} value is not iterable", type_of(val)),
+            )),
+        }
+    }
+
+    pub(crate) fn next_item(
+        &mut self,
+        iteration: &mut Iteration,
+    ) -> Result<Option<Value>, RuntimeError> {
+        match iteration {
+            Iteration::List(list_id, index) => {
+                let elt = self.get_list_elts(*list_id).get(*index).cloned();
+                *index += 1;
+                Ok(elt)
+            }
+            Iteration::Dict(dict_id, index) => {
+                let key = self
+                    .get_dict(*dict_id)
+                    .entries()
+                    .get(*index)
+                    .map(|(key, _)| key.clone());
+                *index += 1;
+                Ok(key)
+            }
+            Iteration::Set(set_id, index) => {
+                let item = self.get_set(*set_id).item_at(*index).cloned();
+                *index += 1;
+                Ok(item)
+            }
+            Iteration::Tuple(elts) => Ok(elts.next()),
+            Iteration::Chars(chars) => Ok(chars.next().map(|c| Value::String(c.to_string()))),
+            Iteration::Range(next, stop, step) => {
+                if (*step > 0 && *next < *stop) || (*step < 0 && *next > *stop) {
+                    let val = Value::Int(*next);
+                    // i64를 넘어가면 stop도 넘어간 것이므로 여기서 끝냄
+                    *next = next.checked_add(*step).unwrap_or(*stop);
+                    Ok(Some(val))
+                } else {
+                    Ok(None)
+                }
+            }
+            Iteration::Instance(iterator) => {
+                let next_method = self.get_attribute(iterator, "__next__")?;
+                match self.call_value(&next_method, &[])? {
+                    Value::Nil => Ok(None),
+                    val => Ok(Some(val)),
+                }
+            }
+        }
+    }
+
+    pub(crate) fn collect_iterable(&mut self, val: &Value) -> Result<Vec<Value>, RuntimeError> {
+        let mut iteration = self.iterate(val)?;
+        let mut elts = Vec::new();
+        while let Some(elt) = self.next_item(&mut iteration)? {
+            elts.push(elt);
+        }
+        Ok(elts)
+    }
+
+    fn lookup(&self, sym: &expr::Symbol) -> Result<&Value, RuntimeError> {
         match self.env.get(sym) {
             Ok(val) => Ok(val),
             Err(_) => self.globals.get(sym),
@@ -829,40 +2444,68 @@ impl Interpreter {
         }
     }
 
-    fn interpret_expr(&mut self, expr: &expr::Expr) -> Result<Value, String> {
+    fn interpret_expr(&mut self, expr: &expr::Expr) -> Result<Value, RuntimeError> {
         if self.interrupted.load(Ordering::Acquire) {
             return Ok(Value::Nil);
         }
 
-        match expr {
-            expr::Expr::This(source_location) => match self.lookup(&Interpreter::this_symbol(
+        match &expr.kind {
+            expr::ExprKind::This(source_location) => match self.lookup(&Interpreter::this_symbol(
                 source_location.line,
                 source_location.col,
             )) {
                 Ok(val) => Ok(val.clone()),
                 Err(err) => Err(err),
             },
-            expr::Expr::Literal(lit) => Ok(Interpreter::interpret_literal(lit)),
-            expr::Expr::Unary(op, e) => self.interpret_unary(*op, e),
-            expr::Expr::Binary(lhs, op, rhs) => self.interpret_binary(lhs, *op, rhs),
-            expr::Expr::Call(callee, loc, args) => self.call(callee, loc, args),
-            expr::Expr::Get(lhs, attr) => self.getattr(lhs, &attr.name),
-            expr::Expr::Set(lhs, attr, rhs) => self.setattr(lhs, attr, rhs),
-            expr::Expr::Grouping(e) => self.interpret_expr(e),
-            expr::Expr::Variable(sym) => match self.lookup(sym) {
-                Ok(val) => Ok(val.clone()),
+            expr::ExprKind::Literal(lit) => Ok(Interpreter::interpret_literal(lit)),
+            expr::ExprKind::Unary(op, e) => self.interpret_unary(*op, e),
+            expr::ExprKind::Binary(lhs, op, rhs) => self.interpret_binary(lhs, *op, rhs),
+            expr::ExprKind::Compare(first, rest) => {
+                let mut lhs = self.interpret_expr(first)?;
+                let mut res = Value::Bool(true);
+                for (op, rhs_expr) in rest {
+                    let rhs = self.interpret_expr(rhs_expr)?;
+                    res = self
+                        .binary_op(&lhs, *op, &rhs)
+                        .map_err(|err| err.at(op.line, op.col))?;
+                    // 하나라도 거짓이면 나머지는 평가하지 않음
+                    if !Interpreter::is_truthy(&res) {
+                        break;
+                    }
+                    lhs = rhs;
+                }
+                Ok(res)
+            }
+            expr::ExprKind::Call(callee, loc, args) => self.call(callee, loc, args),
+            expr::ExprKind::Get(lhs, attr) => self.getattr(lhs, attr),
+            expr::ExprKind::Set(lhs, attr, maybe_op, rhs) => {
+                self.setattr(lhs, attr, *maybe_op, rhs)
+            }
+            expr::ExprKind::Grouping(e) => self.interpret_expr(e),
+            expr::ExprKind::Variable(sym) => match self.lookup(sym) {
+                Ok(val) => Ok(val.clone()),
                 Err(err) => Err(err),
             },
-            expr::Expr::Assign(sym, val_expr) => {
+            expr::ExprKind::Destructure(pattern, val_expr, binding) => {
+                let val = self.interpret_expr(val_expr)?;
+                self.bind_pattern(pattern, val.clone(), *binding)?;
+                Ok(val)
+            }
+            expr::ExprKind::Assign(sym, val_expr, binding) => {
                 let val = self.interpret_expr(val_expr)?;
 
-                if let Err(err) = self.env.assign(sym.clone(), &val) {
-                    return Err(err);
-                }
+                self.bind_variable(sym, val.clone(), *binding)?;
 
                 Ok(val)
             }
-            expr::Expr::Logical(left_expr, expr::LogicalOp::Or, right_expr) => {
+            expr::ExprKind::Conditional(cond, then, otherwise) => {
+                if Interpreter::is_truthy(&self.interpret_expr(cond)?) {
+                    self.interpret_expr(then)
+                } else {
+                    self.interpret_expr(otherwise)
+                }
+            }
+            expr::ExprKind::Logical(left_expr, expr::LogicalOp::Or, right_expr) => {
                 let left = self.interpret_expr(left_expr)?;
                 if Interpreter::is_truthy(&left) {
                     Ok(left)
@@ -870,7 +2513,7 @@ impl Interpreter {
                     Ok(self.interpret_expr(right_expr)?)
                 }
             }
-            expr::Expr::Logical(left_expr, expr::LogicalOp::And, right_expr) => {
+            expr::ExprKind::Logical(left_expr, expr::LogicalOp::And, right_expr) => {
                 let left = self.interpret_expr(left_expr)?;
                 if !Interpreter::is_truthy(&left) {
                     Ok(left)
@@ -878,7 +2521,7 @@ impl Interpreter {
                     Ok(self.interpret_expr(right_expr)?)
                 }
             }
-            expr::Expr::Super(source_location, sym) => match self.enclosing_function {
+            expr::ExprKind::Super(source_location, sym) => match self.enclosing_function {
                 Some(func_id) => {
                     let func = self.get_lox_function(func_id);
                     match &func.superclass {
@@ -889,49 +2532,128 @@ impl Interpreter {
                                 Ok(Value::LoxFunction(
                                     func_name,
                                     method.id,
-                                    func.this_binding.clone()))
-                            }
-                            else {
-                                Err(format!("no superclass has method {} at line={}, col={}",
-                                            sym.name, source_location.line, source_location.col))
+                                    func.this_binding.clone(),
+                                ))
+                            } else {
+                                Err(RuntimeError::new(
+                                    ErrorKind::AttributeError,
+                                    format!("no superclass has method {}", sym.name),
+                                )
+                                .at(source_location.line, source_location.col))
                             }
                         }
-                        _ => {
-                            Err(format!("Super expression not enclosed in a method definition at line={}, col={}.",
-                                        source_location.line, source_location.col))
-                        }
+                        _ => Err(RuntimeError::new(
+                            ErrorKind::RuntimeError,
+                            "Super expression not enclosed in a method definition",
+                        )
+                        .at(source_location.line, source_location.col)),
                     }
                 }
-                None => Err(format!(
-                    "super expression not enclosed in a function at line={}, col={}.",
-                    source_location.line, source_location.col
-                )),
+                None => Err(RuntimeError::new(
+                    ErrorKind::RuntimeError,
+                    "super expression not enclosed in a function",
+                )
+                .at(source_location.line, source_location.col)),
             },
-            expr::Expr::List(elements) => self.list(elements),
-            expr::Expr::Subscript {
+            expr::ExprKind::List(elements) => self.list(elements),
+            expr::ExprKind::Tuple(elements) => {
+                let mut items = Vec::new();
+                for element in elements {
+                    match &element.kind {
+                        // (*xs, y): 반복 가능한 값을 펼쳐 넣음
+                        expr::ExprKind::Starred(inner, _) => {
+                            let inner = self.interpret_expr(inner)?;
+                            items.extend(self.collect_iterable(&inner)?);
+                        }
+                        _ => items.push(self.interpret_expr(element)?),
+                    }
+                }
+                Ok(Value::Tuple(items))
+            }
+            expr::ExprKind::Starred(_, loc) => Err(RuntimeError::new(
+                ErrorKind::RuntimeError,
+                "can't use starred expression here",
+            )
+            .at(loc.line, loc.col)),
+            expr::ExprKind::Dict(entries) => {
+                let mut dict = dict::Dict::default();
+                for (key_expr, value_expr) in entries {
+                    let key = self.interpret_expr(key_expr)?;
+                    let value = self.interpret_expr(value_expr)?;
+                    dict.insert(key, value)?;
+                }
+                Ok(self.create_dict(dict))
+            }
+            expr::ExprKind::SetLiteral(elements) => {
+                let mut set = set::Set::default();
+                for element in elements {
+                    let item = self.interpret_expr(element)?;
+                    set.insert(item)?;
+                }
+                Ok(self.create_set(set))
+            }
+            expr::ExprKind::ListComp(element, clauses) => {
+                let items = self
+                    .comprehension(&[element], clauses)?
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                Ok(self.create_list(items))
+            }
+            expr::ExprKind::SetComp(element, clauses) => {
+                let mut set = set::Set::default();
+                for item in self.comprehension(&[element], clauses)?.into_iter().flatten() {
+                    set.insert(item)?;
+                }
+                Ok(self.create_set(set))
+            }
+            expr::ExprKind::DictComp(key, value, clauses) => {
+                let mut dict = dict::Dict::default();
+                for entry in self.comprehension(&[key, value], clauses)? {
+                    let [key, value]: [Value; 2] = entry.try_into().unwrap();
+                    dict.insert(key, value)?;
+                }
+                Ok(self.create_dict(dict))
+            }
+            expr::ExprKind::Subscript {
                 value,
                 slice,
                 source_location,
-            } => self.subscript(value, slice, source_location),
-            expr::Expr::SetItem {
+            } => self
+                .subscript(value, slice, source_location)
+                .map_err(|err| err.at(source_location.line, source_location.col)),
+            expr::ExprKind::SetItem {
                 lhs,
                 slice,
+                op,
                 rhs,
                 source_location,
-            } => self.setitem(lhs, slice, rhs, source_location),
-            expr::Expr::Lambda(lambda_decl) => {
+            } => self
+                .setitem(lhs, slice, *op, rhs, source_location)
+                .map_err(|err| err.at(source_location.line, source_location.col)),
+            expr::ExprKind::Slice { .. } => Err(RuntimeError::new(
+                ErrorKind::RuntimeError,
+                "Internal interpreter error! slice outside of subscript.",
+            )),
+            expr::ExprKind::Lambda(lambda_decl) => {
                 let lambda_sym = expr::Symbol {
                     name: self.lambda_name(),
                     line: 0,
                     col: 0,
                 };
-                let maybe_err = self.execute(&expr::Stmt::FunDecl(expr::FunDecl {
-                    name: lambda_sym.clone(),
-                    params: lambda_decl.params.clone(),
-                    body: lambda_decl.body.clone(),
-                }));
+                let maybe_err = self.execute(&expr::Stmt::new(
+                    expr::StmtKind::FunDecl(expr::FunDecl {
+                        name: lambda_sym.clone(),
+                        params: lambda_decl.params.clone(),
+                        body: lambda_decl.body.clone(),
+                    }),
+                    expr.span,
+                ));
                 match maybe_err {
-                    Ok(_) => self.interpret_expr(&expr::Expr::Variable(lambda_sym)),
+                    Ok(_) => self.interpret_expr(&expr::Expr::new(
+                        expr::ExprKind::Variable(lambda_sym),
+                        expr.span,
+                    )),
                     Err(err) => Err(err),
                 }
             }
@@ -948,22 +2670,77 @@ impl Interpreter {
         &mut self,
         lhs_expr: &expr::Expr,
         slice_expr: &expr::Expr,
+        maybe_op: Option<expr::BinaryOp>,
         rhs_expr: &expr::Expr,
         source_location: &expr::SourceLocation,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RuntimeError> {
         let lhs = self.interpret_expr(lhs_expr)?;
-        let slice = self.interpret_expr(slice_expr)?;
-        let rhs = self.interpret_expr(rhs_expr)?;
-        if let Value::List(list_id) = lhs {
-            let elements = self.get_list_elts_mut(list_id);
-            let subscript_index =
-                Interpreter::subscript_to_inbound_index(elements.len(), &slice, source_location)?;
-            elements[subscript_index] = rhs.clone();
-            Ok(rhs)
+        let key = self.subscript_key(slice_expr)?;
+        // xs[i] += v: xs와 i는 한번만 평가함
+        let rhs = match maybe_op {
+            Some(op) => {
+                let current = self.getitem(&lhs, &key, source_location)?;
+                let rhs = self.interpret_expr(rhs_expr)?;
+                self.binary_op(&current, op, &rhs)?
+            }
+            None => self.interpret_expr(rhs_expr)?,
+        };
+        self.store_item(&lhs, key, rhs.clone(), source_location)?;
+        Ok(rhs)
+    }
+
+    fn store_item(
+        &mut self,
+        lhs: &Value,
+        key: SubscriptKey,
+        rhs: Value,
+        source_location: &expr::SourceLocation,
+    ) -> Result<(), RuntimeError> {
+        if let Value::List(list_id) = *lhs {
+            match key {
+                SubscriptKey::Index(index) => {
+                    let elements = self.get_list_elts_mut(list_id);
+                    let subscript_index = Interpreter::subscript_to_inbound_index(
+                        "List",
+                        elements.len(),
+                        &index,
+                        source_location,
+                    )?;
+                    elements[subscript_index] = rhs;
+                }
+                SubscriptKey::Slice(start, stop, step) => {
+                    // xs[1:3] = xs 처럼 자기 자신을 넣는 경우를 위해 먼저 꺼내둠
+                    let items = self.collect_iterable(&rhs)?;
+                    let elements = self.get_list_elts_mut(list_id);
+                    let (start, stop, step) = slice_bounds(elements.len(), start, stop, step)?;
+                    if step == 1 {
+                        let start = start as usize;
+                        elements.splice(start..(stop as usize).max(start), items);
+                    } else {
+                        let positions = slice_positions(start, stop, step);
+                        if positions.len() != items.len() {
+                            return Err(RuntimeError::new(
+                                ErrorKind::ValueError,
+                                format!(
+                                    "attempt to assign sequence of size {} to extended slice of size {}",
+                                    items.len(),
+                                    positions.len()
+                                ),
+                            ));
+                        }
+                        for (pos, item) in positions.into_iter().zip(items) {
+                            elements[pos] = item;
+                        }
+                    }
+                }
+            }
+            Ok(())
+        } else if let (Value::Dict(dict_id), SubscriptKey::Index(key)) = (lhs, key) {
+            self.get_dict_mut(*dict_id).insert(key, rhs)
         } else {
-            Err(format!(
-                "Invalid value of type {:?} in setitem expr.",
-                type_of(&lhs),
+            Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!("Invalid value of type {:?} in setitem expr.", type_of(lhs)),
             ))
         }
     }
@@ -973,48 +2750,211 @@ impl Interpreter {
         value_expr: &expr::Expr,
         slice_expr: &expr::Expr,
         source_location: &expr::SourceLocation,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RuntimeError> {
         let value = self.interpret_expr(value_expr)?;
-        let slice = self.interpret_expr(slice_expr)?;
-        if let Value::List(list_id) = value {
-            let elements = self.get_list_elts(list_id);
-            let subscript_index =
-                Interpreter::subscript_to_inbound_index(elements.len(), &slice, source_location)?;
-            Ok(elements[subscript_index].clone())
-        } else {
-            Err(format!(
-                "Invalid value of type {:?} in subscript expr.",
-                type_of(&value),
-            ))
+        let key = self.subscript_key(slice_expr)?;
+        self.getitem(&value, &key, source_location)
+    }
+
+    // 슬라이스 노드면 각 부분을 평가하고, 아니면 보통의 인덱스 값
+    fn subscript_key(&mut self, slice_expr: &expr::Expr) -> Result<SubscriptKey, RuntimeError> {
+        match &slice_expr.kind {
+            expr::ExprKind::Slice { start, stop, step } => Ok(SubscriptKey::Slice(
+                self.slice_index(start)?,
+                self.slice_index(stop)?,
+                self.slice_index(step)?,
+            )),
+            _ => Ok(SubscriptKey::Index(self.interpret_expr(slice_expr)?)),
+        }
+    }
+
+    fn slice_index(&mut self, part: &Option<Box<expr::Expr>>) -> Result<Option<i64>, RuntimeError> {
+        let value = match part {
+            Some(part) => self.interpret_expr(part)?,
+            None => return Ok(None),
+        };
+        match value {
+            Value::Nil => Ok(None),
+            Value::Int(n) => Ok(Some(n)),
+            // 어차피 범위 밖이라 양끝으로 맞춰짐
+            Value::BigInt(n) if n.is_negative() => Ok(Some(i64::MIN)),
+            Value::BigInt(_) => Ok(Some(i64::MAX)),
+            _ => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!(
+                    "slice indices must be integers or nil, found {:?}",
+                    type_of(&value)
+                ),
+            )),
+        }
+    }
+
+    fn getitem(
+        &mut self,
+        value: &Value,
+        key: &SubscriptKey,
+        source_location: &expr::SourceLocation,
+    ) -> Result<Value, RuntimeError> {
+        match (value, key) {
+            (Value::List(list_id), SubscriptKey::Index(index)) => {
+                let elements = self.get_list_elts(*list_id);
+                let subscript_index = Interpreter::subscript_to_inbound_index(
+                    "List",
+                    elements.len(),
+                    index,
+                    source_location,
+                )?;
+                Ok(elements[subscript_index].clone())
+            }
+            (Value::List(list_id), SubscriptKey::Slice(start, stop, step)) => {
+                let elements = self.get_list_elts(*list_id);
+                let (start, stop, step) = slice_bounds(elements.len(), *start, *stop, *step)?;
+                let sliced = slice_positions(start, stop, step)
+                    .into_iter()
+                    .map(|pos| elements[pos].clone())
+                    .collect();
+                Ok(self.create_list(sliced))
+            }
+            (Value::Tuple(elements), SubscriptKey::Index(index)) => {
+                let subscript_index = Interpreter::subscript_to_inbound_index(
+                    "Tuple",
+                    elements.len(),
+                    index,
+                    source_location,
+                )?;
+                Ok(elements[subscript_index].clone())
+            }
+            (Value::Tuple(elements), SubscriptKey::Slice(start, stop, step)) => {
+                let (start, stop, step) = slice_bounds(elements.len(), *start, *stop, *step)?;
+                Ok(Value::Tuple(
+                    slice_positions(start, stop, step)
+                        .into_iter()
+                        .map(|pos| elements[pos].clone())
+                        .collect(),
+                ))
+            }
+            (Value::Dict(dict_id), SubscriptKey::Index(key)) => {
+                match self.get_dict(*dict_id).get(key)? {
+                    Some(value) => Ok(value.clone()),
+                    None => Err(self.key_error(key)),
+                }
+            }
+            // 문자열은 바이트가 아니라 문자 단위로 셈
+            (Value::String(s), SubscriptKey::Index(index)) => {
+                let chars: Vec<char> = s.chars().collect();
+                let subscript_index = Interpreter::subscript_to_inbound_index(
+                    "String",
+                    chars.len(),
+                    index,
+                    source_location,
+                )?;
+                Ok(Value::String(chars[subscript_index].to_string()))
+            }
+            (Value::String(s), SubscriptKey::Slice(start, stop, step)) => {
+                let chars: Vec<char> = s.chars().collect();
+                let (start, stop, step) = slice_bounds(chars.len(), *start, *stop, *step)?;
+                Ok(Value::String(
+                    slice_positions(start, stop, step)
+                        .into_iter()
+                        .map(|pos| chars[pos])
+                        .collect(),
+                ))
+            }
+            _ => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!(
+                    "Invalid value of type {:?} in subscript expr.",
+                    type_of(value)
+                ),
+            )),
+        }
+    }
+
+    fn delitem(
+        &mut self,
+        value: &Value,
+        key: &SubscriptKey,
+        source_location: &expr::SourceLocation,
+    ) -> Result<(), RuntimeError> {
+        match (value, key) {
+            (Value::List(list_id), SubscriptKey::Index(index)) => {
+                let elements = self.get_list_elts_mut(*list_id);
+                let subscript_index = Interpreter::subscript_to_inbound_index(
+                    "List",
+                    elements.len(),
+                    index,
+                    source_location,
+                )?;
+                elements.remove(subscript_index);
+                Ok(())
+            }
+            (Value::List(list_id), SubscriptKey::Slice(start, stop, step)) => {
+                let elements = self.get_list_elts_mut(*list_id);
+                let (start, stop, step) = slice_bounds(elements.len(), *start, *stop, *step)?;
+                let mut positions = slice_positions(start, stop, step);
+                // 뒤에서부터 지워야 앞쪽 인덱스가 밀리지 않음
+                positions.sort_unstable_by(|a, b| b.cmp(a));
+                for pos in positions {
+                    elements.remove(pos);
+                }
+                Ok(())
+            }
+            (Value::Dict(dict_id), SubscriptKey::Index(key)) => {
+                match self.get_dict_mut(*dict_id).remove(key)? {
+                    Some(_) => Ok(()),
+                    None => Err(self.key_error(key)),
+                }
+            }
+            _ => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!(
+                    "Invalid value of type {:?} in del statement.",
+                    type_of(value)
+                ),
+            )),
         }
     }
 
     fn subscript_to_inbound_index(
+        what: &str,
         list_len: usize,
         slice: &Value,
         source_location: &expr::SourceLocation,
-    ) -> Result<usize, String> {
-        if let Value::Number(index_float) = slice {
-            let index_int = *index_float as i64;
-            if 0 <= index_int && index_int < list_len as i64 {
-                return Ok(index_int as usize);
-            }
-            if index_int < 0 && -index_int <= list_len as i64 {
-                return Ok((list_len as i64 + index_int) as usize);
-            }
-            Err(format!(
-                "List subscript index out of range at {:?}",
-                source_location
-            ))
+    ) -> Result<usize, RuntimeError> {
+        if let Value::Int(index_int) = slice {
+            // 음수는 뒤에서부터 셈. i64::MIN도 부호를 바꾸면 넘치므로 절댓값을 u64로 비교함
+            let len = list_len as u64;
+            let index = if *index_int >= 0 {
+                Some(*index_int as u64).filter(|&index| index < len)
+            } else {
+                len.checked_sub(index_int.unsigned_abs())
+            };
+            if let Some(index) = index {
+                return Ok(index as usize);
+            }
+            Err(RuntimeError::new(
+                ErrorKind::IndexError,
+                format!("{} subscript index out of range", what),
+            )
+            .at(source_location.line, source_location.col))
+        } else if let Value::BigInt(_) = slice {
+            Err(RuntimeError::new(
+                ErrorKind::IndexError,
+                format!("{} subscript index out of range", what),
+            )
+            .at(source_location.line, source_location.col))
         } else {
-            Err(format!(
-                "Invalid subscript of type {:?} in subscript expression",
-                type_of(slice),
+            Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!(
+                    "Invalid subscript of type {:?} in subscript expression",
+                    type_of(slice)
+                ),
             ))
         }
     }
 
-    fn list(&mut self, element_exprs: &[expr::Expr]) -> Result<Value, String> {
+    fn list(&mut self, element_exprs: &[expr::Expr]) -> Result<Value, RuntimeError> {
         let maybe_elements: Result<Vec<_>, _> = element_exprs
             .iter()
             .map(|expr| self.interpret_expr(expr))
@@ -1026,40 +2966,193 @@ impl Interpreter {
         }
     }
 
-    fn getattr(&mut self, lhs: &expr::Expr, attr: &str) -> Result<Value, String> {
+    fn getattr(&mut self, lhs: &expr::Expr, attr: &expr::Symbol) -> Result<Value, RuntimeError> {
         let val = self.interpret_expr(lhs)?;
+        self.get_attribute(&val, &attr.name)
+            .map_err(|err| err.at(attr.line, attr.col))
+    }
+
+    fn get_attribute(&self, val: &Value, attr: &str) -> Result<Value, RuntimeError> {
         match val {
-            Value::LoxInstance(_, id) => self.get_lox_instance(id).getattr(attr, self),
-            _ => Err(format!(
-                "Only LoxInstance values have attributes. Found {:?}.",
-                type_of(&val)
-            )),
+            Value::LoxInstance(_, id) => self.get_lox_instance(*id).getattr(attr, self),
+            _ => match Interpreter::builtin_method(val, attr) {
+                Some(method) => Ok(Value::NativeMethod(method)),
+                None => Err(RuntimeError::new(
+                    ErrorKind::AttributeError,
+                    format!("{:?} value has no '{}' attribute.", type_of(val), attr),
+                )),
+            },
         }
     }
 
+    // 내장 타입은 타입별 메소드 테이블에서 찾음
+    fn builtin_method(val: &Value, attr: &str) -> Option<NativeMethod> {
+        let methods: &[MethodDef] = match val {
+            Value::List(_) => list::METHODS,
+            Value::Dict(_) => dict::METHODS,
+            Value::Set(_) => set::METHODS,
+            Value::String(_) => string::METHODS,
+            _ => &[],
+        };
+
+        methods
+            .iter()
+            .find(|method| method.name == attr)
+            .map(|method| NativeMethod {
+                name: method.name,
+                arity: method.arity,
+                keywords: method.keywords,
+                receiver: Box::new(val.clone()),
+                callable: method.callable,
+            })
+    }
+
     fn setattr(
         &mut self,
         lhs_exp: &expr::Expr,
         attr: &expr::Symbol,
+        maybe_op: Option<expr::BinaryOp>,
         rhs_exp: &expr::Expr,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RuntimeError> {
         let lhs = self.interpret_expr(lhs_exp)?;
-        let rhs = self.interpret_expr(rhs_exp)?;
+        // obj.x += v: obj는 한번만 평가함
+        let rhs = match maybe_op {
+            Some(op) => {
+                let current = self
+                    .get_attribute(&lhs, &attr.name)
+                    .map_err(|err| err.at(attr.line, attr.col))?;
+                let rhs = self.interpret_expr(rhs_exp)?;
+                self.binary_op(&current, op, &rhs)?
+            }
+            None => self.interpret_expr(rhs_exp)?,
+        };
+        self.store_attr(&lhs, &attr.name, rhs.clone())
+            .map_err(|err| err.at(attr.line, attr.col))?;
+        Ok(rhs)
+    }
+
+    fn store_attr(&mut self, lhs: &Value, attr: &str, rhs: Value) -> Result<(), RuntimeError> {
         match lhs {
-            Value::LoxInstance(_, id) => match self.lox_instances.get_mut(&id) {
+            Value::LoxInstance(_, id) => match self.lox_instances.get_mut(id) {
                 Some(inst) => {
-                    inst.fields.insert(attr.name.clone(), rhs.clone());
-                    Ok(rhs)
+                    inst.fields.insert(attr.to_string(), rhs);
+                    Ok(())
                 }
                 None => panic!(
                     "Internal interpreter error: could not find instance with id {}",
                     id
                 ),
             },
-            _ => Err(format!(
-                "Only LoxInstance values have attributes. Found {:?}.",
-                type_of(&lhs)
+            _ => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!(
+                    "Only LoxInstance values have attributes. Found {:?}.",
+                    type_of(lhs)
+                ),
+            )),
+        }
+    }
+
+    // 구조 분해 대입. declare면 for 루프처럼 현재 환경에 새로 정의함
+    fn bind_pattern(
+        &mut self,
+        pattern: &expr::Pattern,
+        value: Value,
+        binding: expr::Binding,
+    ) -> Result<(), RuntimeError> {
+        let patterns = match pattern {
+            expr::Pattern::Target(target) | expr::Pattern::Starred(target) => {
+                return self.bind_target(target, value, binding)
+            }
+            expr::Pattern::Tuple(patterns) => patterns,
+        };
+        let mut items = self.collect_iterable(&value)?;
+        let starred = patterns
+            .iter()
+            .position(|pattern| matches!(pattern, expr::Pattern::Starred(_)));
+        match starred {
+            None if items.len() > patterns.len() => Err(RuntimeError::new(
+                ErrorKind::ValueError,
+                format!("too many values to unpack (expected {})", patterns.len()),
+            )),
+            None if items.len() < patterns.len() => Err(RuntimeError::new(
+                ErrorKind::ValueError,
+                format!(
+                    "not enough values to unpack (expected {}, got {})",
+                    patterns.len(),
+                    items.len()
+                ),
+            )),
+            None => {
+                for (pattern, item) in patterns.iter().zip(items) {
+                    self.bind_pattern(pattern, item, binding)?;
+                }
+                Ok(())
+            }
+            Some(_) if items.len() < patterns.len() - 1 => Err(RuntimeError::new(
+                ErrorKind::ValueError,
+                format!(
+                    "not enough values to unpack (expected at least {}, got {})",
+                    patterns.len() - 1,
+                    items.len()
+                ),
             )),
+            Some(star) => {
+                // 별표 앞뒤를 먼저 떼어내고 가운데를 리스트로 묶음
+                let tail = items.split_off(items.len() - (patterns.len() - star - 1));
+                let rest = items.split_off(star);
+                let rest = self.create_list(rest);
+                let values = items.into_iter().chain(std::iter::once(rest)).chain(tail);
+                for (pattern, item) in patterns.iter().zip(values) {
+                    self.bind_pattern(pattern, item, binding)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn bind_target(
+        &mut self,
+        target: &expr::Expr,
+        value: Value,
+        binding: expr::Binding,
+    ) -> Result<(), RuntimeError> {
+        match &target.kind {
+            expr::ExprKind::Variable(sym) => self.bind_variable(sym, value, binding),
+            expr::ExprKind::Get(lhs, attr) => {
+                let lhs = self.interpret_expr(lhs)?;
+                self.store_attr(&lhs, &attr.name, value)
+            }
+            expr::ExprKind::Subscript {
+                value: lhs,
+                slice,
+                source_location,
+            } => {
+                let lhs = self.interpret_expr(lhs)?;
+                self.subscript_key(slice)
+                    .and_then(|key| self.store_item(&lhs, key, value, source_location))
+                    .map_err(|err| err.at(source_location.line, source_location.col))
+            }
+            _ => panic!("Internal interpreter error! invalid destructuring target."),
+        }
+    }
+
+    fn bind_variable(
+        &mut self,
+        sym: &expr::Symbol,
+        value: Value,
+        binding: expr::Binding,
+    ) -> Result<(), RuntimeError> {
+        let define = match binding {
+            expr::Binding::Define => true,
+            expr::Binding::Assign => false,
+            expr::Binding::AssignOrDefine => !self.env.is_declared(&sym.name),
+        };
+        if define {
+            self.env.define(sym.clone(), Some(value));
+            Ok(())
+        } else {
+            self.env.assign(sym.clone(), &value)
         }
     }
 
@@ -1067,82 +3160,181 @@ impl Interpreter {
         &mut self,
         callee_expr: &expr::Expr,
         loc: &expr::SourceLocation,
-        arg_exprs: &[expr::Expr],
-    ) -> Result<Value, String> {
+        arg_exprs: &[expr::Argument],
+    ) -> Result<Value, RuntimeError> {
         let callee = self.interpret_expr(callee_expr)?;
 
         match as_callable(self, &callee) {
             Some(callable) => {
-                let maybe_args: Result<Vec<_>, _> = arg_exprs
-                    .iter()
-                    .map(|arg| self.interpret_expr(arg))
-                    .collect();
-
-                match maybe_args {
-                    Ok(args) => {
-                        if args.len() != callable.arity(self).into() {
-                            Err(format!(
-                                "Invalid call at line={},col={}: callee has arity {}, but \
-                                         was called with {} arguments",
-                                loc.line,
-                                loc.col,
-                                callable.arity(self),
-                                args.len()
-                            ))
-                        } else {
-                            callable.call(self, &args)
+                let mut args = Vec::new();
+                let mut kwargs = Vec::new();
+                for arg in arg_exprs {
+                    match arg {
+                        expr::Argument::Positional(value) => {
+                            args.push(self.interpret_expr(value)?)
+                        }
+                        expr::Argument::Keyword(name, value) => {
+                            kwargs.push((name.name.clone(), self.interpret_expr(value)?))
+                        }
+                        expr::Argument::Unpack(value) => {
+                            let value = self.interpret_expr(value)?;
+                            args.extend(self.collect_iterable(&value)?)
+                        }
+                        expr::Argument::UnpackKeywords(value) => {
+                            let value = self.interpret_expr(value)?;
+                            kwargs.extend(
+                                self.keyword_arguments(callable.name(), &value)
+                                    .map_err(|err| err.at(loc.line, loc.col))?,
+                            )
                         }
                     }
-                    Err(err) => Err(err),
                 }
+
+                let args = callable
+                    .signature(self)
+                    .bind(self, callable.name(), args, kwargs)
+                    .map_err(|err| err.at(loc.line, loc.col))?;
+                self.set_current_line(loc.line);
+                // 네이티브 함수의 에러는 위치가 없으니 호출한 곳으로 함
+                let res = callable
+                    .call(self, args)
+                    .map_err(|err| err.at(loc.line, loc.col));
+                if res.is_ok() {
+                    self.set_current_line(0);
+                }
+                res
+            }
+            None => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!("value {:?} is not callable", callee),
+            )
+            .at(loc.line, loc.col)),
+        }
+    }
+
+    // f(**d)의 d. 키는 문자열이어야 함
+    fn keyword_arguments(
+        &self,
+        name: &str,
+        value: &Value,
+    ) -> Result<Vec<(String, Value)>, RuntimeError> {
+        let dict_id = match value {
+            Value::Dict(dict_id) => *dict_id,
+            _ => {
+                return Err(RuntimeError::new(
+                    ErrorKind::TypeError,
+                    format!(
+                        "{}() argument after ** must be a dict, not {:?}",
+                        name,
+                        type_of(value)
+                    ),
+                ))
+            }
+        };
+        self.get_dict(dict_id)
+            .entries()
+            .iter()
+            .map(|(key, value)| match key {
+                Value::String(key) => Ok((key.clone(), value.clone())),
+                _ => Err(RuntimeError::new(
+                    ErrorKind::TypeError,
+                    format!("{}() keywords must be strings", name),
+                )),
+            })
+            .collect()
+    }
+
+    // 네이티브 코드에서 값을 호출할 때 사용 (sort의 key 등)
+    pub(crate) fn call_value(
+        &mut self,
+        callee: &Value,
+        args: &[Value],
+    ) -> Result<Value, RuntimeError> {
+        match as_callable(self, callee) {
+            Some(callable) => {
+                let args = callable
+                    .signature(self)
+                    .bind(self, callable.name(), args.to_vec(), Vec::new())?;
+                callable.call(self, args)
             }
-            None => Err(format!(
-                "value {:?} is not callable at line={},col={}",
-                callee, loc.line, loc.col
+            None => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!("value {:?} is not callable", callee),
             )),
         }
     }
 
+    // 기본값은 정의하는 시점의 환경에서 한번만 평가함
+    fn eval_defaults(
+        &mut self,
+        params: &[expr::Param],
+    ) -> Result<Vec<Option<Value>>, RuntimeError> {
+        params
+            .iter()
+            .map(|param| match &param.default {
+                Some(default) => self.interpret_expr(default).map(Some),
+                None => Ok(None),
+            })
+            .collect()
+    }
+
     fn interpret_binary(
         &mut self,
         lhs_expr: &expr::Expr,
         op: expr::BinaryOp,
         rhs_expr: &expr::Expr,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RuntimeError> {
         let lhs = self.interpret_expr(lhs_expr)?;
         let rhs = self.interpret_expr(rhs_expr)?;
+        self.binary_op(&lhs, op, &rhs)
+            .map_err(|err| err.at(op.line, op.col))
+    }
 
-        match (&lhs, op.toktype, &rhs) {
-            (Value::Number(n1), expr::BinaryOpTy::Less, Value::Number(n2)) => {
-                Ok(Value::Bool(n1 < n2))
-            }
-            (Value::Number(n1), expr::BinaryOpTy::LessEqual, Value::Number(n2)) => {
-                Ok(Value::Bool(n1 <= n2))
-            }
-            (Value::Number(n1), expr::BinaryOpTy::Greater, Value::Number(n2)) => {
-                Ok(Value::Bool(n1 > n2))
-            }
-            (Value::Number(n1), expr::BinaryOpTy::GreaterEqual, Value::Number(n2)) => {
-                Ok(Value::Bool(n1 >= n2))
-            }
-            (Value::Number(n1), expr::BinaryOpTy::Plus, Value::Number(n2)) => {
-                Ok(Value::Number(n1 + n2))
+    fn binary_op(
+        &mut self,
+        lhs: &Value,
+        op: expr::BinaryOp,
+        rhs: &Value,
+    ) -> Result<Value, RuntimeError> {
+        match (lhs, op.toktype, rhs) {
+            // 정수끼리는 정수로 계산하고, 실수가 섞이면 실수로 바꿔서 계산함
+            (Value::Int(n1), _, Value::Int(n2)) if is_numeric_op(op.toktype) => {
+                int_binary_op(*n1, op, *n2)
             }
-            (Value::Number(n1), expr::BinaryOpTy::Minus, Value::Number(n2)) => {
-                Ok(Value::Number(n1 - n2))
+            (Value::Int(_) | Value::BigInt(_), _, Value::Int(_) | Value::BigInt(_))
+                if is_numeric_op(op.toktype) =>
+            {
+                big_binary_op(&as_bigint(lhs).unwrap(), op, &as_bigint(rhs).unwrap())
             }
-            (Value::Number(n1), expr::BinaryOpTy::Star, Value::Number(n2)) => {
-                Ok(Value::Number(n1 * n2))
+            // 정수와 실수의 대소 비교는 실수로 바꾸지 않고 정확히 함
+            (
+                Value::Int(_) | Value::BigInt(_) | Value::Number(_),
+                expr::BinaryOpTy::Less
+                | expr::BinaryOpTy::LessEqual
+                | expr::BinaryOpTy::Greater
+                | expr::BinaryOpTy::GreaterEqual,
+                Value::Int(_) | Value::BigInt(_) | Value::Number(_),
+            ) => {
+                let ord = compare_numbers(lhs, rhs);
+                Ok(Value::Bool(match op.toktype {
+                    expr::BinaryOpTy::Less => ord == Some(std::cmp::Ordering::Less),
+                    expr::BinaryOpTy::LessEqual => {
+                        matches!(ord, Some(std::cmp::Ordering::Less | std::cmp::Ordering::Equal))
+                    }
+                    expr::BinaryOpTy::Greater => ord == Some(std::cmp::Ordering::Greater),
+                    _ => matches!(
+                        ord,
+                        Some(std::cmp::Ordering::Greater | std::cmp::Ordering::Equal)
+                    ),
+                }))
             }
-            (Value::Number(n1), expr::BinaryOpTy::Slash, Value::Number(n2)) => {
-                if *n2 != 0.0 {
-                    Ok(Value::Number(n1 / n2))
-                } else {
-                    Err(format!(
-                        "division by zero at line={},col={}",
-                        op.line, op.col
-                    ))
-                }
+            (
+                Value::Int(_) | Value::BigInt(_) | Value::Number(_),
+                _,
+                Value::Int(_) | Value::BigInt(_) | Value::Number(_),
+            ) if is_numeric_op(op.toktype) && !is_bitwise_op(op.toktype) =>
+            {
+                float_binary_op(as_float(lhs), op, as_float(rhs))
             }
             (Value::String(s1), expr::BinaryOpTy::Plus, Value::String(s2)) => {
                 Ok(Value::String(format!("{}{}", s1, s2)))
@@ -1154,75 +3346,320 @@ impl Interpreter {
                 res.extend(ys.clone());
                 Ok(self.create_list(res))
             }
-            (_, expr::BinaryOpTy::EqualEqual, _) => {
-                Ok(Value::Bool(Interpreter::equals(&lhs, &rhs)))
-            }
-            (_, expr::BinaryOpTy::NotEqual, _) => Ok(Value::Bool(!Interpreter::equals(&lhs, &rhs))),
-            _ => Err(format!(
-                "invalid operands in binary operator {:?} of type {:?} and {:?} at line={},col={}",
-                op.toktype,
-                type_of(&lhs),
-                type_of(&rhs),
-                op.line,
-                op.col
+            (Value::Tuple(xs), expr::BinaryOpTy::Plus, Value::Tuple(ys)) => {
+                Ok(Value::Tuple(xs.iter().chain(ys).cloned().collect()))
+            }
+            (_, expr::BinaryOpTy::In, _) => Ok(Value::Bool(self.contains(rhs, lhs)?)),
+            (_, expr::BinaryOpTy::NotIn, _) => Ok(Value::Bool(!self.contains(rhs, lhs)?)),
+            (_, expr::BinaryOpTy::Is, _) => Ok(Value::Bool(Interpreter::is_same(lhs, rhs))),
+            (_, expr::BinaryOpTy::IsNot, _) => Ok(Value::Bool(!Interpreter::is_same(lhs, rhs))),
+            (_, expr::BinaryOpTy::EqualEqual, _) => Ok(Value::Bool(self.equals(lhs, rhs))),
+            (_, expr::BinaryOpTy::NotEqual, _) => Ok(Value::Bool(!self.equals(lhs, rhs))),
+            _ => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!(
+                    "invalid operands in binary operator {:?} of type {:?} and {:?}",
+                    op.toktype,
+                    type_of(lhs),
+                    type_of(rhs)
+                ),
+            )
+            .at(op.line, op.col)),
+        }
+    }
+
+    // 컴프리헨션은 자기 스코프에서 돌아서 루프 변수가 밖으로 새지 않음.
+    // 가장 안쪽 절까지 올 때마다 outputs를 평가한 값들을 하나씩 모음
+    fn comprehension(
+        &mut self,
+        outputs: &[&expr::Expr],
+        clauses: &[expr::CompClause],
+    ) -> Result<Vec<Vec<Value>>, RuntimeError> {
+        self.env = Environment::with_enclosing(self.env.clone());
+        let mut results = Vec::new();
+        let res = self.run_clauses(outputs, clauses, &mut results);
+        if let Some(enclosing) = self.env.enclosing.clone() {
+            self.env = *enclosing
+        }
+        res.map(|()| results)
+    }
+
+    fn run_clauses(
+        &mut self,
+        outputs: &[&expr::Expr],
+        clauses: &[expr::CompClause],
+        results: &mut Vec<Vec<Value>>,
+    ) -> Result<(), RuntimeError> {
+        match clauses.split_first() {
+            None => {
+                let values = outputs
+                    .iter()
+                    .map(|output| self.interpret_expr(output))
+                    .collect::<Result<_, _>>()?;
+                results.push(values);
+                Ok(())
+            }
+            Some((expr::CompClause::For(target, iterable), rest)) => {
+                let iterable = self.interpret_expr(iterable)?;
+                let mut iteration = self.iterate(&iterable)?;
+                while let Some(val) = self.next_item(&mut iteration)? {
+                    self.bind_pattern(target, val, expr::Binding::Define)?;
+                    self.run_clauses(outputs, rest, results)?;
+                }
+                Ok(())
+            }
+            Some((expr::CompClause::If(cond), rest)) => {
+                if Interpreter::is_truthy(&self.interpret_expr(cond)?) {
+                    self.run_clauses(outputs, rest, results)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // x in container. 문자열은 부분 문자열을, 인스턴스는 __contains__를 봄
+    fn contains(&mut self, container: &Value, item: &Value) -> Result<bool, RuntimeError> {
+        match container {
+            Value::List(list_id) => Ok(self
+                .get_list_elts(*list_id)
+                .iter()
+                .any(|elt| self.equals(elt, item))),
+            Value::Tuple(elts) => Ok(elts.iter().any(|elt| self.equals(elt, item))),
+            Value::Dict(dict_id) => self.get_dict(*dict_id).contains(item),
+            Value::Set(set_id) => self.get_set(*set_id).contains(item),
+            Value::Range(start, stop, step) => match item {
+                Value::Int(n) => Ok(range_contains(*start, *stop, *step, *n)),
+                // 1.0 in range(2)처럼 정수 값인 실수는 정수로 바꿔서 셈
+                Value::Number(n) => {
+                    Ok(float_to_int(*n).is_some_and(|n| range_contains(*start, *stop, *step, n)))
+                }
+                // Int 밖의 정수나 정수가 아닌 실수, 다른 타입은 원소와 같을 수 없음
+                _ => Ok(false),
+            },
+            Value::String(s) => match item {
+                Value::String(sub) => Ok(s.contains(sub.as_str())),
+                _ => Err(RuntimeError::new(
+                    ErrorKind::TypeError,
+                    format!(
+                        "'in <string>' requires string as left operand, not {:?}",
+                        type_of(item)
+                    ),
+                )),
+            },
+            Value::LoxInstance(..) => match self.get_attribute(container, "__contains__") {
+                Ok(method) => {
+                    let res = self.call_value(&method, std::slice::from_ref(item))?;
+                    Ok(Interpreter::is_truthy(&res))
+                }
+                // __contains__가 없으면 파이썬처럼 반복하면서 찾음
+                Err(_) => self.iteration_contains(container, item),
+            },
+            _ => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!("argument of type {:?} is not iterable", type_of(container)),
             )),
         }
     }
 
-    fn equals(lhs: &Value, rhs: &Value) -> bool {
+    fn iteration_contains(
+        &mut self,
+        container: &Value,
+        item: &Value,
+    ) -> Result<bool, RuntimeError> {
+        let mut iteration = self.iterate(container)?;
+        while let Some(elt) = self.next_item(&mut iteration)? {
+            if self.equals(&elt, item) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    // ==. 리스트, 딕셔너리, 집합은 내용을 비교하고 그 밖의 힙 값은 같은 객체일 때만 같음
+    pub(crate) fn equals(&self, lhs: &Value, rhs: &Value) -> bool {
+        if Interpreter::is_same(lhs, rhs) {
+            return true;
+        }
         match (lhs, rhs) {
             (Value::Number(n1), Value::Number(n2)) => (n1 - n2).abs() < f64::EPSILON,
+            // 1 == 1.0. 실수가 정수 값과 정확히 같을 때만 참
+            (Value::Int(_) | Value::BigInt(_), Value::Number(_))
+            | (Value::Number(_), Value::Int(_) | Value::BigInt(_)) => {
+                compare_numbers(lhs, rhs) == Some(std::cmp::Ordering::Equal)
+            }
+            (Value::Tuple(xs), Value::Tuple(ys)) => self.all_equal(xs, ys),
+            (Value::List(id1), Value::List(id2)) => {
+                self.all_equal(self.get_list_elts(*id1), self.get_list_elts(*id2))
+            }
+            (Value::Dict(id1), Value::Dict(id2)) => {
+                let (d1, d2) = (self.get_dict(*id1), self.get_dict(*id2));
+                d1.entries().len() == d2.entries().len()
+                    && d1.entries().iter().all(|(key, v1)| match d2.get(key) {
+                        Ok(Some(v2)) => self.equals(v1, v2),
+                        _ => false,
+                    })
+            }
+            (Value::Set(id1), Value::Set(id2)) => {
+                let (s1, s2) = (self.get_set(*id1), self.get_set(*id2));
+                s1.items().count() == s2.items().count()
+                    && s1.items().all(|item| matches!(s2.contains(item), Ok(true)))
+            }
+            (_, _) => false,
+        }
+    }
+
+    fn all_equal(&self, xs: &[Value], ys: &[Value]) -> bool {
+        xs.len() == ys.len() && xs.iter().zip(ys).all(|(x, y)| self.equals(x, y))
+    }
+
+    // is. 힙 값은 id를, 그 밖의 값은 값 자체를 비교함
+    pub(crate) fn is_same(lhs: &Value, rhs: &Value) -> bool {
+        match (lhs, rhs) {
+            (Value::Number(n1), Value::Number(n2)) => n1 == n2,
+            (Value::Int(n1), Value::Int(n2)) => n1 == n2,
+            (Value::BigInt(n1), Value::BigInt(n2)) => n1 == n2,
             (Value::String(s1), Value::String(s2)) => s1 == s2,
             (Value::Bool(b1), Value::Bool(b2)) => b1 == b2,
             (Value::Nil, Value::Nil) => true,
+            (Value::NativeFunction(f1), Value::NativeFunction(f2)) => f1.name == f2.name,
+            (Value::LoxFunction(_, id1, this1), Value::LoxFunction(_, id2, this2)) => {
+                id1 == id2
+                    && match (this1, this2) {
+                        (Some(this1), Some(this2)) => Interpreter::is_same(this1, this2),
+                        (None, None) => true,
+                        _ => false,
+                    }
+            }
+            (Value::LoxClass(_, id1), Value::LoxClass(_, id2))
+            | (Value::LoxInstance(_, id1), Value::LoxInstance(_, id2))
+            | (Value::List(id1), Value::List(id2))
+            | (Value::Dict(id1), Value::Dict(id2))
+            | (Value::Set(id1), Value::Set(id2)) => id1 == id2,
+            (Value::Tuple(xs), Value::Tuple(ys)) => {
+                xs.len() == ys.len() && xs.iter().zip(ys).all(|(x, y)| Interpreter::is_same(x, y))
+            }
+            (Value::Range(a1, b1, c1), Value::Range(a2, b2, c2)) => (a1, b1, c1) == (a2, b2, c2),
             (_, _) => false,
         }
     }
 
-    fn interpret_unary(&mut self, op: expr::UnaryOp, expr: &expr::Expr) -> Result<Value, String> {
+    fn interpret_unary(
+        &mut self,
+        op: expr::UnaryOp,
+        expr: &expr::Expr,
+    ) -> Result<Value, RuntimeError> {
         let val = self.interpret_expr(expr)?;
 
         match (op.toktype, &val) {
             (expr::UnaryOpTy::Minus, Value::Number(n)) => Ok(Value::Number(-n)),
+            (expr::UnaryOpTy::Minus, Value::Int(n)) => match n.checked_neg() {
+                Some(n) => Ok(Value::Int(n)),
+                None => Ok(Value::BigInt(BigInt::from_i64(*n).neg())),
+            },
+            (expr::UnaryOpTy::Minus, Value::BigInt(n)) => Ok(int_value(n.neg())),
+            (expr::UnaryOpTy::Tilde, Value::Int(n)) => Ok(Value::Int(!n)),
+            (expr::UnaryOpTy::Tilde, Value::BigInt(n)) => Ok(int_value(n.not())),
+            (expr::UnaryOpTy::Tilde, Value::Number(_)) => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!(
+                    "invalid application of unary op {:?} to object of type Number",
+                    op.toktype
+                ),
+            )
+            .at(op.line, op.col)),
             (expr::UnaryOpTy::Bang, _) => Ok(Value::Bool(!Interpreter::is_truthy(&val))),
-            (_, Value::String(_)) => Err(format!(
-                "invalid application of unary op {:?} to object of type String at line={},col={}",
-                op.toktype, op.line, op.col
-            )),
-            (_, Value::NativeFunction(_)) => Err(format!(
-                "invalid application of unary op {:?} to object of type NativeFunction at line={},col={}",
-                op.toktype, op.line, op.col
-            )),
-            (_, Value::LoxFunction(_, _, _)) => Err(format!(
-                "invalid application of unary op {:?} to object of type LoxFunction at line={},col={}",
-                op.toktype, op.line, op.col
-            )),
-            (_, Value::LoxClass(_, _)) => Err(format!(
-                "invalid application of unary op {:?} to object of type LoxClass at line={},col={}",
-                op.toktype, op.line, op.col
-            )),
-            (_, Value::LoxInstance(class_name, _)) => Err(format!(
-                "invalid application of unary op {:?} to object of type {:?} at line={},col={}",
-                class_name.name, op.toktype, op.line, op.col
-            )),
-            (expr::UnaryOpTy::Minus, Value::Bool(_)) => Err(format!(
-                "invalid application of unary op {:?} to object of type Bool at line={},col={}",
-                op.toktype, op.line, op.col
-            )),
-            (_, Value::Nil) => Err(format!(
-                "invalid application of unary op {:?} to nil at line={},col={}",
-                op.toktype, op.line, op.col
-            )),
-            (_, Value::List(_)) => Err(format!(
-                "invalid application of unary op {:?} to list at line={},col={}",
-                op.toktype, op.line, op.col
-            )),
-        }
-    }
-
-    fn is_truthy(val: &Value) -> bool {
-        match val {
-            Value::Nil => false,
+            (_, Value::String(_)) => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!(
+                    "invalid application of unary op {:?} to object of type String",
+                    op.toktype
+                ),
+            )
+            .at(op.line, op.col)),
+            (_, Value::NativeFunction(_)) => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!(
+                    "invalid application of unary op {:?} to object of type NativeFunction",
+                    op.toktype
+                ),
+            )
+            .at(op.line, op.col)),
+            (_, Value::NativeMethod(_)) => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!(
+                    "invalid application of unary op {:?} to object of type NativeMethod",
+                    op.toktype
+                ),
+            )
+            .at(op.line, op.col)),
+            (_, Value::LoxFunction(_, _, _)) => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!(
+                    "invalid application of unary op {:?} to object of type LoxFunction",
+                    op.toktype
+                ),
+            )
+            .at(op.line, op.col)),
+            (_, Value::LoxClass(_, _)) => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!(
+                    "invalid application of unary op {:?} to object of type LoxClass",
+                    op.toktype
+                ),
+            )
+            .at(op.line, op.col)),
+            (_, Value::LoxInstance(class_name, _)) => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!(
+                    "invalid application of unary op {:?} to object of type {:?}",
+                    class_name.name, op.toktype
+                ),
+            )
+            .at(op.line, op.col)),
+            (_, Value::Bool(_)) => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!(
+                    "invalid application of unary op {:?} to object of type Bool",
+                    op.toktype
+                ),
+            )
+            .at(op.line, op.col)),
+            (_, Value::Nil) => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!("invalid application of unary op {:?} to nil", op.toktype),
+            )
+            .at(op.line, op.col)),
+            (_, Value::List(_)) => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!("invalid application of unary op {:?} to list", op.toktype),
+            )
+            .at(op.line, op.col)),
+            (_, Value::Tuple(_)) => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!("invalid application of unary op {:?} to tuple", op.toktype),
+            )
+            .at(op.line, op.col)),
+            (_, Value::Dict(_)) => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!("invalid application of unary op {:?} to dict", op.toktype),
+            )
+            .at(op.line, op.col)),
+            (_, Value::Set(_)) => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!("invalid application of unary op {:?} to set", op.toktype),
+            )
+            .at(op.line, op.col)),
+            (_, Value::Range(..)) => Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                format!("invalid application of unary op {:?} to range", op.toktype),
+            )
+            .at(op.line, op.col)),
+        }
+    }
+
+    pub(crate) fn is_truthy(val: &Value) -> bool {
+        match val {
+            Value::Nil => false,
             Value::Bool(b) => *b,
             _ => true,
         }
@@ -1231,6 +3668,8 @@ impl Interpreter {
     fn interpret_literal(lit: &expr::Literal) -> Value {
         match lit {
             expr::Literal::Number(n) => Value::Number(*n),
+            expr::Literal::Int(n) => Value::Int(*n),
+            expr::Literal::BigInt(n) => Value::BigInt(n.clone()),
             expr::Literal::String(s) => Value::String(s.clone()),
             expr::Literal::True => Value::Bool(true),
             expr::Literal::False => Value::Bool(false),
@@ -1238,13 +3677,21 @@ impl Interpreter {
         }
     }
 
-    fn format_val(&self, val: &Value) -> String {
+    // 잡으면 message 필드에 키 자체가 들어감
+    pub(crate) fn key_error(&self, key: &Value) -> RuntimeError {
+        RuntimeError::new(ErrorKind::KeyError, self.format_val(key)).with_value(key.clone())
+    }
+
+    pub(crate) fn format_val(&self, val: &Value) -> String {
         match val {
-            Value::Number(n) => format!("{}", n),
+            Value::Number(n) => format_float(*n),
+            Value::Int(n) => format!("{}", n),
+            Value::BigInt(n) => format!("{}", n),
             Value::String(s) => format!("'{}'", s),
             Value::Bool(b) => format!("{}", b),
             Value::Nil => "nil".to_string(),
             Value::NativeFunction(func) => format!("NativeFunction({})", func.name),
+            Value::NativeMethod(method) => format!("NativeMethod({})", method.name),
             Value::LoxFunction(sym, _, _) => format!("LoxFunction({})", sym.name),
             Value::LoxClass(sym, _) => format!("LoxClass({})", sym.name),
             Value::LoxInstance(sym, _) => format!("LoxInstance({})", sym.name),
@@ -1261,6 +3708,1486 @@ impl Interpreter {
                 write!(&mut res, "]").unwrap();
                 res
             }
+            // 원소가 하나면 (1,) 로 씀
+            Value::Tuple(elts) => match elts.as_slice() {
+                [elt] => format!("({},)", self.format_val(elt)),
+                _ => format!(
+                    "({})",
+                    elts.iter()
+                        .map(|elt| self.format_val(elt))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            },
+            Value::Dict(dict_id) => {
+                let entries = self
+                    .get_dict(*dict_id)
+                    .entries()
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", self.format_val(key), self.format_val(value)))
+                    .collect::<Vec<_>>();
+                format!("{{{}}}", entries.join(", "))
+            }
+            // 빈 집합은 {}가 아니라 set()
+            Value::Set(set_id) => {
+                let items = self
+                    .get_set(*set_id)
+                    .items()
+                    .map(|item| self.format_val(item))
+                    .collect::<Vec<_>>();
+                if items.is_empty() {
+                    "set()".to_string()
+                } else {
+                    format!("{{{}}}", items.join(", "))
+                }
+            }
+            Value::Range(start, stop, step) => {
+                if *step == 1 {
+                    format!("range({}, {})", start, stop)
+                } else {
+                    format!("range({}, {}, {})", start, stop, step)
+                }
+            }
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extensions::Extensions;
+    use crate::lexer::{Dialect, Lexer};
+    use crate::parser;
+
+    // print로 나온 값들. 파싱이나 실행 중 에러면 그 메세지
+    fn run_in(dialect: Dialect, source: &str) -> Result<Vec<String>, String> {
+        let extensions = Extensions {
+            lists: true,
+            lambdas: true,
+            dialect,
+            ..Default::default()
+        };
+        run_with(extensions, source)
+    }
+
+    fn run_with(extensions: Extensions, source: &str) -> Result<Vec<String>, String> {
+        let lexer = Lexer::new(source, extensions.dialect);
+        let (stmts, errors) = parser::parse(extensions, source, lexer);
+        if let Some(err) = errors.first() {
+            return Err(err.to_string());
+        }
+        let mut interpreter = Interpreter::default();
+        interpreter.interpret(&stmts).map_err(|err| err.message)?;
+        Ok(interpreter.output)
+    }
+
+    fn run(source: &str) -> Vec<String> {
+        run_in(Dialect::Lox, source).unwrap()
+    }
+
+    fn run_python(source: &str) -> Vec<String> {
+        run_in(Dialect::Python, source).unwrap()
+    }
+
+    fn run_err(source: &str) -> String {
+        run_in(Dialect::Lox, source).unwrap_err()
+    }
+
+    #[test]
+    fn augmented_assignment() {
+        assert_eq!(
+            run("var x = 10; x += 5; x -= 3; x *= 2; x /= 4; print x;\n\
+                 var y = 7; y %= 4; y **= 3; print y;\n\
+                 var s = \"a\"; s += \"b\"; print s;"),
+            ["6.0", "27", "'ab'"]
+        );
+    }
+
+    #[test]
+    fn augmented_assignment_in_loops() {
+        assert_eq!(
+            run("var total = 0; for (var i = 0; i < 4; i += 1) { total += i; } print total;"),
+            ["6"]
+        );
+        assert_eq!(
+            run_python("n = 0\nwhile n < 5:\n    n += 2\nprint(n)\n"),
+            ["6"]
+        );
+    }
+
+    #[test]
+    fn augmented_assignment_to_attributes_and_subscripts() {
+        assert_eq!(
+            run("class A {}\n\
+                 var a = A(); a.n = 1; a.n += 2; print a.n;\n\
+                 var xs = [1, 2, 3]; var calls = [0];\n\
+                 def index() { calls[0] += 1; return 1; }\n\
+                 xs[index()] *= 10; print xs; print calls;"),
+            ["3", "[1, 20, 3]", "[1]"]
+        );
+    }
+
+    #[test]
+    fn modulo_floors_like_python() {
+        assert_eq!(
+            run("print -7 % 2; print 7 % -2; print 5.5 % 2; print -1.5 % 1;"),
+            ["1", "-1", "1.5", "0.5"]
+        );
+        assert_eq!(
+            run_err("print 1 % 0;"),
+            "ZeroDivisionError: modulo by zero at line=1,col=8"
+        );
+    }
+
+    #[test]
+    fn power_is_right_associative_and_binds_tighter_than_minus() {
+        assert_eq!(
+            run("print 2 ** 3 ** 2; print -2 ** 2; print 2 ** -1; print 10 % 3 * 2;"),
+            ["512", "-4", "0.5", "2"]
+        );
+    }
+
+    #[test]
+    fn floor_division_in_the_python_dialect() {
+        assert_eq!(
+            run_python("print(-7 // 2)\nprint(-7.5 // 2)\nx = 17\nx //= 5\nprint(x)\n"),
+            ["-4", "-4.0", "3"]
+        );
+        // Lox 방언에서 //는 주석
+        assert_eq!(run("print 7 // 2;\n;"), ["7"]);
+    }
+
+    #[test]
+    fn attribute_access_on_instances() {
+        assert_eq!(
+            run("class A { init(x) { this.x = x; } get() { return this.x; } }\n\
+                 class B < A { init(x) { this.x = x; } get() { return super.get() + 1; } }\n\
+                 var b = B(4);\n\
+                 print b.x;\n\
+                 b.x = 10;\n\
+                 print b.get();\n\
+                 var m = b.get;\n\
+                 print m();"),
+            ["4", "11", "11"]
+        );
+    }
+
+    #[test]
+    fn builtin_values_dispatch_methods() {
+        assert_eq!(
+            run("print \"abc\".upper();\n\
+                 var l = [1];\n\
+                 l.append(2);\n\
+                 print l;\n\
+                 var push = l.append;\n\
+                 push(3);\n\
+                 print [3, 1].copy().count(3);\n\
+                 print l;"),
+            ["'ABC'", "[1, 2]", "1", "[1, 2, 3]"]
+        );
+    }
+
+    #[test]
+    fn missing_attributes_are_errors() {
+        assert_eq!(
+            run_err("class B {}\nprint B().nope;"),
+            "AttributeError: 'B' instance has no 'nope' attribute. at line=2,col=14"
+        );
+        assert_eq!(
+            run_err("print \"a\".nope();"),
+            "AttributeError: String value has no 'nope' attribute. at line=1,col=13"
+        );
+        assert_eq!(
+            run_err("var x = 1;\nx.y = 2;"),
+            "TypeError: Only LoxInstance values have attributes. Found Int. at line=2,col=3"
+        );
+    }
+
+    #[test]
+    fn list_methods_mutate_in_place() {
+        assert_eq!(
+            run("var l = [3, 1, 2];\n\
+                 l.extend([5, 4]);\n\
+                 l.insert(0, 9);\n\
+                 l.insert(-1, 7);\n\
+                 l.insert(100, 8);\n\
+                 print l;\n\
+                 print l.pop();\n\
+                 print l.pop(0);\n\
+                 print l.pop(-2);\n\
+                 l.remove(1);\n\
+                 print l;\n\
+                 l.reverse();\n\
+                 print l;\n\
+                 l.clear();\n\
+                 print l;\n\
+                 print [1].append(2);"),
+            ["[9, 3, 1, 2, 5, 7, 4, 8]", "8", "9", "7", "[3, 2, 5, 4]", "[4, 5, 2, 3]", "[]", "nil"]
+        );
+    }
+
+    #[test]
+    fn list_index_count_and_copy() {
+        assert_eq!(
+            run("var l = [1, 2, 1];\n\
+                 print l.index(2);\n\
+                 print l.count(1);\n\
+                 var c = l.copy();\n\
+                 c.append(0);\n\
+                 print l;\n\
+                 print c;"),
+            ["1", "2", "[1, 2, 1]", "[1, 2, 1, 0]"]
+        );
+    }
+
+    #[test]
+    fn list_sort_takes_key_and_reverse() {
+        assert_eq!(
+            run("var l = [3, 1.5, 2];\n\
+                 l.sort();\n\
+                 print l;\n\
+                 l.sort(reverse=true);\n\
+                 print l;\n\
+                 var w = [\"bb\", \"a\", \"ccc\"];\n\
+                 w.sort(key=len, reverse=true);\n\
+                 print w;"),
+            ["[1.5, 2, 3]", "[3, 2, 1.5]", "['ccc', 'bb', 'a']"]
+        );
+    }
+
+    #[test]
+    fn list_sort_compares_tuples_and_lists_by_element() {
+        assert_eq!(
+            run_python("d = {\"b\": 2, \"a\": 1, \"c\": 0}\n\
+                        items = [kv for kv in d.items()]\n\
+                        items.sort()\n\
+                        print(items)\n\
+                        xs = [[2, 1], [1, 5, 0], [1, 5], [0]]\n\
+                        xs.sort(reverse=True)\n\
+                        print(xs)\n"),
+            [
+                "[('a', 1), ('b', 2), ('c', 0)]",
+                "[[2, 1], [1, 5, 0], [1, 5], [0]]"
+            ]
+        );
+        assert_eq!(
+            run_err("[(1, 2), (1, \"a\")].sort();"),
+            "TypeError: '<' not supported between String and Int at line=1,col=24"
+        );
+    }
+
+    #[test]
+    fn list_sort_puts_nan_last() {
+        assert_eq!(
+            run_python("nan = 10.0 ** 400 - 10.0 ** 400\n\
+                        ys = [3, nan, 1, 2.5, nan, -1]\n\
+                        ys.sort()\n\
+                        print(ys)\n\
+                        ys.sort(reverse=True)\n\
+                        print(ys)\n"),
+            ["[-1, 1, 2.5, 3, nan, nan]", "[nan, nan, 3, 2.5, 1, -1]"]
+        );
+    }
+
+    #[test]
+    fn list_method_errors() {
+        assert_eq!(
+            run_err("print [].pop();"),
+            "IndexError: pop from empty list at line=1,col=13"
+        );
+        assert_eq!(
+            run_err("print [1].pop(5);"),
+            "IndexError: pop index out of range at line=1,col=15"
+        );
+        assert_eq!(
+            run_err("[1].remove(2);"),
+            "ValueError: list.remove(x): x not in list at line=1,col=12"
+        );
+        assert_eq!(
+            run_err("[1].index(2);"),
+            "ValueError: 2 is not in list at line=1,col=11"
+        );
+        assert_eq!(
+            run_err("[1, \"a\"].sort();"),
+            "TypeError: '<' not supported between String and Int at line=1,col=14"
+        );
+        assert_eq!(
+            run_err("[1].sort(foo=1);"),
+            "TypeError: sort() got an unexpected keyword argument 'foo' at line=1,col=14"
+        );
+    }
+
+    #[test]
+    fn elif_chains() {
+        assert_eq!(
+            run("for (var i = 0; i < 4; i = i + 1) {\n\
+                   if (i == 0) { print \"zero\"; }\n\
+                   elif (i == 1) print \"one\";\n\
+                   elif (i == 2) { print \"two\"; }\n\
+                   else { print \"many\"; }\n\
+                 }\n\
+                 if (false) print 1; elif (false) print 2;\n\
+                 print 3;"),
+            ["'zero'", "'one'", "'two'", "'many'", "3"]
+        );
+    }
+
+    #[test]
+    fn elif_chains_in_the_python_dialect() {
+        assert_eq!(
+            run_python("def sign(x):\n\
+                        \x20   if x < 0:\n\
+                        \x20       return \"neg\"\n\
+                        \x20   elif x == 0:\n\
+                        \x20       return \"zero\"\n\
+                        \x20   elif x < 10: return \"small\"\n\
+                        \x20   else:\n\
+                        \x20       return \"big\"\n\
+                        print(sign(-1))\n\
+                        print(sign(0))\n\
+                        print(sign(3))\n\
+                        print(sign(30))\n\
+                        if False: print(1)\n\
+                        elif True: print(2)\n"),
+            ["'neg'", "'zero'", "'small'", "'big'", "2"]
+        );
+    }
+
+    #[test]
+    fn for_in_loops_over_builtin_iterables() {
+        assert_eq!(
+            run_python("for x in [1, 2]:\n\
+                        \x20   print(x)\n\
+                        for c in \"ab\":\n\
+                        \x20   print(c)\n\
+                        for i in range(3):\n\
+                        \x20   print(i)\n\
+                        for v in (7,):\n\
+                        \x20   print(v)\n\
+                        print(x)\n"),
+            ["1", "2", "'a'", "'b'", "0", "1", "2", "7", "2"]
+        );
+    }
+
+    #[test]
+    fn ranges_at_the_ends_of_int() {
+        assert_eq!(
+            run_python("for x in range(9223372036854775800, 9223372036854775807, 5):\n\
+                        \x20   print(x)\n\
+                        for x in range(-9223372036854775800, -9223372036854775807 - 1, -5):\n\
+                        \x20   print(x)\n\
+                        print(len(range(-9223372036854775807, 9223372036854775807, 2)))\n"),
+            [
+                "9223372036854775800",
+                "9223372036854775805",
+                "-9223372036854775800",
+                "-9223372036854775805",
+                "9223372036854775807"
+            ]
+        );
+        assert_eq!(
+            run_err("print len(range(-9223372036854775807, 9223372036854775807));"),
+            "OverflowError: range has too many elements for len() at line=1,col=58"
+        );
+    }
+
+    #[test]
+    fn for_in_sees_items_appended_during_the_loop() {
+        assert_eq!(
+            run("var xs = [1, 2];\n\
+                 for x in xs { xs.append(x); if (len(xs) > 5) break; }\n\
+                 print xs;"),
+            ["[1, 2, 1, 2, 1, 2]"]
+        );
+    }
+
+    #[test]
+    fn for_in_over_user_iterables() {
+        assert_eq!(
+            run("class Count {\n\
+                   init(n) { this.n = n; }\n\
+                   __iter__() { return this; }\n\
+                   __next__() {\n\
+                     if (this.n == 0) return nil;\n\
+                     this.n = this.n - 1;\n\
+                     return this.n;\n\
+                   }\n\
+                 }\n\
+                 for n in Count(3) print n;\n\
+                 print [n for n in Count(2)];"),
+            ["2", "1", "0", "[1, 0]"]
+        );
+        assert_eq!(
+            run_err("class A {}\nfor x in A() {}"),
+            "TypeError: LoxInstance value is not iterable"
+        );
+        assert_eq!(run_err("for x in 5 {}"), "TypeError: Int value is not iterable");
+    }
+
+    #[test]
+    fn enumerate_and_zip() {
+        assert_eq!(
+            run_python("for i, v in enumerate([\"a\", \"b\"]):\n\
+                        \x20   print(i)\n\
+                        \x20   print(v)\n\
+                        for i, v in enumerate(\"c\", 5):\n\
+                        \x20   print(i)\n\
+                        for a, b in zip([1, 2, 3], \"xy\"):\n\
+                        \x20   print((a, b))\n\
+                        print([p for p in zip()])\n\
+                        print([p for p in zip([1], [2], [3])])\n"),
+            ["0", "'a'", "1", "'b'", "5", "(1, 'x')", "(2, 'y')", "[]", "[(1, 2, 3)]"]
+        );
+    }
+
+    #[test]
+    fn break_and_continue() {
+        assert_eq!(
+            run("for (var i = 0; i < 5; i = i + 1) {\n\
+                   if (i == 1) continue;\n\
+                   if (i == 3) break;\n\
+                   print i;\n\
+                 }\n\
+                 var j = 0;\n\
+                 while (true) { j = j + 1; if (j < 3) continue; break; }\n\
+                 print j;"),
+            ["0", "2", "3"]
+        );
+    }
+
+    #[test]
+    fn break_leaves_only_the_innermost_loop() {
+        assert_eq!(
+            run_python("for x in [1, 2]:\n\
+                        \x20   for y in [1, 2]:\n\
+                        \x20       if y == 2:\n\
+                        \x20           break\n\
+                        \x20       print(x * 10 + y)\n\
+                        \x20   print(-x)\n\
+                        print(x)\n"),
+            ["11", "-1", "21", "-2", "2"]
+        );
+    }
+
+    #[test]
+    fn continue_runs_finally_blocks() {
+        assert_eq!(
+            run("var n = 0;\n\
+                 while (n < 2) { n = n + 1; try { continue; } finally { print n; } }\n\
+                 for x in [1] { try { break; } finally { print \"f\"; } }"),
+            ["1", "2", "'f'"]
+        );
+    }
+
+    #[test]
+    fn break_and_continue_outside_loops() {
+        assert_eq!(
+            run_err("break;"),
+            "break statement not enclosed in a loop at line=1,col=4"
+        );
+        assert_eq!(
+            run_err("while (true) { def f() { continue; } }"),
+            "continue statement not enclosed in a loop at line=1,col=32"
+        );
+    }
+
+    #[test]
+    fn runtime_errors_are_std_errors() {
+        let err: Box<dyn std::error::Error> = Box::new(
+            RuntimeError::new(ErrorKind::ValueError, "bad value").at(3, 7),
+        );
+        assert_eq!(err.to_string(), "ValueError: bad value at line=3,col=7");
+        let err: Box<dyn std::error::Error> = Box::new(Error {
+            message: "boom".to_string(),
+            backtrace: Vec::new(),
+        });
+        assert_eq!(err.to_string(), "boom");
+    }
+
+    #[test]
+    fn slicing_clamps_like_python() {
+        assert_eq!(
+            run_python("xs = [0, 1, 2, 3, 4]\n\
+                        print(xs[1:3])\n\
+                        print(xs[::-1])\n\
+                        print(xs[:-2])\n\
+                        print(xs[-100:100])\n\
+                        print(xs[::2])\n\
+                        print(xs[3:1])\n\
+                        print(xs[4:0:-2])\n\
+                        print(xs[None:2])\n"),
+            [
+                "[1, 2]",
+                "[4, 3, 2, 1, 0]",
+                "[0, 1, 2]",
+                "[0, 1, 2, 3, 4]",
+                "[0, 2, 4]",
+                "[]",
+                "[4, 2]",
+                "[0, 1]",
+            ]
+        );
+    }
+
+    #[test]
+    fn slicing_strings_and_tuples() {
+        assert_eq!(
+            run_python("print(\"héllo\"[1:4])\nprint(\"hello\"[::-1])\nprint((1, 2, 3)[1:])\n"),
+            ["'éll'", "'olleh'", "(2, 3)"]
+        );
+    }
+
+    #[test]
+    fn len_counts_characters_of_strings() {
+        assert_eq!(
+            run("var s = \"héllo\"; print len(s); print s[len(s) - 1];"),
+            ["5", "'o'"]
+        );
+    }
+
+    #[test]
+    fn slice_assignment_and_del() {
+        assert_eq!(
+            run_python("ys = [0, 1, 2, 3, 4]\n\
+                        ys[1:3] = [9]\n\
+                        print(ys)\n\
+                        ys[:0] = (7, 8)\n\
+                        print(ys)\n\
+                        ys[::2] = [0, 0, 0]\n\
+                        print(ys)\n\
+                        del ys[0]\n\
+                        print(ys)\n\
+                        del ys[1:3]\n\
+                        print(ys)\n\
+                        del ys[-1]\n\
+                        print(ys)\n\
+                        zs = [0, 1, 2, 3]\n\
+                        del zs[::2]\n\
+                        print(zs)\n"),
+            [
+                "[0, 9, 3, 4]",
+                "[7, 8, 0, 9, 3, 4]",
+                "[0, 8, 0, 9, 0, 4]",
+                "[8, 0, 9, 0, 4]",
+                "[8, 0, 4]",
+                "[8, 0]",
+                "[1, 3]",
+            ]
+        );
+    }
+
+    #[test]
+    fn slice_errors() {
+        assert_eq!(
+            run_err("var xs = [1, 2, 3];\nxs[::2] = [1];"),
+            "ValueError: attempt to assign sequence of size 1 to extended slice of size 2 at line=2,col=7"
+        );
+        assert_eq!(
+            run_err("print [1][::0];"),
+            "ValueError: slice step cannot be zero at line=1,col=13"
+        );
+        assert_eq!(
+            run_err("print [1][\"a\":];"),
+            "TypeError: slice indices must be integers or nil, found String at line=1,col=14"
+        );
+        assert_eq!(
+            run_err("var xs = [1];\ndel xs[3];"),
+            "IndexError: List subscript index out of range at line=2,col=9"
+        );
+        assert_eq!(
+            run_err("var s = \"ab\";\ns[0] = \"c\";"),
+            "TypeError: Invalid value of type String in setitem expr. at line=2,col=4"
+        );
+    }
+
+    #[test]
+    fn indices_at_the_ends_of_int() {
+        assert_eq!(
+            run_python("xs = [1, 2, 3]\n\
+                        print(xs[1::9223372036854775807])\n\
+                        print(xs[::-9223372036854775807 - 1])\n\
+                        print(xs[-9223372036854775807 - 1:9223372036854775807])\n\
+                        print(xs[9223372036854775807:-9223372036854775807 - 1:-1])\n\
+                        print(\"abc\"[2::9223372036854775807])\n\
+                        xs[1::9223372036854775807] = [9]\n\
+                        print(xs)\n"),
+            ["[2]", "[3]", "[1, 2, 3]", "[3, 2, 1]", "'c'", "[1, 9, 3]"]
+        );
+        assert_eq!(
+            run_err("var xs = [1, 2, 3];\nprint xs[-9223372036854775807 - 1];"),
+            "IndexError: List subscript index out of range at line=2,col=34"
+        );
+    }
+
+    #[test]
+    fn dicts_keep_insertion_order() {
+        assert_eq!(
+            run_python("d = {\"b\": 1, \"a\": 2, 3: \"x\", (1, 2): None}\n\
+                        d[\"c\"] = 5\n\
+                        d[\"b\"] = 10\n\
+                        print(d)\n\
+                        print(d[\"a\"])\n\
+                        print(len(d))\n\
+                        for k in d:\n\
+                        \x20   print(k)\n\
+                        del d[\"a\"]\n\
+                        print(d)\n\
+                        print({1: 1, 1.0: 2})\n\
+                        print({})\n"),
+            [
+                "{'b': 10, 'a': 2, 3: 'x', (1, 2): nil, 'c': 5}",
+                "2",
+                "5",
+                "'b'",
+                "'a'",
+                "3",
+                "(1, 2)",
+                "'c'",
+                "{'b': 10, 3: 'x', (1, 2): nil, 'c': 5}",
+                "{1: 2}",
+                "{}",
+            ]
+        );
+    }
+
+    #[test]
+    fn dict_membership_and_methods() {
+        assert_eq!(
+            run_python("d = {\"a\": 1, \"b\": 2}\n\
+                        print(\"a\" in d)\n\
+                        print(1 in d)\n\
+                        print(d.keys())\n\
+                        print(d.values())\n\
+                        print(d.items())\n\
+                        print(d.get(\"z\"))\n\
+                        print(d.get(\"z\", 7))\n\
+                        print(d.pop(\"a\"))\n\
+                        print(d.pop(\"a\", 0))\n\
+                        d.update({\"b\": 3, \"c\": 4})\n\
+                        print(d)\n\
+                        print(d == {\"c\": 4, \"b\": 3})\n"),
+            [
+                "true",
+                "false",
+                "['a', 'b']",
+                "[1, 2]",
+                "[('a', 1), ('b', 2)]",
+                "nil",
+                "7",
+                "1",
+                "0",
+                "{'b': 3, 'c': 4}",
+                "true",
+            ]
+        );
+    }
+
+    #[test]
+    fn integral_float_keys_match_int_keys() {
+        assert_eq!(
+            run_python("d = {}\n\
+                        d[-9223372036854775808] = \"int\"\n\
+                        d[-9223372036854775808.0] = \"float\"\n\
+                        d[9223372036854775808.0] = \"big\"\n\
+                        print(len(d))\n\
+                        print(d[-9223372036854775807 - 1])\n\
+                        print(9223372036854775808 in d)\n"),
+            ["2", "'float'", "true"]
+        );
+    }
+
+    #[test]
+    fn dict_errors() {
+        assert_eq!(run_err("print {}[\"k\"];"), "KeyError: 'k' at line=1,col=12");
+        assert_eq!(
+            run_err("print {}.pop(\"k\");"),
+            "KeyError: 'k' at line=1,col=16"
+        );
+        assert_eq!(
+            run_err("var d = {};\nd[[1]] = 1;"),
+            "TypeError: unhashable type: List at line=2,col=6"
+        );
+        assert_eq!(
+            run_err("print [1] in {};"),
+            "TypeError: unhashable type: List at line=1,col=11"
+        );
+        assert_eq!(run_err("print {[1]: 2};"), "TypeError: unhashable type: List");
+    }
+
+    #[test]
+    fn tuple_literals() {
+        assert_eq!(
+            run_python("t = (1, 2)\n\
+                        print(t)\n\
+                        print((1,))\n\
+                        print(())\n\
+                        print((1))\n\
+                        print(t == (1, 2))\n\
+                        print(t[1])\n\
+                        print(len(t))\n\
+                        print(t + (3,))\n"),
+            ["(1, 2)", "(1,)", "()", "1", "true", "2", "2", "(1, 2, 3)"]
+        );
+        assert_eq!(
+            run_err("var t = (1, 2);\nt[0] = 5;"),
+            "TypeError: Invalid value of type Tuple in setitem expr. at line=2,col=4"
+        );
+    }
+
+    #[test]
+    fn destructuring_assignment() {
+        assert_eq!(
+            run_python("a = 1\n\
+                        b = 2\n\
+                        a, b = b, a\n\
+                        print((a, b))\n\
+                        a, (b, c) = 1, (2, 3)\n\
+                        print((a, b, c))\n\
+                        def pair():\n\
+                        \x20   return 5, 6\n\
+                        a, b = pair()\n\
+                        print(b)\n\
+                        xs = [0, 0]\n\
+                        xs[0], xs[1] = 1, 2\n\
+                        print(xs)\n\
+                        for i, (j, k) in [(1, (2, 3))]:\n\
+                        \x20   print(i + j + k)\n"),
+            ["(2, 1)", "(1, 2, 3)", "6", "[1, 2]", "6"]
+        );
+    }
+
+    #[test]
+    fn starred_targets_collect_the_rest() {
+        assert_eq!(
+            run_python("first, *rest = [1, 2, 3]\n\
+                        print(first)\n\
+                        print(rest)\n\
+                        *rest, first = \"abc\"\n\
+                        print(rest)\n\
+                        print(first)\n\
+                        first, *rest = (1,)\n\
+                        print(rest)\n"),
+            ["1", "[2, 3]", "['a', 'b']", "'c'", "[]"]
+        );
+    }
+
+    #[test]
+    fn destructuring_length_mismatch() {
+        assert_eq!(
+            run_err("var a;\nvar b;\na, b = 1, 2, 3;"),
+            "ValueError: too many values to unpack (expected 2)"
+        );
+        assert_eq!(
+            run_err("var a;\nvar b;\na, b, a = 1, 2;"),
+            "ValueError: not enough values to unpack (expected 3, got 2)"
+        );
+        assert_eq!(
+            run_err("var a;\nvar b;\na, *b, a = [1];"),
+            "ValueError: not enough values to unpack (expected at least 2, got 1)"
+        );
+        assert_eq!(
+            run_err("var a;\n*a, *a = [1];"),
+            "invalid assignment target at line=2,col=8"
+        );
+    }
+
+    #[test]
+    fn parameters_bind_like_python() {
+        assert_eq!(
+            run_python("def f(a, b=2, *args, c, d=4, **kw):\n\
+                        \x20   print((a, b, args, c, d, kw))\n\
+                        f(1, c=3)\n\
+                        f(1, 5, 6, 7, c=3, e=9)\n\
+                        f(*[1, 2, 3], **{\"c\": 0, \"z\": 1})\n\
+                        f(d=0, c=1, a=2)\n"),
+            [
+                "(1, 2, (), 3, 4, {})",
+                "(1, 5, (6, 7), 3, 4, {'e': 9})",
+                "(1, 2, (3,), 0, 4, {'z': 1})",
+                "(2, 2, (), 1, 0, {})",
+            ]
+        );
+    }
+
+    #[test]
+    fn defaults_are_evaluated_once_at_definition() {
+        assert_eq!(
+            run_python("def g(x=[]):\n\
+                        \x20   x.append(1)\n\
+                        \x20   return x\n\
+                        g()\n\
+                        print(g())\n\
+                        n = 10\n\
+                        def h(a=n):\n\
+                        \x20   return a\n\
+                        n = 20\n\
+                        print(h())\n"),
+            ["[1, 1]", "10"]
+        );
+    }
+
+    #[test]
+    fn keywords_reach_initializers_and_lambdas() {
+        assert_eq!(
+            run("class P { init(x, y=0) { this.s = x + y; } }\n\
+                 print P(1, y=2).s;\n\
+                 print P(x=5).s;\n\
+                 var l = lambda (a, b=1) { return a + b; };\n\
+                 print l(1);\n\
+                 print l(1, b=5);"),
+            ["3", "5", "2", "6"]
+        );
+    }
+
+    #[test]
+    fn binding_errors_name_the_parameter() {
+        assert_eq!(
+            run_err("def f(a, b) {}\nf(1);"),
+            "TypeError: f() missing 1 required positional argument: 'b' at line=2,col=4"
+        );
+        assert_eq!(
+            run_err("def f(a) {}\nf(1, 2);"),
+            "TypeError: f() takes 1 positional argument but 2 were given at line=2,col=7"
+        );
+        assert_eq!(
+            run_err("def f(a) {}\nf(b=1);"),
+            "TypeError: f() got an unexpected keyword argument 'b' at line=2,col=6"
+        );
+        assert_eq!(
+            run_err("def f(a) {}\nf(1, a=2);"),
+            "TypeError: f() got multiple values for argument 'a' at line=2,col=9"
+        );
+        assert_eq!(
+            run_err("def f(*, a) {}\nf();"),
+            "TypeError: f() missing 1 required keyword-only argument: 'a' at line=2,col=3"
+        );
+        assert_eq!(
+            run_err("print len(1, 2);"),
+            "TypeError: len() takes 1 positional argument but 2 were given at line=1,col=14"
+        );
+        assert_eq!(
+            run_err("def f(**kw) {}\nf(**{1: 2});"),
+            "TypeError: f() keywords must be strings at line=2,col=11"
+        );
+    }
+
+    #[test]
+    fn bad_parameter_lists_are_parse_errors() {
+        assert_eq!(
+            run_err("def f(a=1, b) {}"),
+            "non-default argument follows default argument at line=1,col=11"
+        );
+        assert_eq!(
+            run_err("def f(a, a) {}"),
+            "duplicate argument 'a' in function definition at line=1,col=9"
+        );
+        assert_eq!(
+            run_err("def f(**k, a) {}"),
+            "parameter follows **kwargs at line=1,col=11"
+        );
+        assert_eq!(
+            run_err("def f(a, b) {}\nf(a=1, 2);"),
+            "Positional argument follows keyword argument. Line=2,col=8"
+        );
+    }
+
+    #[test]
+    fn conditional_expressions() {
+        assert_eq!(
+            run("def boom() { raise ValueError(\"evaluated\"); }\n\
+                 print true ? 1 : boom();\n\
+                 print false ? boom() : 2;\n\
+                 print false ? 1 : false ? 2 : 3;\n\
+                 print 1 + 1 == 2 ? \"y\" : \"n\";\n\
+                 var a = nil;\n\
+                 a = true ? 4 : 5;\n\
+                 print a;"),
+            ["1", "2", "3", "'y'", "4"]
+        );
+    }
+
+    #[test]
+    fn conditional_expressions_in_the_python_dialect() {
+        assert_eq!(
+            run_python("def boom():\n\
+                        \x20   raise ValueError(\"evaluated\")\n\
+                        print(1 if True else boom())\n\
+                        print(boom() if False else 2)\n\
+                        print(1 if False else 2 if False else 3)\n\
+                        print(False or 1 if False or None else 7)\n\
+                        x = 0 if False else 5\n\
+                        print(x)\n"),
+            ["1", "2", "3", "7", "5"]
+        );
+    }
+
+    #[test]
+    fn conditional_syntax_depends_on_the_dialect() {
+        assert_eq!(
+            run_err("print 1 if 2 else 3;"),
+            "expected `;` after statement, found `if` at line=1,col=9"
+        );
+        assert_eq!(
+            run_in(Dialect::Python, "print(True ? 1 : 2)\n").unwrap_err(),
+            "expected one of `,`, `)` after expression, found `?` at line=1,col=11"
+        );
+    }
+
+    #[test]
+    fn comprehensions() {
+        assert_eq!(
+            run_python("xs = [1, 2, 3, 4]\n\
+                        print([x * x for x in xs if x % 2 == 0])\n\
+                        print([(x, y) for x in [1, 2] for y in \"ab\" if x != 2 or y != \"a\"])\n\
+                        print([[y for y in range(x)] for x in range(3)])\n\
+                        print({x: x * 10 for x in xs if x > 2})\n\
+                        print({x % 2 for x in xs})\n"),
+            [
+                "[4, 16]",
+                "[(1, 'a'), (1, 'b'), (2, 'b')]",
+                "[[], [0], [0, 1]]",
+                "{3: 30, 4: 40}",
+                "{1, 0}",
+            ]
+        );
+    }
+
+    #[test]
+    fn comprehension_variables_do_not_leak() {
+        assert_eq!(
+            run_python("x = \"outer\"\nprint([x for x in [1, 2]])\nprint(x)\n"),
+            ["[1, 2]", "'outer'"]
+        );
+        assert_eq!(
+            run_err("print [z for z in [1]];\nprint z;"),
+            "NameError: Use of undefined variable 'z' at line=2,col=7"
+        );
+    }
+
+    #[test]
+    fn set_iteration_follows_insertion_order() {
+        assert_eq!(
+            run_python("s = {1, 2, 3, 4, 5}\n\
+                        s.remove(2)\n\
+                        s.discard(4)\n\
+                        s.add(2)\n\
+                        s.add(3)\n\
+                        for e in s:\n\
+                        \x20   print(e)\n\
+                        print(s)\n\
+                        print(len(s))\n\
+                        print(3 in s)\n\
+                        print({1, 2} == {2, 1})\n\
+                        print(set())\n"),
+            ["1", "3", "5", "2", "{1, 3, 5, 2}", "4", "true", "true", "set()"]
+        );
+    }
+
+    #[test]
+    fn membership() {
+        assert_eq!(
+            run_python("print(2 in [1, 2])\n\
+                        print(3 not in [1, 2])\n\
+                        print(\"ell\" in \"hello\")\n\
+                        print(\"\" in \"a\")\n\
+                        print(\"k\" in {\"k\": 1})\n\
+                        print(1 in (1, 2))\n\
+                        print(not 1 in [1])\n"),
+            ["true", "true", "true", "true", "true", "true", "false"]
+        );
+    }
+
+    #[test]
+    fn membership_in_ranges_is_computed() {
+        assert_eq!(
+            run_python("print(5 in range(0, 10, 5))\n\
+                        print(10 in range(0, 10, 5))\n\
+                        print(3 in range(0, 10, 5))\n\
+                        print(4 in range(10, 0, -2))\n\
+                        print(0 in range(10, 0, -2))\n\
+                        print(5.0 in range(6))\n\
+                        print(\"a\" in range(3))\n"),
+            ["true", "false", "false", "true", "false", "true", "false"]
+        );
+    }
+
+    #[test]
+    fn membership_in_huge_ranges_never_iterates() {
+        assert_eq!(
+            run_python("print(2**70 in range(10**18))\n\
+                        print(4.0 in range(0, 10**18, 2))\n\
+                        print(5.0 in range(0, 10**18, 2))\n\
+                        print(4.5 in range(10**18))\n\
+                        print(2.0**70 in range(10**18))\n\
+                        print(-9223372036854775808.0 in range(-9223372036854775807 - 1, 0))\n"),
+            ["false", "true", "false", "false", "false", "true"]
+        );
+    }
+
+    #[test]
+    fn membership_in_user_classes() {
+        assert_eq!(
+            run("class Box { __contains__(x) { return x == 42; } }\n\
+                 print 42 in Box();\n\
+                 print 41 not in Box();\n\
+                 class Count {\n\
+                   init() { this.n = 3; }\n\
+                   __iter__() { return this; }\n\
+                   __next__() { this.n = this.n - 1; return this.n < 0 ? nil : this.n; }\n\
+                 }\n\
+                 print 1 in Count();\n\
+                 print 7 in Count();"),
+            ["true", "true", "true", "false"]
+        );
+        assert_eq!(
+            run_err("class O {}\nprint 1 in O();"),
+            "TypeError: LoxInstance value is not iterable at line=2,col=10"
+        );
+        assert_eq!(
+            run_err("print 1 in \"a\";"),
+            "TypeError: 'in <string>' requires string as left operand, not Int at line=1,col=9"
+        );
+    }
+
+    #[test]
+    fn identity() {
+        assert_eq!(
+            run_python("a = [1]\n\
+                        b = [1]\n\
+                        c = a\n\
+                        print(a == b)\n\
+                        print(a is b)\n\
+                        print(a is not b)\n\
+                        print(c is a)\n\
+                        print(None is None)\n\
+                        print(1 is not None)\n"),
+            ["true", "false", "true", "true", "true", "true"]
+        );
+    }
+
+    #[test]
+    fn chained_comparisons() {
+        assert_eq!(
+            run_python("print(1 < 2 > 0 == 0 != 1)\n\
+                        print(1 < 2 < 2)\n\
+                        print(1 == 1 in [1])\n\
+                        print(1 < 3 is not None)\n"),
+            ["true", "false", "true", "true"]
+        );
+    }
+
+    #[test]
+    fn chained_comparisons_evaluate_operands_once_and_short_circuit() {
+        assert_eq!(
+            run_python("calls = [0]\n\
+                        def mid():\n\
+                        \x20   calls[0] += 1\n\
+                        \x20   return 5\n\
+                        print(0 <= mid() < 10)\n\
+                        print(calls[0])\n\
+                        print(10 < mid() < boom())\n\
+                        print(calls[0])\n"),
+            ["true", "1", "false", "2"]
+        );
+    }
+
+    #[test]
+    fn comparisons_do_not_chain_in_lox() {
+        assert_eq!(
+            run_err("print 1 < 2 < 3;"),
+            "TypeError: invalid operands in binary operator Less of type Bool and Int at line=1,col=12"
+        );
+    }
+
+    #[test]
+    fn bitwise_operators() {
+        assert_eq!(
+            run("print 6 & 3;\n\
+                 print 6 | 3;\n\
+                 print 6 ^ 3;\n\
+                 print ~5;\n\
+                 print -5 & 255;\n\
+                 print 1 | 2 ^ 3 & 4;"),
+            ["2", "7", "5", "-6", "251", "3"]
+        );
+    }
+
+    #[test]
+    fn shifts() {
+        assert_eq!(
+            run("print 1 << 4;\n\
+                 print -16 >> 2;\n\
+                 print -1 >> 100;\n\
+                 print 1 + 2 << 1;\n\
+                 print 1 << 63;"),
+            ["16", "-4", "-1", "6", "9223372036854775808"]
+        );
+        assert_eq!(
+            run_err("print 1 << -1;"),
+            "ValueError: negative shift count at line=1,col=9"
+        );
+        assert_eq!(
+            run_err("print 1 >> -1;"),
+            "ValueError: negative shift count at line=1,col=9"
+        );
+    }
+
+    #[test]
+    fn bitwise_augmented_assignment() {
+        assert_eq!(
+            run_python("x = 12\n\
+                        x &= 10\n\
+                        print(x)\n\
+                        x |= 1\n\
+                        print(x)\n\
+                        x ^= 3\n\
+                        print(x)\n\
+                        x <<= 2\n\
+                        print(x)\n\
+                        x >>= 1\n\
+                        print(x)\n"),
+            ["8", "9", "10", "40", "20"]
+        );
+    }
+
+    #[test]
+    fn bitwise_operators_need_integers() {
+        assert_eq!(
+            run_err("print 1.5 & 1;"),
+            "TypeError: invalid operands in binary operator Amp of type Number and Int at line=1,col=10"
+        );
+        assert_eq!(
+            run_err("print ~1.5;"),
+            "TypeError: invalid application of unary op Tilde to object of type Number at line=1,col=6"
+        );
+    }
+
+    #[test]
+    fn ints_and_floats_stay_distinct() {
+        assert_eq!(
+            run("print 7 / 2;\n\
+                 print 6 / 2;\n\
+                 print 1 + 2.0;\n\
+                 print 1 == 1.0;\n\
+                 print int(3.9);\n\
+                 print int(-3.9);\n\
+                 print float(2);\n\
+                 print int(\"42\");\n\
+                 print int(2.0 ** 70);"),
+            ["3.5", "3.0", "3.0", "true", "3", "-3", "2.0", "42", "1180591620717411303424"]
+        );
+        assert_eq!(
+            run_err("print int(float(\"inf\"));"),
+            "ValueError: cannot convert float inf to integer at line=1,col=22"
+        );
+    }
+
+    #[test]
+    fn int_float_comparisons_are_exact() {
+        assert_eq!(
+            run("print 2 ** 53 + 1 == 2.0 ** 53;\n\
+                 print 2 ** 53 + 1 > 2.0 ** 53;\n\
+                 print 2 ** 53 < 2.0 ** 53 + 2;\n\
+                 print 10 ** 20 == 100000000000000000000.0;\n\
+                 print 10 ** 400 > 2.0 ** 1000;\n\
+                 print -(2 ** 63) == -9223372036854775808.0;\n\
+                 print 2 ** 63 > 9223372036854775807.0;\n\
+                 print 1 < float(\"inf\");\n\
+                 print 1 == float(\"nan\");"),
+            ["false", "true", "true", "true", "true", "true", "false", "true", "false"]
+        );
+    }
+
+    #[test]
+    fn mixed_numbers_sort_exactly() {
+        assert_eq!(
+            run("var xs = [3, 2.5, 1, 2 ** 53 + 1, 2.0 ** 53];\nxs.sort();\nprint xs;"),
+            ["[1, 2.5, 3, 9007199254740992.0, 9007199254740993]"]
+        );
+    }
+
+    #[test]
+    fn integer_overflow_promotes() {
+        assert_eq!(
+            run_python("mx = 9223372036854775807\n\
+                        mn = -9223372036854775807 - 1\n\
+                        print(mx + 1)\n\
+                        print(mn - 1)\n\
+                        print(-mn)\n\
+                        print(mn // -1)\n\
+                        print(mn % -1)\n\
+                        print(mx * mx)\n\
+                        print(2 ** 63)\n\
+                        print(1 << 63 >> 63)\n\
+                        print(9223372036854775808)\n\
+                        print(mx + 1 > mx)\n"),
+            [
+                "9223372036854775808",
+                "-9223372036854775809",
+                "9223372036854775808",
+                "9223372036854775808",
+                "0",
+                "85070591730234615847396907784232501249",
+                "9223372036854775808",
+                "1",
+                "9223372036854775808",
+                "true",
+            ]
+        );
+    }
+
+    #[test]
+    fn big_results_that_fit_demote_to_int() {
+        // 리스트 인덱스는 Int만 받으므로 다시 Int가 됐는지 확인할 수 있음
+        assert_eq!(
+            run("print [7][2 ** 64 - 2 ** 64];\n\
+                 print (9223372036854775807 + 1) - 1 == 9223372036854775807;\n\
+                 print [1, 2][(2 ** 70 + 1) % 2];"),
+            ["7", "true", "2"]
+        );
+        assert_eq!(
+            run_err("print [1][2 ** 64];"),
+            "IndexError: List subscript index out of range at line=1,col=17"
+        );
+    }
+
+    #[test]
+    fn huge_powers_and_big_true_division() {
+        assert_eq!(
+            run_python("print(1 ** 5000000000)\n\
+                        print(0 ** 5000000000)\n\
+                        print((-1) ** 5000000000)\n\
+                        print((-1) ** (2**100 + 1))\n\
+                        print(10**400 / 10**399)\n\
+                        print(-(10**400) / 10**398)\n\
+                        print(1 / 10**400)\n\
+                        print(2**64 / 3)\n"),
+            [
+                "1",
+                "0",
+                "1",
+                "-1",
+                "10.0",
+                "-100.0",
+                "0.0",
+                "6.148914691236517e+18",
+            ]
+        );
+        assert_eq!(
+            run_err("print 2 ** 5000000000;"),
+            "OverflowError: exponent too large at line=1,col=9"
+        );
+        assert_eq!(
+            run_err("print 10 ** 400 / 3;"),
+            "OverflowError: integer division result too large for a float at line=1,col=16"
+        );
+        assert_eq!(
+            run_err("print 10 ** 30 / 0;"),
+            "ZeroDivisionError: division by zero at line=1,col=15"
+        );
+    }
+
+    #[test]
+    fn runtime_errors_are_caught_by_class() {
+        assert_eq!(
+            run("try { [].pop(); } except IndexError as e { print e.message; }\n\
+                 var d = {};\n\
+                 try { d[\"k\"]; } except KeyError as e { print e.message; }\n\
+                 try { d.pop(1); } except LookupError as e { print e.message; }\n\
+                 try { 1 / 0; } except (ValueError, ZeroDivisionError) as e { print e.message; }\n\
+                 try { nope; } except NameError as e { print e.message; }\n\
+                 try { raise ValueError; } except ValueError as e { print e.message; }"),
+            [
+                "'pop from empty list'",
+                "'k'",
+                "1",
+                "'division by zero'",
+                "'Use of undefined variable 'nope''",
+                "''",
+            ]
+        );
+    }
+
+    #[test]
+    fn handlers_are_tried_in_order() {
+        assert_eq!(
+            run("try { raise ValueError(\"v\"); }\n\
+                 except LookupError { print \"lookup\"; }\n\
+                 except Exception as e { print e.message; }\n\
+                 class MyErr < ValueError {}\n\
+                 try { raise MyErr(\"mine\"); } except ValueError as e { print e.message; }\n\
+                 try { print 1; } except Exception { print 2; } else { print 3; } finally { print 4; }"),
+            ["'v'", "'mine'", "1", "3", "4"]
+        );
+    }
+
+    #[test]
+    fn finally_runs_while_unwinding() {
+        assert_eq!(
+            run("var log = [];\n\
+                 def inner() {\n\
+                   var x = \"inner\";\n\
+                   try { { var y = 1; raise ValueError(\"deep\"); } }\n\
+                   finally { log.append(\"inner finally\"); }\n\
+                 }\n\
+                 def outer() {\n\
+                   try { inner(); } finally { log.append(\"outer finally\"); }\n\
+                 }\n\
+                 var x = \"global\";\n\
+                 try { outer(); } except ValueError as e { log.append(e.message); }\n\
+                 print log;\n\
+                 print x;\n\
+                 def f() { try { return \"body\"; } finally { print \"cleanup\"; } }\n\
+                 print f();\n\
+                 def g() { try { raise ValueError(\"x\"); } finally { return \"swallowed\"; } }\n\
+                 print g();"),
+            [
+                "['inner finally', 'outer finally', 'deep']",
+                "'global'",
+                "'cleanup'",
+                "'body'",
+                "'swallowed'",
+            ]
+        );
+    }
+
+    #[test]
+    fn exceptions_in_loops() {
+        assert_eq!(
+            run_python("i = 0\n\
+                        while i < 3:\n\
+                        \x20   i += 1\n\
+                        \x20   try:\n\
+                        \x20       if i == 2:\n\
+                        \x20           raise KeyError(i)\n\
+                        \x20   except KeyError:\n\
+                        \x20       continue\n\
+                        \x20   print(i)\n"),
+            ["1", "3"]
+        );
+    }
+
+    #[test]
+    fn bare_raise_reraises_the_handled_exception() {
+        assert_eq!(
+            run_err("try { raise ValueError(\"a\"); }\n\
+                     except ValueError {\n\
+                       try { raise KeyError(\"b\"); } except KeyError {}\n\
+                       raise;\n\
+                     }"),
+            "ValueError: a at line=1,col=10"
+        );
+        assert_eq!(
+            run("try { try { raise KeyError(\"inner\"); } except KeyError { raise; } }\n\
+                 except LookupError as e { print e.message; }"),
+            ["'inner'"]
+        );
+    }
+
+    #[test]
+    fn raise_errors() {
+        assert_eq!(
+            run_err("raise;"),
+            "RuntimeError: No active exception to reraise at line=1,col=4"
+        );
+        assert_eq!(
+            run_err("raise 5;"),
+            "TypeError: exceptions must derive from BaseException at line=1,col=4"
+        );
+        assert_eq!(
+            run_err("try { raise ValueError(\"boom\"); } except KeyError {}"),
+            "ValueError: boom at line=1,col=10"
+        );
+        assert_eq!(
+            run_err("try { raise ValueError(\"a\"); } except ValueError { raise KeyError(\"b\"); }"),
+            "KeyError: b at line=1,col=55"
+        );
+    }
+
+    #[test]
+    fn assert_passes_silently() {
+        assert_eq!(
+            run("var calls = [0];\n\
+                 def bump() { calls[0] += 1; return true; }\n\
+                 assert bump();\n\
+                 assert 1 + 1 == 2, \"math\";\n\
+                 print calls[0];"),
+            ["1"]
+        );
+    }
+
+    #[test]
+    fn assert_failures_show_the_source() {
+        assert_eq!(
+            run_err("var x = 0;\nassert   x  ==  1 ,  \"x is \" + \"zero\";"),
+            "AssertionError: x is zero (assertion `x  ==  1` failed) at line=2,col=6"
+        );
+        assert_eq!(
+            run_in(Dialect::Python, "x = 0\nassert x == 1\n").unwrap_err(),
+            "AssertionError: assertion `x == 1` failed at line=2,col=6"
+        );
+        assert_eq!(
+            run_err("assert [] == [1], 5;"),
+            "AssertionError: 5 (assertion `[] == [1]` failed) at line=1,col=5"
+        );
+    }
+
+    #[test]
+    fn caught_assertions_carry_only_the_message() {
+        assert_eq!(
+            run("try { assert 1 > 2, \"nope\"; } except AssertionError as e { print e.message; }\n\
+                 try { assert false; } except AssertionError as e { print e.message; }"),
+            ["'nope'", "''"]
+        );
+    }
+
+    #[test]
+    fn asserts_can_be_disabled() {
+        let extensions = Extensions {
+            lists: true,
+            lambdas: true,
+            no_asserts: true,
+            ..Default::default()
+        };
+        // 조건도 메세지도 평가하지 않음
+        assert_eq!(
+            run_with(
+                extensions,
+                "var calls = [0];\n\
+                 def bump() { calls[0] += 1; return false; }\n\
+                 assert bump(), bump();\n\
+                 assert undefined;\n\
+                 print calls[0];"
+            ),
+            Ok(vec!["0".to_string()])
+        );
+    }
+
+    #[test]
+    fn assignment_declares_in_the_python_dialect() {
+        assert_eq!(
+            run_python("d = {\"b\": 2}\n\
+                        a, b = 1, 2\n\
+                        def f():\n\
+                        \x20   x = 1\n\
+                        \x20   x += 1\n\
+                        \x20   return x\n\
+                        total = 0\n\
+                        for i in range(4):\n\
+                        \x20   total = total + i\n\
+                        print(d[\"b\"] + a + b)\n\
+                        print(f())\n\
+                        print(total)\n"),
+            ["5", "2", "6"]
+        );
+        assert_eq!(
+            run_err("x = 1;"),
+            "NameError: attempting to assign to undeclared variable 'x' at line=1,col=0"
+        );
+    }
+
+    #[test]
+    fn augmented_assignment_needs_a_declared_target() {
+        assert!(run_err("z += 1;").starts_with("NameError: "));
+        assert!(run_err("1 += 2;").contains("invalid assignment target"));
+    }
+}
@@ -1,6 +1,8 @@
-use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::fmt;
 
+use crate::span::Span;
+
 
 // tokernizing부터 구현
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
@@ -13,12 +15,18 @@ pub enum TokenType {
     LeftBracket,
     RightBracket,
     Comma,
+    Colon,
+    Question,
     Dot,
     Minus,
     Plus,
     Semicolon,
     Slash,
     Percent,        // % 추가
+    Amp,
+    Pipe,
+    Caret,
+    Tilde,
 
     // One or two char tokens
     Equal,
@@ -31,6 +39,23 @@ pub enum TokenType {
     LessEqual,
     Star,
     StarStar,       // ** 추가
+    LessLess,
+    GreaterGreater,
+
+    // Augmented assignment
+    PlusEqual,
+    MinusEqual,
+    StarEqual,
+    SlashEqual,
+    PercentEqual,
+    StarStarEqual,
+    SlashSlash,         // 파이썬 방언의 // (Lox 방언에서는 주석)
+    SlashSlashEqual,
+    AmpEqual,
+    PipeEqual,
+    CaretEqual,
+    LessLessEqual,
+    GreaterGreaterEqual,
 
     // Literals
     Identifier,     // 식별자 이름 지정할 때 규칙 만들어 둘 것
@@ -44,6 +69,8 @@ pub enum TokenType {
     Def,
     For,
     In,
+    Is,
+    Not,
     If,
     Elif,
     Else,
@@ -51,58 +78,195 @@ pub enum TokenType {
     Print,
     Return,
     While,
+    Break,
+    Continue,
+    Del,
+    Try,
+    Except,
+    Finally,
+    Raise,
+    As,
+    Assert,
     Class,
     Lambda,
     Nil,
     Super,
     This,
     Var,
-    Append,
+
+    // 파이썬 방언의 들여쓰기 블록. 괄호 안에서는 나오지 않음
+    Newline,
+    Indent,
+    Dedent,
 
     Eof,
 }
 
-#[derive(Debug, Clone)]
-pub enum Literal {
-    Identifier(String),
-    Str(String),
-    Number(f64),        // 숫자는 모두 받을 것이므로 f64
+// 에러 메세지에 쓰는 토큰 종류 이름
+impl fmt::Display for TokenType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            TokenType::LeftParen => "(",
+            TokenType::RightParen => ")",
+            TokenType::LeftBrace => "{",
+            TokenType::RightBrace => "}",
+            TokenType::LeftBracket => "[",
+            TokenType::RightBracket => "]",
+            TokenType::Comma => ",",
+            TokenType::Colon => ":",
+            TokenType::Question => "?",
+            TokenType::Dot => ".",
+            TokenType::Minus => "-",
+            TokenType::Plus => "+",
+            TokenType::Semicolon => ";",
+            TokenType::Slash => "/",
+            TokenType::Percent => "%",
+            TokenType::Amp => "&",
+            TokenType::Pipe => "|",
+            TokenType::Caret => "^",
+            TokenType::Tilde => "~",
+            TokenType::Equal => "=",
+            TokenType::Bang => "!",
+            TokenType::BangEqual => "!=",
+            TokenType::EqualEqual => "==",
+            TokenType::Greater => ">",
+            TokenType::GreaterEqual => ">=",
+            TokenType::Less => "<",
+            TokenType::LessEqual => "<=",
+            TokenType::Star => "*",
+            TokenType::StarStar => "**",
+            TokenType::LessLess => "<<",
+            TokenType::GreaterGreater => ">>",
+            TokenType::PlusEqual => "+=",
+            TokenType::MinusEqual => "-=",
+            TokenType::StarEqual => "*=",
+            TokenType::SlashEqual => "/=",
+            TokenType::PercentEqual => "%=",
+            TokenType::StarStarEqual => "**=",
+            TokenType::SlashSlash => "//",
+            TokenType::SlashSlashEqual => "//=",
+            TokenType::AmpEqual => "&=",
+            TokenType::PipeEqual => "|=",
+            TokenType::CaretEqual => "^=",
+            TokenType::LessLessEqual => "<<=",
+            TokenType::GreaterGreaterEqual => ">>=",
+            TokenType::And => "and",
+            TokenType::True => "true",
+            TokenType::False => "false",
+            TokenType::Def => "def",
+            TokenType::For => "for",
+            TokenType::In => "in",
+            TokenType::Is => "is",
+            TokenType::Not => "not",
+            TokenType::If => "if",
+            TokenType::Elif => "elif",
+            TokenType::Else => "else",
+            TokenType::Or => "or",
+            TokenType::Print => "print",
+            TokenType::Return => "return",
+            TokenType::While => "while",
+            TokenType::Break => "break",
+            TokenType::Continue => "continue",
+            TokenType::Del => "del",
+            TokenType::Try => "try",
+            TokenType::Except => "except",
+            TokenType::Finally => "finally",
+            TokenType::Raise => "raise",
+            TokenType::As => "as",
+            TokenType::Assert => "assert",
+            TokenType::Class => "class",
+            TokenType::Lambda => "lambda",
+            TokenType::Nil => "nil",
+            TokenType::Super => "super",
+            TokenType::This => "this",
+            TokenType::Var => "var",
+            // 기호가 없는 토큰은 이름으로 씀
+            TokenType::Identifier => return write!(f, "identifier"),
+            TokenType::String => return write!(f, "string"),
+            TokenType::Number => return write!(f, "number"),
+            TokenType::Newline => return write!(f, "newline"),
+            TokenType::Indent => return write!(f, "indent"),
+            TokenType::Dedent => return write!(f, "dedent"),
+            TokenType::Eof => return write!(f, "end of input"),
+        };
+        write!(f, "`{}`", symbol)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Literal<'src> {
+    Identifier(&'src str),
+    Str(&'src str),
+    Number(f64),        // 소수점이 있는 숫자
+    Int(i64),
+    BigInt(&'src str),  // i64에 안 들어가는 정수. 숫자 그대로
 }
 
 #[derive(Clone)]
-pub struct Token {
+pub struct Token<'src> {
     pub toktype: TokenType,
-    pub lexing: Vec<u8>,            // clone해서 사용(1바이트)
-    pub literal: Option<Literal>,   // 식별자, 문자, 숫자 중 하나
-    pub line: usize,                // 라인은 1부터
-    pub col: i64,                   // 칼럼은 -1부터
+    pub lexing: &'src str,               // 소스를 빌려서 사용(복사 없음)
+    pub literal: Option<Literal<'src>>,  // 식별자, 문자, 숫자 중 하나
+    pub line: usize,                     // 라인은 1부터
+    pub col: i64,                        // 칼럼은 -1부터
+    pub span: Span,                      // 소스 상의 바이트 범위
 }
 
 // pub trait Debug {
 //     fn fmt(&self, f: &mut Formatter<'_>) -> Result;
 // }
-impl fmt::Debug for Token {
+impl fmt::Debug for Token<'_> {
     fn fmt(&self, form: &mut fmt::Formatter) -> fmt::Result {
         write!(
             form,
-            "Token {{ tktype: {:?}, lexing: \"{}\", literal: {:?}, line: {:?}, col: {:?}}}",
+            "Token {{ tktype: {:?}, lexing: \"{}\", literal: {:?}, line: {:?}, col: {:?}, span: {:?}}}",
             self.toktype,
-            String::from_utf8(self.lexing.clone()).unwrap(),
+            self.lexing,
             self.literal,
             self.line,
-            self.col
+            self.col,
+            self.span
         )
     }
 }
 
-pub fn check_tokens(input: String) -> Result<Vec<Token>, Error> {
-    let mut lexer: Lexer = Default::default();  // Returns the "default value" for a type.
+impl Token<'_> {
+    // 에러 메세지용: 소스에 쓰인 그대로 보여줌
+    pub fn describe(&self) -> String {
+        match self.toktype {
+            TokenType::Newline | TokenType::Indent | TokenType::Dedent | TokenType::Eof => {
+                self.toktype.to_string()
+            }
+            _ => format!("`{}`", self.lexing),
+        }
+    }
+}
 
-    lexer.check_tokens(input);
+// 문법 방언: Lox 스타일과 파이썬 스타일
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub enum Dialect {
+    #[default]
+    Lox,
+    Python,
+}
 
-    match lexer.err {
-        Some(err) => Err(err),
-        None => Ok(lexer.tokens),               // 에러가 없을 시에 Ok 반환함
+impl Dialect {
+    // 방언별 키워드 테이블. 공통 키워드에 없는 것만 따로 둠
+    fn keyword(self, text: &str) -> Option<TokenType> {
+        match (self, text) {
+            (Dialect::Python, "True") => Some(TokenType::True),
+            (Dialect::Python, "False") => Some(TokenType::False),
+            (Dialect::Python, "None") => Some(TokenType::Nil),
+            (_, text) => keyword(text),
+        }
+    }
+
+    // 한 줄 주석의 시작. 블록 주석 /* */ 는 두 방언 모두 같음
+    fn line_comment(self) -> &'static str {
+        match self {
+            Dialect::Lox => "//",
+            Dialect::Python => "#",
+        }
     }
 }
 
@@ -113,181 +277,399 @@ pub struct Error {      // Error type 정의해서 lexer struct의 필드 원소
     pub col: i64,
 }
 
-pub struct Lexer {
-    source: Vec<u8>,    // u8로 받는 이유
-    tokens: Vec<Token>, // Vec<Token>
-    err: Option<Error>,
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at line={},col={}", self.what, self.line, self.col)
+    }
+}
+
+impl std::error::Error for Error {}
+
+// 토큰을 한번에 Vec으로 만들지 않고, 파서가 요청할 때마다 하나씩 생성함
+pub struct Lexer<'src> {
+    source: &'src str,
+    dialect: Dialect,
     start: usize,
     cursor: usize,
     line: usize,
     col: i64,
-    keywords: HashMap<String, TokenType>,
+    finished: bool,     // Eof 또는 에러를 내보낸 뒤에는 더 이상 토큰이 없음
+    line_start: usize,  // 현재 줄의 시작 위치
+    depth: usize,       // 열린 괄호 수
+    at_line_start: bool,
+    indents: Vec<usize>,
+    pending: VecDeque<Token<'src>>,
 }
 
 // keyword type checker
-// pub trait Default: Sized {
-//     fn default() -> Self;
-// }
-impl Default for Lexer {
-    fn default() -> Lexer {
+// 키워드는 식별자를 읽은 뒤에만 조회함
+fn keyword(text: &str) -> Option<TokenType> {
+    match text {
+        "and" => Some(TokenType::And),
+        "true" => Some(TokenType::True),
+        "false" => Some(TokenType::False),
+        "in" => Some(TokenType::In),
+        "is" => Some(TokenType::Is),
+        "not" => Some(TokenType::Not),
+        "for" => Some(TokenType::For),
+        "def" => Some(TokenType::Def),
+        "if" => Some(TokenType::If),
+        "elif" => Some(TokenType::Elif),
+        "else" => Some(TokenType::Else),
+        "or" => Some(TokenType::Or),
+        "print" => Some(TokenType::Print),
+        "class" => Some(TokenType::Class),
+        "return" => Some(TokenType::Return),
+        "while" => Some(TokenType::While),
+        "break" => Some(TokenType::Break),
+        "continue" => Some(TokenType::Continue),
+        "del" => Some(TokenType::Del),
+        "try" => Some(TokenType::Try),
+        "except" => Some(TokenType::Except),
+        "finally" => Some(TokenType::Finally),
+        "raise" => Some(TokenType::Raise),
+        "as" => Some(TokenType::As),
+        "assert" => Some(TokenType::Assert),
+        "lambda" => Some(TokenType::Lambda),
+        "super" => Some(TokenType::Super),
+        "this" => Some(TokenType::This),
+        "nil" => Some(TokenType::Nil),
+        "var" => Some(TokenType::Var),
+        _ => None,
+    }
+}
+
+impl<'src> Iterator for Lexer<'src> {
+    type Item = Result<Token<'src>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(token) = self.pending.pop_front() {
+            return Some(Ok(token));
+        }
+        if self.finished {
+            return None;
+        }
+
+        loop {
+            if self.is_end() {
+                self.finished = true;
+                self.start = self.cursor;
+                let eof = self.make_token(TokenType::Eof, None);
+                if self.dialect == Dialect::Python {
+                    // 마지막 줄을 닫고 열린 블록을 모두 닫음
+                    if !self.at_line_start {
+                        self.pending.push_back(Lexer::layout_token(TokenType::Newline, &eof));
+                    }
+                    while self.indents.len() > 1 {
+                        self.indents.pop();
+                        self.pending.push_back(Lexer::layout_token(TokenType::Dedent, &eof));
+                    }
+                }
+                self.pending.push_back(eof);
+                return self.pending.pop_front().map(Ok);
+            }
+
+            self.start = self.cursor;
+            match self.check_token() {
+                Ok(Some(token)) => {
+                    if self.at_line_start && token.toktype != TokenType::Newline {
+                        self.at_line_start = false;
+                        return Some(self.indent(token));
+                    }
+                    return Some(Ok(token));
+                }
+                Ok(None) => {}      // 공백, 주석
+                Err(err) => {
+                    self.finished = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+impl<'src> Lexer<'src> {
+    pub fn new(source: &'src str, dialect: Dialect) -> Lexer<'src> {
         Lexer {
-            source: Vec::new(),
-            tokens: Vec::new(),
-            err: None,
+            source,
+            dialect,
             start: 0,
             cursor: 0,
             line: 1,
             col: -1,
-            keywords: vec![
-                ("and", TokenType::And),
-                ("true", TokenType::True),
-                ("false", TokenType::False),
-                ("in", TokenType::In),
-                ("for", TokenType::For),
-                ("def", TokenType::Def),
-                ("if", TokenType::If),
-                ("elif", TokenType::Elif),
-                ("else", TokenType::Else),
-                ("or", TokenType::Or),
-                ("print", TokenType::Print),
-                ("class", TokenType::Class),
-                ("return", TokenType::Return),
-                ("while", TokenType::While),
-                ("labmda", TokenType::Lambda),
-                ("super", TokenType::Super),
-                ("this", TokenType::This),
-                ("nil", TokenType::Nil),
-                ("var", TokenType::Var),
-                ("append", TokenType::Append),
-            ]
-                .into_iter()
-                .map(|(key, val)| (String::from(key), val))
-                .collect(),
+            finished: false,
+            line_start: 0,
+            depth: 0,
+            at_line_start: dialect == Dialect::Python,
+            indents: vec![0],
+            pending: VecDeque::new(),
         }
     }
-}
 
-impl Lexer {
-    fn check_tokens(&mut self, input: String) {
-        self.source = input.into_bytes();       // into_bytes()
+    // 줄의 첫 토큰에서 들여쓰기를 비교해 Indent/Dedent를 만듦 (파이썬 방언)
+    fn indent(&mut self, token: Token<'src>) -> Result<Token<'src>, Error> {
+        let width = self.source[self.line_start..token.span.start]
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .fold(0, |width, c| if c == '\t' { width / 8 * 8 + 8 } else { width + 1 });
+
+        let top = *self.indents.last().unwrap();
+        if width > top {
+            self.indents.push(width);
+            self.pending.push_back(token.clone());
+            return Ok(Lexer::layout_token(TokenType::Indent, &token));
+        }
 
-        while !self.done() {
-            self.start = self.cursor;
-            self.check_token();
+        while width < *self.indents.last().unwrap() {
+            self.indents.pop();
+            self.pending.push_back(Lexer::layout_token(TokenType::Dedent, &token));
+        }
+        if width != *self.indents.last().unwrap() {
+            return Err(Error {
+                what: "Unindent does not match any outer indentation level".to_string(),
+                line: token.line,
+                col: token.col,
+            });
         }
 
-        match self.err {
-            Some(_) => {}
-            None => self.tokens.push(Token {
-                toktype: TokenType::Eof,
-                lexing: Vec::new(),
-                literal: None,
-                line: self.line,
-                col: self.col,
-            })
+        self.pending.push_back(token);
+        Ok(self.pending.pop_front().unwrap())
+    }
+
+    fn layout_token(toktype: TokenType, at: &Token<'src>) -> Token<'src> {
+        Token {
+            toktype,
+            lexing: "",
+            literal: None,
+            line: at.line,
+            col: at.col,
+            span: Span::new(at.span.start, at.span.start),
         }
     }
 
     fn nexting(&mut self) -> char {
-        self.cursor += 1;
+        let c = self.peek();
+        self.cursor += c.len_utf8();
         self.col += 1;
 
-        char::from(self.source[self.cursor - 1])
+        c
     }
 
-    fn check_token(&mut self) {
+    fn check_token(&mut self) -> Result<Option<Token<'src>>, Error> {
+        if self.rest().starts_with(self.dialect.line_comment()) {
+            while self.peek() != '\n' && !self.is_end() {
+                self.nexting();
+            }
+            return Ok(None);
+        }
+
+        if self.rest().starts_with("/*") {
+            self.block_comment()?;
+            return Ok(None);
+        }
+
         let c = self.nexting();
 
-        match c {
-            '%' => self.add_token(TokenType::Percent),
-            '&' => self.add_token(TokenType::And),
-            '|' => self.add_token(TokenType::Or),
+        let token_type = match c {
+            '%' => {
+                if self.matches('=') {
+                    TokenType::PercentEqual
+                } else {
+                    TokenType::Percent
+                }
+            }
+            '&' => {
+                if self.matches('=') {
+                    TokenType::AmpEqual
+                } else {
+                    TokenType::Amp
+                }
+            }
+            '|' => {
+                if self.matches('=') {
+                    TokenType::PipeEqual
+                } else {
+                    TokenType::Pipe
+                }
+            }
+            '^' => {
+                if self.matches('=') {
+                    TokenType::CaretEqual
+                } else {
+                    TokenType::Caret
+                }
+            }
+            '~' => TokenType::Tilde,
             '*' => {
-                let matches_eq = self.matches('*');
-                self.add_token(if matches_eq {
-                    TokenType::StarStar                         // 추가 할 것
+                if self.matches('*') {
+                    if self.matches('=') {
+                        TokenType::StarStarEqual
+                    } else {
+                        TokenType::StarStar                     // 추가 할 것
+                    }
+                } else if self.matches('=') {
+                    TokenType::StarEqual
                 } else {
                     TokenType::Star
-                })
+                }
             }
 
-            '(' => self.add_token(TokenType::LeftParen),
-            ')' => self.add_token(TokenType::RightParen),
-            '{' => self.add_token(TokenType::LeftBrace),
-            '}' => self.add_token(TokenType::RightBrace),
-            '[' => self.add_token(TokenType::LeftBracket),
-            ']' => self.add_token(TokenType::RightBracket),
-            ',' => self.add_token(TokenType::Comma),
-            '.' => self.add_token(TokenType::Dot),
-            '-' => self.add_token(TokenType::Minus),
-            '+' => self.add_token(TokenType::Plus),
-            ';' => self.add_token(TokenType::Semicolon),
+            '(' | '{' | '[' => {
+                self.depth += 1;
+                match c {
+                    '(' => TokenType::LeftParen,
+                    '{' => TokenType::LeftBrace,
+                    _ => TokenType::LeftBracket,
+                }
+            }
+            ')' | '}' | ']' => {
+                self.depth = self.depth.saturating_sub(1);
+                match c {
+                    ')' => TokenType::RightParen,
+                    '}' => TokenType::RightBrace,
+                    _ => TokenType::RightBracket,
+                }
+            }
+            ',' => TokenType::Comma,
+            ':' => TokenType::Colon,
+            '?' => TokenType::Question,
+            '.' => TokenType::Dot,
+            '-' => {
+                if self.matches('=') {
+                    TokenType::MinusEqual
+                } else {
+                    TokenType::Minus
+                }
+            }
+            '+' => {
+                if self.matches('=') {
+                    TokenType::PlusEqual
+                } else {
+                    TokenType::Plus
+                }
+            }
+            ';' => TokenType::Semicolon,
             '!' => {
-                let matches_eq = self.matches('=');
-                self.add_token(if matches_eq {
+                if self.matches('=') {
                     TokenType::BangEqual
                 } else {
                     TokenType::Bang
-                })
+                }
             }
             '=' => {
-                let matches_eq = self.matches('=');
-                self.add_token(if matches_eq {
+                if self.matches('=') {
                     TokenType::EqualEqual
                 } else {
                     TokenType::Equal
-                })
+                }
             }
             '<' => {
-                let matches_eq = self.matches('=');
-                self.add_token(if matches_eq {
+                if self.matches('<') {
+                    if self.matches('=') {
+                        TokenType::LessLessEqual
+                    } else {
+                        TokenType::LessLess
+                    }
+                } else if self.matches('=') {
                     TokenType::LessEqual
                 } else {
                     TokenType::Less
-                })
+                }
             }
             '>' => {
-                let matches_eq = self.matches('=');
-                self.add_token(if matches_eq {
+                if self.matches('>') {
+                    if self.matches('=') {
+                        TokenType::GreaterGreaterEqual
+                    } else {
+                        TokenType::GreaterGreater
+                    }
+                } else if self.matches('=') {
                     TokenType::GreaterEqual
                 } else {
                     TokenType::Greater
-                })
+                }
             }
             '/' => {
+                // Lox 방언에서 //는 이미 주석으로 처리됨
                 if self.matches('/') {
-                    while self.peek() != '\n' && !self.is_end() {
-                        self.nexting();
+                    if self.matches('=') {
+                        TokenType::SlashSlashEqual
+                    } else {
+                        TokenType::SlashSlash
                     }
+                } else if self.matches('=') {
+                    TokenType::SlashEqual
                 } else {
-                    self.add_token(TokenType::Slash)
+                    TokenType::Slash
                 }
             }
-            ' ' | '\r' | '\t' => {}
+            ' ' | '\r' | '\t' => return Ok(None),
             '\n' => {
+                // 파이썬 방언에서는 괄호 밖의 줄바꿈이 문장을 끝냄
+                let newline = self.dialect == Dialect::Python
+                    && self.depth == 0
+                    && !self.at_line_start;
+                let token = newline.then(|| self.make_token(TokenType::Newline, None));
                 self.line += 1;
-                self.col = 0
+                self.col = 0;
+                self.line_start = self.cursor;
+                self.at_line_start |= newline;
+                return Ok(token);
             }
-            '"' => self.string(),
-            '\'' => self.string2(),
+            '"' | '\'' => return self.string(c).map(Some),
             _ => {
                 if Lexer::is_decimal_digit(c) {
-                    self.number()
-                } else if Lexer::is_alpha(c) {
-                    self.identifier()
-                } else {
-                    self.err = Some(Error {
-                        what: format!("Lexer can't handle {}", c),
-                        line: self.line,
-                        col: self.col,
-                    })
+                    return Ok(Some(self.number()));
+                }
+                if Lexer::is_alpha(c) {
+                    return Ok(Some(self.identifier()));
+                }
+                return Err(Error {
+                    what: format!("Lexer can't handle {}", c),
+                    line: self.line,
+                    col: self.col,
+                });
+            }
+        };
+
+        Ok(Some(self.make_token(token_type, None)))
+    }
+
+    // 블록 주석은 중첩될 수 있음: /* a /* b */ c */
+    fn block_comment(&mut self) -> Result<(), Error> {
+        let (line, col) = (self.line, self.col + 1);
+        let mut depth = 0;
+
+        loop {
+            if self.is_end() {
+                return Err(Error {
+                    what: "Unterminated block comment".to_string(),
+                    line,
+                    col,
+                });
+            }
+
+            match self.nexting() {
+                '/' if self.matches('*') => depth += 1,
+                '*' if self.matches('/') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
                 }
+                '\n' => {
+                    self.line += 1;
+                    self.col = 0;
+                    self.line_start = self.cursor;
+                }
+                _ => {}
             }
         }
     }
 
+    // 식별자는 _로 시작할 수 있음 (__iter__ 등)
     fn is_alpha(c: char) -> bool {
-        c.is_alphabetic()
+        c.is_alphabetic() || c == '_'
     }
 
     fn is_decimal_digit(c: char) -> bool {
@@ -299,23 +681,18 @@ impl Lexer {
     }
 
     fn peek(&self) -> char {
-        if self.is_end() {
-            '\0'
-        } else {
-            char::from(self.source[self.cursor])
-        }
+        self.rest().chars().next().unwrap_or('\0')
     }
 
     fn peek_next(&self) -> char {
-        if self.cursor + 1 >= self.source.len() {
-            '\0'
-        } else {
-            char::from(self.source[self.cursor + 1])
-        }
+        let mut chars = self.rest().chars();
+        chars.next();
+        chars.next().unwrap_or('\0')
     }
 
-    fn string(&mut self) {
-        while self.peek() != '"' && !self.is_end() {
+    // 큰 따옴표, 작은 따옴표 모두 받음
+    fn string(&mut self, quote: char) -> Result<Token<'src>, Error> {
+        while self.peek() != quote && !self.is_end() {
             if self.peek() == '\n' {
                 self.line += 1
             }
@@ -323,85 +700,40 @@ impl Lexer {
         }
 
         if self.is_end() {
-            self.err = Some(Error {
+            return Err(Error {
                 what: "Unterminated string".to_string(),
                 line: self.line,
                 col: self.col,
-            })
+            });
         }
 
-        assert_eq!(self.peek(), '"');
-
         self.nexting();
 
-        self.add_token_literal(
-            TokenType::String,
-            Some(Literal::Str(
-                String::from_utf8(self.source[self.start + 1..self.cursor - 1].to_vec())
-                    .unwrap(),
-            ))
-        )
+        let contents = &self.source[self.start + 1..self.cursor - 1];
+        Ok(self.make_token(TokenType::String, Some(Literal::Str(contents))))
     }
 
-    // 싱글 쿠테이션 마크도 받을 수 있게 하기
-    fn string2(&mut self) {
-        while self.peek() != '\'' && !self.is_end() {
-            if self.peek() == '\n' {
-                self.line += 1
-            }
-            self.nexting();
-        }
-
-        if self.is_end() {
-            self.err = Some(Error {
-                what: "Unterminated string".to_string(),
-                line: self.line,
-                col: self.col,
-            })
-        }
-
-        assert_eq!(self.peek(), '\'');
-
-        self.nexting();
-
-        self.add_token_literal(
-            TokenType::String,
-            Some(Literal::Str(
-                String::from_utf8(self.source[self.start + 1..self.cursor - 1].to_vec())
-                    .unwrap(),
-            ))
-        )
-    }
-
-    fn identifier(&mut self) {
+    fn identifier(&mut self) -> Token<'src> {
         while Lexer::is_alphanumeric(self.peek()) {
             self.nexting();
         }
 
-        let literal_val =
-            String::from_utf8(self.source[self.start..self.cursor].to_vec()).unwrap();
-
-        let token_type = match self.keywords.get(&literal_val) {
-            Some(tokentype) => *tokentype,
-            None => TokenType::Identifier,
-        };
+        let text = &self.source[self.start..self.cursor];
 
-        match token_type {
-            TokenType::Identifier => self.add_token_literal(
-                TokenType::Identifier,
-                Some(Literal::Identifier(literal_val)),
-            ),
-            _ => self.add_token(token_type),
+        match self.dialect.keyword(text) {
+            Some(token_type) => self.make_token(token_type, None),
+            None => self.make_token(TokenType::Identifier, Some(Literal::Identifier(text))),
         }
     }
 
-
-    fn number(&mut self) {
+    fn number(&mut self) -> Token<'src> {
         while Lexer::is_decimal_digit(self.peek()) {
             self.nexting();
         }
 
+        let mut is_float = false;
         if self.peek() == '.' && Lexer::is_decimal_digit(self.peek_next()) {
+            is_float = true;
             self.nexting();
         }
 
@@ -410,47 +742,202 @@ impl Lexer {
         }
 
         // 숫자값은 그대로 밸류 생성함
-        let val: f64 = String::from_utf8(self.source[self.start..self.cursor].to_vec())
-            .unwrap()
-            .parse()
-            .unwrap();
-
-        self.add_token_literal(TokenType::Number, Some(Literal::Number(val)))
-    }
+        let text = &self.source[self.start..self.cursor];
+        let literal = if is_float {
+            Literal::Number(text.parse().unwrap())
+        } else {
+            match text.parse() {
+                Ok(val) => Literal::Int(val),
+                Err(_) => Literal::BigInt(text),
+            }
+        };
 
-    fn add_token(&mut self, token_type: TokenType) {
-        self.add_token_literal(token_type, None)
+        self.make_token(TokenType::Number, Some(literal))
     }
 
-    fn add_token_literal(&mut self, token_type: TokenType, literal: Option<Literal>) {
-        self.tokens.push(Token {
+    fn make_token(&self, token_type: TokenType, literal: Option<Literal<'src>>) -> Token<'src> {
+        Token {
             toktype: token_type,
-            lexing: self.source[self.start..self.cursor].to_vec(),
+            lexing: &self.source[self.start..self.cursor],
             literal,
             line: self.line,
             col: self.col,
-        })
+            span: Span::new(self.start, self.cursor),
+        }
     }
 
     fn matches(&mut self, c: char) -> bool {
-        if self.is_end() {
-            return true;
-        }
-
-        if char::from(self.source[self.cursor]) != c {
+        if self.is_end() || self.peek() != c {
             return false;
         }
 
-        self.cursor += 1;
-        self.col += 1;
+        self.nexting();
         true
     }
 
-    fn done(&self) -> bool {
-        self.err.is_some() || self.is_end()
+    fn rest(&self) -> &'src str {
+        &self.source[self.cursor..]
     }
 
     fn is_end(&self) -> bool {
         self.cursor >= self.source.len()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lex(source: &str, dialect: Dialect) -> Vec<Token<'_>> {
+        Lexer::new(source, dialect).collect::<Result<_, _>>().unwrap()
+    }
+
+    fn toktypes(source: &str, dialect: Dialect) -> Vec<TokenType> {
+        lex(source, dialect).iter().map(|token| token.toktype).collect()
+    }
+
+    #[test]
+    fn lexemes_borrow_the_source() {
+        let source = "var x = \"hi\" + 12;";
+        let range = source.as_bytes().as_ptr_range();
+        for token in lex(source, Dialect::Lox) {
+            assert_eq!(token.lexing, &source[token.span.start..token.span.end]);
+            if !token.lexing.is_empty() {
+                assert!(range.contains(&token.lexing.as_ptr()));
+            }
+        }
+    }
+
+    #[test]
+    fn spans_are_byte_offsets() {
+        let spans: Vec<_> = lex("é = \"ü\";", Dialect::Lox)
+            .iter()
+            .map(|token| (token.span.start, token.span.end))
+            .collect();
+        assert_eq!(spans, [(0, 2), (3, 4), (5, 9), (9, 10), (10, 10)]);
+    }
+
+    #[test]
+    fn keywords_are_matched_after_identifiers() {
+        assert_eq!(
+            toktypes("lambda labmda variable var", Dialect::Lox),
+            [
+                TokenType::Lambda,
+                TokenType::Identifier,
+                TokenType::Identifier,
+                TokenType::Var,
+                TokenType::Eof
+            ]
+        );
+        assert_eq!(
+            toktypes("True None", Dialect::Lox),
+            [TokenType::Identifier, TokenType::Identifier, TokenType::Eof]
+        );
+    }
+
+    #[test]
+    fn literals() {
+        let tokens = lex("12 3.5 99999999999999999999 'a b' name", Dialect::Lox);
+        assert!(matches!(tokens[0].literal, Some(Literal::Int(12))));
+        assert!(matches!(tokens[1].literal, Some(Literal::Number(n)) if n == 3.5));
+        assert!(matches!(
+            tokens[2].literal,
+            Some(Literal::BigInt("99999999999999999999"))
+        ));
+        assert!(matches!(tokens[3].literal, Some(Literal::Str("a b"))));
+        assert!(matches!(tokens[4].literal, Some(Literal::Identifier("name"))));
+    }
+
+    #[test]
+    fn multibyte_characters_are_one_column() {
+        let tokens = lex("\"héllo\" 변수 = 1;", Dialect::Lox);
+        assert_eq!(tokens[0].lexing, "\"héllo\"");
+        assert_eq!(tokens[1].lexing, "변수");
+        assert_eq!(tokens[1].toktype, TokenType::Identifier);
+        assert_eq!(tokens[2].toktype, TokenType::Equal);
+        assert_eq!(tokens[2].col, 11);
+    }
+
+    #[test]
+    fn line_comments_depend_on_the_dialect() {
+        assert_eq!(
+            toktypes("x // y\n", Dialect::Lox),
+            [TokenType::Identifier, TokenType::Eof]
+        );
+        assert_eq!(
+            toktypes("x // y # z\n", Dialect::Python),
+            [
+                TokenType::Identifier,
+                TokenType::SlashSlash,
+                TokenType::Identifier,
+                TokenType::Newline,
+                TokenType::Eof
+            ]
+        );
+        let err = Lexer::new("# x", Dialect::Lox).next().unwrap().unwrap_err();
+        assert_eq!(err.what, "Lexer can't handle #");
+    }
+
+    #[test]
+    fn block_comments_nest() {
+        for dialect in [Dialect::Lox, Dialect::Python] {
+            let tokens = lex("a /* b /* c */ d */ e", dialect);
+            let lexemes: Vec<_> = tokens.iter().map(|token| token.lexing).collect();
+            assert_eq!(lexemes[..2], ["a", "e"]);
+        }
+    }
+
+    #[test]
+    fn unterminated_block_comment_reports_its_start() {
+        let err = Lexer::new("x\n  /* a /* b */\n", Dialect::Lox)
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(err.what, "Unterminated block comment");
+        let token = &lex("x\n  /", Dialect::Lox)[1];
+        assert_eq!((err.line, err.col), (token.line, token.col));
+    }
+
+    #[test]
+    fn block_comments_keep_line_and_column() {
+        let after_comment = &lex("/* a\nb */ x", Dialect::Lox)[0];
+        let after_newline = &lex("\n     x", Dialect::Lox)[0];
+        assert_eq!(
+            (after_comment.line, after_comment.col),
+            (after_newline.line, after_newline.col)
+        );
+
+        // 주석이 끝난 줄의 들여쓰기로 블록을 판단함
+        assert_eq!(
+            toktypes("if x:\n/* a\n  */ y\n", Dialect::Python),
+            [
+                TokenType::If,
+                TokenType::Identifier,
+                TokenType::Colon,
+                TokenType::Newline,
+                TokenType::Indent,
+                TokenType::Identifier,
+                TokenType::Newline,
+                TokenType::Dedent,
+                TokenType::Eof
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated_string_is_an_error() {
+        let mut lexer = Lexer::new("print \"abc", Dialect::Lox);
+        assert_eq!(lexer.next().unwrap().unwrap().toktype, TokenType::Print);
+        let err = lexer.next().unwrap().unwrap_err();
+        assert_eq!(err.what, "Unterminated string");
+        // 에러 뒤에는 토큰이 더 나오지 않음
+        assert!(lexer.next().is_none());
+    }
+
+    #[test]
+    fn stops_after_eof() {
+        let mut lexer = Lexer::new("x", Dialect::Lox);
+        assert_eq!(lexer.next().unwrap().unwrap().toktype, TokenType::Identifier);
+        assert_eq!(lexer.next().unwrap().unwrap().toktype, TokenType::Eof);
+        assert!(lexer.next().is_none());
+    }
+}
@@ -0,0 +1,350 @@
+use std::cmp::Ordering;
+
+use crate::interpreter::{
+    compare_numbers, type_of, Arity, ErrorKind, Interpreter, MethodDef, RuntimeError, Value,
+};
+
+// 파이썬 리스트 메소드
+pub static METHODS: &[MethodDef] = &[
+    MethodDef {
+        name: "append",
+        arity: Arity::exactly(1),
+        keywords: &[],
+        callable: append,
+    },
+    MethodDef {
+        name: "extend",
+        arity: Arity::exactly(1),
+        keywords: &[],
+        callable: extend,
+    },
+    MethodDef {
+        name: "insert",
+        arity: Arity::exactly(2),
+        keywords: &[],
+        callable: insert,
+    },
+    MethodDef {
+        name: "remove",
+        arity: Arity::exactly(1),
+        keywords: &[],
+        callable: remove,
+    },
+    MethodDef {
+        name: "pop",
+        arity: Arity::range(0, 1),
+        keywords: &[],
+        callable: pop,
+    },
+    MethodDef {
+        name: "clear",
+        arity: Arity::exactly(0),
+        keywords: &[],
+        callable: clear,
+    },
+    MethodDef {
+        name: "index",
+        arity: Arity::range(1, 3),
+        keywords: &[],
+        callable: index,
+    },
+    MethodDef {
+        name: "count",
+        arity: Arity::exactly(1),
+        keywords: &[],
+        callable: count,
+    },
+    MethodDef {
+        name: "reverse",
+        arity: Arity::exactly(0),
+        keywords: &[],
+        callable: reverse,
+    },
+    MethodDef {
+        name: "copy",
+        arity: Arity::exactly(0),
+        keywords: &[],
+        callable: copy,
+    },
+    MethodDef {
+        name: "sort",
+        arity: Arity::exactly(0),
+        keywords: &["key", "reverse"],
+        callable: sort,
+    },
+];
+
+fn list_id(receiver: &Value) -> u64 {
+    match receiver {
+        Value::List(list_id) => *list_id,
+        _ => panic!("Internal interpreter error! list method called on {:?}.", receiver),
+    }
+}
+
+fn as_int(method: &str, val: &Value) -> Result<i64, RuntimeError> {
+    match val {
+        Value::Int(n) => Ok(*n),
+        _ => Err(RuntimeError::new(
+            ErrorKind::TypeError,
+            format!(
+                "list.{}() expected an integer, found {:?}",
+                method,
+                type_of(val)
+            ),
+        )),
+    }
+}
+
+// 파이썬 슬라이스처럼 음수는 뒤에서부터 세고 범위 밖은 양끝으로 맞춤
+fn clamp_index(index: i64, len: usize) -> usize {
+    let len = len as i64;
+    let index = if index < 0 { index + len } else { index };
+    index.clamp(0, len) as usize
+}
+
+fn append(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    args: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    interpreter
+        .get_list_elts_mut(list_id(receiver))
+        .push(args[0].clone());
+    Ok(Value::Nil)
+}
+
+fn extend(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    args: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    // xs.extend(xs)도 되도록 먼저 모두 꺼내둠
+    let elts = interpreter.collect_iterable(&args[0])?;
+    interpreter
+        .get_list_elts_mut(list_id(receiver))
+        .extend(elts);
+    Ok(Value::Nil)
+}
+
+fn insert(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    args: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    let index = as_int("insert", &args[0])?;
+    let elts = interpreter.get_list_elts_mut(list_id(receiver));
+    let index = clamp_index(index, elts.len());
+    elts.insert(index, args[1].clone());
+    Ok(Value::Nil)
+}
+
+fn remove(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    args: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    let found = interpreter
+        .get_list_elts(list_id(receiver))
+        .iter()
+        .position(|elt| interpreter.equals(elt, &args[0]));
+    match found {
+        Some(pos) => {
+            interpreter.get_list_elts_mut(list_id(receiver)).remove(pos);
+            Ok(Value::Nil)
+        }
+        None => Err(RuntimeError::new(
+            ErrorKind::ValueError,
+            "list.remove(x): x not in list",
+        )),
+    }
+}
+
+fn pop(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    args: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    let index = match args.first() {
+        Some(arg) => as_int("pop", arg)?,
+        None => -1,
+    };
+    let elts = interpreter.get_list_elts_mut(list_id(receiver));
+    if elts.is_empty() {
+        return Err(RuntimeError::new(
+            ErrorKind::IndexError,
+            "pop from empty list",
+        ));
+    }
+    let len = elts.len() as i64;
+    let index = if index < 0 { index + len } else { index };
+    if !(0..len).contains(&index) {
+        return Err(RuntimeError::new(
+            ErrorKind::IndexError,
+            "pop index out of range",
+        ));
+    }
+    Ok(elts.remove(index as usize))
+}
+
+fn clear(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    _: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    interpreter.get_list_elts_mut(list_id(receiver)).clear();
+    Ok(Value::Nil)
+}
+
+fn index(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    args: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    let elts = interpreter.get_list_elts(list_id(receiver));
+    let start = match args.get(1) {
+        Some(arg) => clamp_index(as_int("index", arg)?, elts.len()),
+        None => 0,
+    };
+    let end = match args.get(2) {
+        Some(arg) => clamp_index(as_int("index", arg)?, elts.len()),
+        None => elts.len(),
+    };
+
+    let found = elts
+        .get(start..end.max(start))
+        .and_then(|window| {
+            window
+                .iter()
+                .position(|elt| interpreter.equals(elt, &args[0]))
+        });
+    match found {
+        Some(pos) => Ok(Value::Int((start + pos) as i64)),
+        None => Err(RuntimeError::new(
+            ErrorKind::ValueError,
+            format!("{} is not in list", interpreter.format_val(&args[0])),
+        )),
+    }
+}
+
+fn count(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    args: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    let n = interpreter
+        .get_list_elts(list_id(receiver))
+        .iter()
+        .filter(|elt| interpreter.equals(elt, &args[0]))
+        .count();
+    Ok(Value::Int(n as i64))
+}
+
+fn reverse(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    _: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    interpreter.get_list_elts_mut(list_id(receiver)).reverse();
+    Ok(Value::Nil)
+}
+
+fn copy(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    _: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    let elts = interpreter.get_list_elts(list_id(receiver)).clone();
+    Ok(interpreter.create_list(elts))
+}
+
+// 정렬할 때 쓰는 비교. NaN은 어떤 수보다도 뒤로 보내서 항상 일관된 순서가 되게 함
+fn compare(interpreter: &Interpreter, lhs: &Value, rhs: &Value) -> Result<Ordering, RuntimeError> {
+    let is_nan = |val: &Value| matches!(val, Value::Number(n) if n.is_nan());
+    match (lhs, rhs) {
+        (
+            Value::Int(_) | Value::BigInt(_) | Value::Number(_),
+            Value::Int(_) | Value::BigInt(_) | Value::Number(_),
+        ) => Ok(compare_numbers(lhs, rhs).unwrap_or_else(|| is_nan(lhs).cmp(&is_nan(rhs)))),
+        (Value::String(s1), Value::String(s2)) => Ok(s1.cmp(s2)),
+        (Value::Tuple(xs), Value::Tuple(ys)) => compare_sequences(interpreter, xs, ys),
+        (Value::List(id1), Value::List(id2)) => compare_sequences(
+            interpreter,
+            interpreter.get_list_elts(*id1),
+            interpreter.get_list_elts(*id2),
+        ),
+        _ => Err(RuntimeError::new(
+            ErrorKind::TypeError,
+            format!(
+                "'<' not supported between {:?} and {:?}",
+                type_of(lhs),
+                type_of(rhs)
+            ),
+        )),
+    }
+}
+
+// 처음으로 다른 원소끼리의 순서를 따르고, 한쪽이 다른 쪽의 앞부분이면 짧은 쪽이 앞
+fn compare_sequences(
+    interpreter: &Interpreter,
+    xs: &[Value],
+    ys: &[Value],
+) -> Result<Ordering, RuntimeError> {
+    match xs.iter().zip(ys).find(|(x, y)| !interpreter.equals(x, y)) {
+        Some((x, y)) => compare(interpreter, x, y),
+        None => Ok(xs.len().cmp(&ys.len())),
+    }
+}
+
+// sort(key=None, reverse=False). 안정 정렬이고, 실패하면 리스트는 그대로 둠
+fn sort(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    _: &[Value],
+    kwargs: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    let mut key = None;
+    let mut reverse = false;
+    for (name, value) in kwargs {
+        match name.as_str() {
+            "key" => key = Some(value).filter(|key| !matches!(key, Value::Nil)),
+            "reverse" => reverse = Interpreter::is_truthy(value),
+            _ => unreachable!(),
+        }
+    }
+
+    let list_id = list_id(receiver);
+    let elts = interpreter.get_list_elts(list_id).clone();
+    let keys = match key {
+        Some(key) => elts
+            .iter()
+            .map(|elt| interpreter.call_value(key, std::slice::from_ref(elt)))
+            .collect::<Result<Vec<_>, _>>()?,
+        None => elts.clone(),
+    };
+
+    let mut err = None;
+    let mut order: Vec<usize> = (0..elts.len()).collect();
+    order.sort_by(|&i, &j| {
+        let (lhs, rhs) = if reverse { (&keys[j], &keys[i]) } else { (&keys[i], &keys[j]) };
+        compare(interpreter, lhs, rhs).unwrap_or_else(|e| {
+            err.get_or_insert(e);
+            Ordering::Equal
+        })
+    });
+    if let Some(err) = err {
+        return Err(err);
+    }
+
+    *interpreter.get_list_elts_mut(list_id) = order.into_iter().map(|i| elts[i].clone()).collect();
+    Ok(Value::Nil)
+}
@@ -6,6 +6,12 @@ mod extensions;
 mod line_reader;
 mod input;
 mod repl;
+mod span;
+mod list;
+mod dict;
+mod set;
+mod bigint;
+mod string;
 
 // Todo: python list
 // 루프 문에 input실행하고 parsing, mut state 전달
@@ -14,21 +20,8 @@ mod repl;
 // 구동할 수 있는 최소 모듈 리팩터
 // 1. single quotation marks 커버드 밸류 역시 스트링으로 받을 수 있게함
 // 2. 파이썬과 같이 선언문 없이 선언할 수 있는 기능 구현(이후에 파싱 시퀀스 바꾸기)
-// 3. 리스트 trait 만들어서 내부 함수들에 파이썬 리스트 함수들 추가하기
 //
 // RC , REFCEL
-// 파이선 리스트 메소드 요소 구현
-// append()
-// extend()
-// insert()
-// remove()
-// pop()
-// clear()
-// index()
-// count()
-// reverse()
-// copy()
-//
 //
 // interpreter 구성요소
 //                       |   source code
@@ -56,14 +49,45 @@ mod repl;
 //
 //
 
-
-use crate::expr::Stmt;
-
 fn main() {
-    let extensions = extensions::Extensions {
+    let mut extensions = extensions::Extensions {
         lists: true,
         lambdas: true,
+        ..Default::default()
     };
+    let mut maybe_path = None;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--python" => extensions.dialect = lexer::Dialect::Python,
+            "-O" => extensions.no_asserts = true,
+            _ if arg.starts_with('-') => {
+                eprintln!("usage: interpreter [--python] [-O] [script]");
+                std::process::exit(2);
+            }
+            _ => maybe_path = Some(arg),
+        }
+    }
+
+    // 스크립트 파일이 주어지면 실행하고 종료
+    if let Some(path) = maybe_path {
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) => {
+                eprintln!("Could not read {}: {}", path, err);
+                std::process::exit(1);
+            }
+        };
+        let input = input::Input {
+            source: input::Source::File(path),
+            content,
+        };
+        let mut interpreter = repl::mk_interpreter();
+        if !repl::eval_input(&mut interpreter, &input, extensions) {
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let mut interpreter = repl::mk_interpreter();
     let mut line_reader = line_reader::LineReader::new(".repl-history.txt", ">>> ");
     println!(
@@ -75,17 +99,14 @@ fn main() {
     loop {
         let readline = line_reader.readline();
         match readline {
-            line_reader::LineReadStatus::Line(line) => match lexer::check_tokens(line.clone()) {
-                Ok(tokens) => {
-                    let mut need_empty_decl = repl::check_eval_tokens(&mut interpreter, tokens.clone(),
-                                                    0, extensions, &line);
-                    if need_empty_decl == true {
-                        repl::eval_tokens2(&mut interpreter, tokens,0, extensions, &line)
-                    }
-                },
-                Err(err) => println!("Tokernizer Failure")
-            },
+            line_reader::LineReadStatus::Line(line) => {
+                let need_empty_decl = repl::check_eval_tokens(&mut interpreter, &line,
+                                                0, extensions);
+                if need_empty_decl {
+                    repl::eval_tokens2(&mut interpreter, &line, 0, extensions)
+                }
+            }
             line_reader::LineReadStatus::Done => break,
         }
     }
-}
\ No newline at end of file
+}
@@ -1,23 +1,37 @@
+use crate::bigint::BigInt;
 use crate::expr;
 use crate::lexer;
 use crate::extensions;
+use crate::span::Span;
 
 use std::fmt;
 
-#[derive(Default)]
-struct Parser {
-    tokens: Vec<lexer::Token>,
-    cursor: usize,
-    in_fundec: bool, // in rust, booleans default to false: https://doc.rust-lang.org/std/primitive.bool.html#impl-Default
+// 토큰은 lexer에서 필요할 때마다 하나씩 받아옴
+struct Parser<'src, I>
+where
+    I: Iterator<Item = Result<lexer::Token<'src>, lexer::Error>>,
+{
+    tokens: std::iter::Peekable<I>,
+    source: &'src str,          // 토큰의 span이 가리키는 소스
+    current: lexer::Token<'src>,
+    previous: lexer::Token<'src>,
+    lex_err: Option<lexer::Error>,
+    errors: Vec<Error<'src>>,   // 복구하면서 모은 에러
+    expected: Vec<lexer::TokenType>, // 현재 토큰 위치에서 accept로 시도해 본 토큰들
+    construct: &'static str,    // 지금 파싱 중인 식의 종류 (에러 메세지용)
+    in_fundec: bool,
+    in_loop: bool,
     extensions: extensions::Extensions,
 }
 
-pub enum Error {
-    UnexpectedToken(lexer::Token),
+pub enum Error<'src> {
+    Lexer(lexer::Error),
+    UnexpectedToken(lexer::Token<'src>),
+    // expected에는 그 위치에서 올 수 있었던 토큰들이 모두 들어감
     TokenMismatch {
-        expected: lexer::TokenType,
-        found: lexer::Token,
-        maybe_on_err_string: Option<String>,
+        expected: Box<[lexer::TokenType]>,
+        found: lexer::Token<'src>,
+        context: String,
     },
     MaxParamsExceeded {
         kind: FunctionKind,
@@ -28,19 +42,47 @@ pub enum Error {
         line: usize,
         col: i64,
     },
+    BreakNotInLoop {
+        line: usize,
+        col: i64,
+    },
+    ContinueNotInLoop {
+        line: usize,
+        col: i64,
+    },
     InvalidAssignment {
         line: usize,
         col: i64,
     },
+    InvalidDeleteTarget {
+        line: usize,
+        col: i64,
+    },
+    DefaultExceptNotLast {
+        line: usize,
+        col: i64,
+    },
+    InvalidStarredExpression {
+        line: usize,
+        col: i64,
+    },
+    InvalidParameter {
+        reason: String,
+        line: usize,
+        col: i64,
+    },
     TooManyArguments {
         line: usize,
         col: i64,
     },
-    ExpectedExpression {
-        token_type: lexer::TokenType,
+    PositionalAfterKeyword {
         line: usize,
         col: i64,
     },
+    ExpectedExpression {
+        found: lexer::Token<'src>,
+        construct: &'static str,
+    },
     InvalidTokenInUnaryOp {
         token_type: lexer::TokenType,
         line: usize,
@@ -53,28 +95,35 @@ pub enum Error {
     },
 }
 
-impl fmt::Debug for Error {
+impl fmt::Display for Error<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match &self {
+            Error::Lexer(err) => write!(f, "{}", err),
             Error::UnexpectedToken(tok) => write!(
                 f,
-                "Unexpected token {:?} at line={},col={}",
-                tok.toktype, tok.line, tok.col
+                "unexpected {} at line={},col={}",
+                tok.describe(),
+                tok.line,
+                tok.col
             ),
             Error::TokenMismatch {
-                maybe_on_err_string,
                 expected,
                 found,
+                context,
             } => {
+                let names: Vec<_> = expected.iter().map(|toktype| toktype.to_string()).collect();
+                match names.as_slice() {
+                    [name] => write!(f, "expected {}", name)?,
+                    _ => write!(f, "expected one of {}", names.join(", "))?,
+                }
                 write!(
                     f,
-                    "Expected token {:?} but found {:?} at line={},col={}",
-                    expected, found.toktype, found.line, found.col
-                )?;
-                if let Some(on_err_string) = maybe_on_err_string {
-                    write!(f, ": {}", on_err_string)?;
-                }
-                fmt::Result::Ok(())
+                    " {}, found {} at line={},col={}",
+                    context,
+                    found.describe(),
+                    found.line,
+                    found.col
+                )
             }
             Error::MaxParamsExceeded { kind, line, col } => write!(
                 f,
@@ -86,22 +135,50 @@ impl fmt::Debug for Error {
                 "return statement not enclosed in a FunDecl at line={},col={}",
                 line, col
             ),
+            Error::BreakNotInLoop { line, col } => write!(
+                f,
+                "break statement not enclosed in a loop at line={},col={}",
+                line, col
+            ),
+            Error::ContinueNotInLoop { line, col } => write!(
+                f,
+                "continue statement not enclosed in a loop at line={},col={}",
+                line, col
+            ),
             Error::InvalidAssignment { line, col } => {
                 write!(f, "invalid assignment target at line={},col={}", line, col)
             }
+            Error::InvalidDeleteTarget { line, col } => {
+                write!(f, "invalid del target at line={},col={}", line, col)
+            }
+            Error::DefaultExceptNotLast { line, col } => {
+                write!(f, "default 'except' must be last at line={},col={}", line, col)
+            }
+            Error::InvalidParameter { reason, line, col } => {
+                write!(f, "{} at line={},col={}", reason, line, col)
+            }
+            Error::InvalidStarredExpression { line, col } => write!(
+                f,
+                "can't use starred expression here at line={},col={}",
+                line, col
+            ),
             Error::TooManyArguments { line, col } => write!(
                 f,
                 "Cannot have more than 255 arguments to a function call. Line={},col={}",
                 line, col
             ),
-            Error::ExpectedExpression {
-                token_type,
-                line,
-                col,
-            } => write!(
+            Error::PositionalAfterKeyword { line, col } => write!(
                 f,
-                "Expected expression, but found token {:?} at line={},col={}",
-                token_type, line, col
+                "Positional argument follows keyword argument. Line={},col={}",
+                line, col
+            ),
+            Error::ExpectedExpression { found, construct } => write!(
+                f,
+                "expected an expression in {}, found {} at line={},col={}",
+                construct,
+                found.describe(),
+                found.line,
+                found.col
             ),
             Error::InvalidTokenInUnaryOp {
                 token_type,
@@ -109,7 +186,7 @@ impl fmt::Debug for Error {
                 col,
             } => write!(
                 f,
-                "invalid token in unary op {:?} at line={},col={}",
+                "invalid token in unary op {} at line={},col={}",
                 token_type, line, col
             ),
             Error::InvalidTokenInBinaryOp {
@@ -118,13 +195,21 @@ impl fmt::Debug for Error {
                 col,
             } => write!(
                 f,
-                "invalid token in binary op {:?} at line={},col={}",
+                "invalid token in binary op {} at line={},col={}",
                 token_type, line, col
             ),
         }
     }
 }
 
+impl fmt::Debug for Error<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for Error<'_> {}
+
 #[derive(Debug)]
 pub enum FunctionKind {
     Function,
@@ -132,90 +217,219 @@ pub enum FunctionKind {
     Lambda,
 }
 
-pub fn parse(
-    extensions: extensions::Extensions,
-    tokens: Vec<lexer::Token>,
-) -> Result<Vec<expr::Stmt>, Error> {
-    let mut p = Parser {
-        tokens,
-        extensions,
-        ..Default::default()
-    };
-    let stmts_or_err = p.parse();
-
-    match stmts_or_err {
-        Ok(stmts_or_err) => {
-            if !p.is_end() {
-                let tok = &p.tokens[p.cursor];
-                Err(Error::UnexpectedToken(tok.clone()))
-            } else {
-                Ok(stmts_or_err)
-            }
+impl fmt::Display for FunctionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FunctionKind::Function => write!(f, "function"),
+            FunctionKind::Method => write!(f, "method"),
+            FunctionKind::Lambda => write!(f, "lambda"),
         }
-        Err(err) => Err(err),
     }
 }
 
+// 에러가 나도 다음 문장부터 계속 파싱함. 파싱된 문장들과 모든 에러를 돌려줌
+pub fn parse<'src>(
+    extensions: extensions::Extensions,
+    source: &'src str,
+    tokens: impl Iterator<Item = Result<lexer::Token<'src>, lexer::Error>>,
+) -> (Vec<expr::Stmt>, Vec<Error<'src>>) {
+    let mut p = Parser::new(extensions, source, tokens);
+    let stmts = p.parse();
+    (stmts, p.finish())
+}
+
 
 
-pub fn parse_varerr(
+pub fn parse_varerr<'src>(
     extensions: extensions::Extensions,
-    tokens: Vec<lexer::Token>,
-) -> Result<Vec<expr::Stmt>, Error> {
-    let mut p = Parser {
-        tokens,
-        extensions,
-        ..Default::default()
-    };
-    let stmts_or_err = p.parse_varerr();
-
-    match stmts_or_err {
-        Ok(stmts_or_err) => {
-            if !p.is_end() {
-                let tok = &p.tokens[p.cursor];
-                Err(Error::UnexpectedToken(tok.clone()))
-            } else {
-                Ok(stmts_or_err)
-            }
+    source: &'src str,
+    tokens: impl Iterator<Item = Result<lexer::Token<'src>, lexer::Error>>,
+) -> (Vec<expr::Stmt>, Vec<Error<'src>>) {
+    let mut p = Parser::new(extensions, source, tokens);
+    let stmts = p.parse_varerr();
+    (stmts, p.finish())
+}
+
+
+impl<'src, I> Parser<'src, I>
+where
+    I: Iterator<Item = Result<lexer::Token<'src>, lexer::Error>>,
+{
+    fn new(extensions: extensions::Extensions, source: &'src str, tokens: I) -> Self {
+        let mut p = Parser {
+            tokens: tokens.peekable(),
+            source,
+            current: lexer::Token {
+                toktype: lexer::TokenType::Eof,
+                lexing: "",
+                literal: None,
+                line: 1,
+                col: -1,
+                span: Span::default(),
+            },
+            previous: lexer::Token {
+                toktype: lexer::TokenType::Eof,
+                lexing: "",
+                literal: None,
+                line: 1,
+                col: -1,
+                span: Span::default(),
+            },
+            lex_err: None,
+            errors: Vec::new(),
+            expected: Vec::new(),
+            construct: "expression",
+            in_fundec: false,
+            in_loop: false,
+            extensions,
+        };
+        p.current = p.pull();
+        p.previous = p.current.clone();
+        p
+    }
+
+    // lexer 에러는 소스 상 마지막이므로 맨 뒤에 붙임
+    fn finish(&mut self) -> Vec<Error<'src>> {
+        if let Some(err) = self.lex_err.take() {
+            self.errors.push(Error::Lexer(err));
         }
-        Err(err) => Err(err),
+        std::mem::take(&mut self.errors)
     }
-}
 
+    pub fn parse(&mut self) -> Vec<expr::Stmt> { // 선언있는지 확인 후 스테이트먼트로 넘김
+        let mut statements = Vec::new();
+        self.declarations(&mut statements);
+        statements
+    }
 
-impl Parser {
-    pub fn parse(&mut self) -> Result<Vec<expr::Stmt>, Error> { // 선언있는지 확인 후 스테이트먼트로 넘김
+
+    pub fn parse_varerr(&mut self) -> Vec<expr::Stmt> {
         let mut statements = Vec::new();
-        while !self.is_end() {
-            let stmt = self.declaration()?;
-            statements.push(stmt);
+        let start = self.peek().span;
+        let stmt = self.declvar();
+        statements.extend(self.recover(start, stmt));
+        self.declarations(&mut statements);
+        statements
+    }
+
+    // 최상위 문장들. 짝이 없는 } 는 에러로 남기고 건너뜀
+    fn declarations(&mut self, statements: &mut Vec<expr::Stmt>) {
+        while !self.skip_newlines() {
+            if self.check(lexer::TokenType::RightBrace) || self.check(lexer::TokenType::Dedent) {
+                let tok = self.nexting().clone();
+                self.report(Error::UnexpectedToken(tok));
+                continue;
+            }
+            let start = self.peek().span;
+            let stmt = self.declaration();
+            statements.extend(self.recover(start, stmt));
         }
-        Ok(statements)
     }
 
+    // 에러를 기록하고 다음 문장 경계까지 건너뜀 (panic mode)
+    fn recover<T>(&mut self, start: Span, result: Result<T, Error<'src>>) -> Option<T> {
+        match result {
+            Ok(val) => Some(val),
+            Err(err) => {
+                self.report(err);
+                // 토큰을 하나도 읽지 못하고 실패했으면 최소 하나는 건너뜀
+                if self.peek().span == start
+                    && !self.check(lexer::TokenType::Semicolon)
+                    && !self.check(lexer::TokenType::Newline)
+                {
+                    self.nexting();
+                }
+                self.synchronize();
+                None
+            }
+        }
+    }
 
-    pub fn parse_varerr(&mut self) -> Result<Vec<expr::Stmt>, Error> {
-        let mut statements = Vec::new();
-        statements.push(self.declvar()?);
+    // lexer 에러 뒤의 에러는 가짜 Eof 때문에 생긴 것이므로 버림
+    fn report(&mut self, err: Error<'src>) {
+        if self.lex_err.is_none() {
+            self.errors.push(err);
+        }
+    }
+
+    // 문장 키워드 앞이나 ;, 줄바꿈 뒤에서 멈춤.
+    // 블록을 닫는 } 와 Dedent는 바깥 블록이 처리하도록 남겨둠
+    fn synchronize(&mut self) {
         while !self.is_end() {
-            let stmt = self.declaration()?;
-            statements.push(stmt);
+            match self.peek().toktype {
+                lexer::TokenType::RightBrace
+                | lexer::TokenType::Dedent
+                | lexer::TokenType::Def
+                | lexer::TokenType::Class
+                | lexer::TokenType::Var
+                | lexer::TokenType::For
+                | lexer::TokenType::If
+                | lexer::TokenType::While
+                | lexer::TokenType::Print
+                | lexer::TokenType::Return
+                | lexer::TokenType::Break
+                | lexer::TokenType::Continue
+                | lexer::TokenType::Del
+                | lexer::TokenType::Try
+                | lexer::TokenType::Raise
+                | lexer::TokenType::Assert => return,
+                lexer::TokenType::Semicolon => {
+                    self.nexting();
+                    return;
+                }
+                lexer::TokenType::Newline => {
+                    self.nexting();
+                    // 실패한 헤더 뒤의 들여쓴 블록은 통째로 건너뜀
+                    if self.check(lexer::TokenType::Indent) {
+                        self.skip_group(lexer::TokenType::Indent, lexer::TokenType::Dedent);
+                    }
+                    return;
+                }
+                lexer::TokenType::LeftBrace => {
+                    self.skip_group(lexer::TokenType::LeftBrace, lexer::TokenType::RightBrace);
+                    return;
+                }
+                _ => {
+                    self.nexting();
+                }
+            }
         }
-        Ok(statements)
     }
 
+    // 여는 토큰부터 짝이 맞는 닫는 토큰까지 건너뜀
+    fn skip_group(&mut self, open: lexer::TokenType, close: lexer::TokenType) {
+        let mut depth = 0;
+        while !self.is_end() {
+            let toktype = self.nexting().toktype;
+            if toktype == open {
+                depth += 1;
+            } else if toktype == close {
+                depth -= 1;
+                if depth == 0 {
+                    return;
+                }
+            }
+        }
+    }
 
-    pub fn declvar(&mut self) -> Result<expr::Stmt, Error> {        // 무지성 var_decl 함수
-        return self.var_decl()
+    pub fn declvar(&mut self) -> Result<expr::Stmt, Error<'src>> {        // 무지성 var_decl 함수
+        let start = self.peek().span;
+        self.var_decl(start)
     }
 
-    fn declaration(&mut self) -> Result<expr::Stmt, Error> {
+    fn declaration(&mut self) -> Result<expr::Stmt, Error<'src>> {
         if self.matches(lexer::TokenType::Var) {              // 변수 선언 방식 고치기
-            return self.var_decl();
+            let start = self.previous().span;
+            return self.var_decl(start);
         }
 
         if self.matches(lexer::TokenType::Def) {
-            return Ok(expr::Stmt::FunDecl(self.fun_decl(FunctionKind::Function)?));
+            let start = self.previous().span;
+            let fun_decl = self.fun_decl(FunctionKind::Function)?;
+            return Ok(expr::Stmt::new(
+                expr::StmtKind::FunDecl(fun_decl),
+                self.span_from(start),
+            ));
         }
 
         if self.matches(lexer::TokenType::Class) {
@@ -225,22 +439,23 @@ impl Parser {
         self.statement()
     }
 
-    fn class_decl(&mut self) -> Result<expr::Stmt, Error> {
+    fn class_decl(&mut self) -> Result<expr::Stmt, Error<'src>> {
+        let start = self.previous().span;
         let name_tok = self
-            .consume(lexer::TokenType::Identifier, "Expected class name")?
+            .consume(lexer::TokenType::Identifier, "as class name")?
             .clone();
 
         let class_symbol = expr::Symbol {
-            name: String::from_utf8(name_tok.lexing).unwrap(),
+            name: name_tok.lexing.to_string(),
             line: name_tok.line,
             col: name_tok.col,
         };
 
-        let superclass_maybe = if self.matches(lexer::TokenType::Less) {
+        let superclass_maybe = if self.accept(lexer::TokenType::Less) {
             let superclass_tok =
-                self.consume(lexer::TokenType::Identifier, "Expected class name.")?;
+                self.consume(lexer::TokenType::Identifier, "as superclass name")?;
             Some(expr::Symbol {
-                name: String::from_utf8(superclass_tok.lexing.clone()).unwrap(),
+                name: superclass_tok.lexing.to_string(),
                 line: superclass_tok.line,
                 col: superclass_tok.col,
             })
@@ -248,36 +463,41 @@ impl Parser {
             None
         };
 
-        self.consume(lexer::TokenType::LeftBrace, "Expected { after class name")?;
+        self.consume(lexer::TokenType::LeftBrace, "before class body")?;
 
         let mut methods = Vec::new();
         while !self.check(lexer::TokenType::RightBrace) && !self.is_end() {
-            methods.push(self.fun_decl(FunctionKind::Method)?);
+            let start = self.peek().span;
+            let method = self.fun_decl(FunctionKind::Method);
+            methods.extend(self.recover(start, method));
         }
         let methods = methods;
 
         self.consume(
             lexer::TokenType::RightBrace,
-            "Expected } after class body",
+            "at end of class body",
         )?;
 
-        Ok(expr::Stmt::ClassDecl(expr::ClassDecl {
-            name: class_symbol,
-            superclass: superclass_maybe,
-            methods,
-        }))
+        Ok(expr::Stmt::new(
+            expr::StmtKind::ClassDecl(expr::ClassDecl {
+                name: class_symbol,
+                superclass: superclass_maybe,
+                methods,
+            }),
+            self.span_from(start),
+        ))
     }
 
-    fn fun_decl(&mut self, kind: FunctionKind) -> Result<expr::FunDecl, Error> {
+    fn fun_decl(&mut self, kind: FunctionKind) -> Result<expr::FunDecl, Error<'src>> {
         let name_tok = self
             .consume(
                 lexer::TokenType::Identifier,
-                format!("Expected {:?} name", kind).as_ref(),
+                format!("as {} name", kind).as_ref(),
             )?
             .clone();
 
         let fun_symbol = expr::Symbol {
-            name: String::from_utf8(name_tok.lexing).unwrap(),
+            name: name_tok.lexing.to_string(),
             line: name_tok.line,
             col: name_tok.col,
         };
@@ -294,13 +514,15 @@ impl Parser {
     fn params_and_body(
         &mut self,
         kind: FunctionKind,
-    ) -> Result<(Vec<expr::Symbol>, Vec<expr::Stmt>), Error> {
+    ) -> Result<(Vec<expr::Param>, Vec<expr::Stmt>), Error<'src>> {
         self.consume(
             lexer::TokenType::LeftParen,
-            format!("Expected ( after {:?} name", kind).as_ref(),
+            format!("after {} name", kind).as_ref(),
         )?;
 
-        let mut parameters = Vec::new();
+        let mut parameters: Vec<expr::Param> = Vec::new();
+        // *args나 단독 * 뒤의 매개변수는 키워드로만 받음
+        let mut keyword_only = false;
 
         if !self.check(lexer::TokenType::RightParen) {
             loop {
@@ -313,17 +535,79 @@ impl Parser {
                     });
                 }
 
+                let start_tok = self.peek().clone();
+                let invalid = |reason: String| Error::InvalidParameter {
+                    reason,
+                    line: start_tok.line,
+                    col: start_tok.col,
+                };
+                if parameters
+                    .iter()
+                    .any(|param| param.kind == expr::ParamKind::VarKeywords)
+                {
+                    return Err(invalid("parameter follows **kwargs".to_string()));
+                }
+                let param_kind = if self.matches(lexer::TokenType::StarStar) {
+                    expr::ParamKind::VarKeywords
+                } else if self.matches(lexer::TokenType::Star) {
+                    if keyword_only {
+                        return Err(invalid("* argument may appear only once".to_string()));
+                    }
+                    keyword_only = true;
+                    // def f(a, *, b): 이름 없는 *는 표시만 함
+                    if self.matches(lexer::TokenType::Comma) {
+                        continue;
+                    }
+                    expr::ParamKind::VarArgs
+                } else if keyword_only {
+                    expr::ParamKind::KeywordOnly
+                } else {
+                    expr::ParamKind::Normal
+                };
+
                 let tok = self
-                    .consume(lexer::TokenType::Identifier, "Expected parameter name")?
+                    .consume(lexer::TokenType::Identifier, "as parameter name")?
                     .clone();
+                if parameters.iter().any(|param| param.name.name == tok.lexing) {
+                    return Err(invalid(format!(
+                        "duplicate argument '{}' in function definition",
+                        tok.lexing
+                    )));
+                }
 
-                parameters.push(expr::Symbol {
-                    name: String::from_utf8(tok.lexing).unwrap(),
-                    line: tok.line,
-                    col: tok.col,
+                let default = if self.matches(lexer::TokenType::Equal) {
+                    if matches!(
+                        param_kind,
+                        expr::ParamKind::VarArgs | expr::ParamKind::VarKeywords
+                    ) {
+                        return Err(invalid(
+                            "variadic parameter cannot have default value".to_string(),
+                        ));
+                    }
+                    Some(self.expression_in("default value")?)
+                } else {
+                    None
+                };
+                if param_kind == expr::ParamKind::Normal
+                    && default.is_none()
+                    && parameters.iter().any(|param| param.default.is_some())
+                {
+                    return Err(invalid(
+                        "non-default argument follows default argument".to_string(),
+                    ));
+                }
+
+                parameters.push(expr::Param {
+                    name: expr::Symbol {
+                        name: tok.lexing.to_string(),
+                        line: tok.line,
+                        col: tok.col,
+                    },
+                    kind: param_kind,
+                    default,
                 });
 
-                if !self.matches(lexer::TokenType::Comma) {
+                if !self.accept(lexer::TokenType::Comma) {
                     break;
                 }
             }
@@ -332,47 +616,55 @@ impl Parser {
 
         self.consume(
             lexer::TokenType::RightParen,
-            "Expected ) after parameter list",
-        )?;
-        self.consume(
-            lexer::TokenType::LeftBrace,
-            "Expected { before function body",
+            "after parameter",
         )?;
+        let colon = self.is_python() && self.matches(lexer::TokenType::Colon);
+        if !colon {
+            self.consume(
+                lexer::TokenType::LeftBrace,
+                "before function body",
+            )?;
+        }
+        // 함수 본문에서는 바깥 루프로 break/continue 할 수 없음
         let saved_is_in_fundec = self.in_fundec;
+        let saved_is_in_loop = self.in_loop;
         self.in_fundec = true;
-        let body = self.block()?;
+        self.in_loop = false;
+        let body = if colon { self.suite() } else { self.block() };
         self.in_fundec = saved_is_in_fundec;
+        self.in_loop = saved_is_in_loop;
+        let body = body?;
 
         Ok((parameters, body))
     }
 
-    fn var_decl(&mut self) -> Result<expr::Stmt, Error> {
+    fn var_decl(&mut self, start: Span) -> Result<expr::Stmt, Error<'src>> {
         let name_token = self
-            .consume(lexer::TokenType::Identifier, "Expected variable name")?
+            .consume(lexer::TokenType::Identifier, "as variable name")?
             .clone();
 
-        let maybe_initializer = if self.matches(lexer::TokenType::Equal) {
-            Some(self.expression()?)
+        let maybe_initializer = if self.accept(lexer::TokenType::Equal) {
+            Some(self.expression_list_in("variable initializer")?)
         } else {
             None
         };
 
-        self.consume(
-            lexer::TokenType::Semicolon,
-            "Expected ; after variable declaration",
-        )?;
+        self.end_statement("after variable declaration")?;
 
-        Ok(expr::Stmt::VarDecl(
-            expr::Symbol {
-                name: String::from_utf8(name_token.lexing).unwrap(),
-                line: name_token.line,
-                col: name_token.col,
-            },
-            maybe_initializer,
+        Ok(expr::Stmt::new(
+            expr::StmtKind::VarDecl(
+                expr::Symbol {
+                    name: name_token.lexing.to_string(),
+                    line: name_token.line,
+                    col: name_token.col,
+                },
+                maybe_initializer,
+            ),
+            self.span_from(start),
         ))
     }
 
-    fn statement(&mut self) -> Result<expr::Stmt, Error> {
+    fn statement(&mut self) -> Result<expr::Stmt, Error<'src>> {
         if self.matches(lexer::TokenType::Print) {
             return self.print_statement();
         }
@@ -382,7 +674,12 @@ impl Parser {
         }
 
         if self.matches(lexer::TokenType::LeftBrace) {
-            return Ok(expr::Stmt::Block(self.block()?));
+            let start = self.previous().span;
+            let stmts = self.block()?;
+            return Ok(expr::Stmt::new(
+                expr::StmtKind::Block(stmts),
+                self.span_from(start),
+            ));
         }
 
         if self.matches(lexer::TokenType::For) {
@@ -397,10 +694,30 @@ impl Parser {
             return self.return_statement();
         }
 
+        if self.match_one_of(vec![lexer::TokenType::Break, lexer::TokenType::Continue]) {
+            return self.loop_control_statement();
+        }
+
+        if self.matches(lexer::TokenType::Del) {
+            return self.del_statement();
+        }
+
+        if self.matches(lexer::TokenType::Try) {
+            return self.try_statement();
+        }
+
+        if self.matches(lexer::TokenType::Raise) {
+            return self.raise_statement();
+        }
+
+        if self.matches(lexer::TokenType::Assert) {
+            return self.assert_statement();
+        }
+
         self.expression_statement()
     }
 
-    fn return_statement(&mut self) -> Result<expr::Stmt, Error> {
+    fn return_statement(&mut self) -> Result<expr::Stmt, Error<'src>> {
         let prev_tok = self.previous().clone();
 
         if !self.in_fundec {
@@ -410,37 +727,183 @@ impl Parser {
             });
         }
 
-        let maybe_retval = if !self.matches(lexer::TokenType::Semicolon) {
-            Some(self.expression()?)
+        let maybe_retval = if !self.at_statement_end() {
+            Some(self.expression_list_in("return value")?)
         } else {
             None
         };
 
-        if maybe_retval.is_some() {
-            self.consume(
-                lexer::TokenType::Semicolon,
-                "Expected ; after return value",
-            )?;
+        self.end_statement("after return statement")?;
+
+        Ok(expr::Stmt::new(
+            expr::StmtKind::Return(maybe_retval),
+            self.span_from(prev_tok.span),
+        ))
+    }
+
+    fn loop_control_statement(&mut self) -> Result<expr::Stmt, Error<'src>> {
+        let tok = self.previous().clone();
+        let kind = match tok.toktype {
+            lexer::TokenType::Break if self.in_loop => expr::StmtKind::Break,
+            lexer::TokenType::Continue if self.in_loop => expr::StmtKind::Continue,
+            lexer::TokenType::Break => {
+                return Err(Error::BreakNotInLoop {
+                    line: tok.line,
+                    col: tok.col,
+                })
+            }
+            _ => {
+                return Err(Error::ContinueNotInLoop {
+                    line: tok.line,
+                    col: tok.col,
+                })
+            }
+        };
+        self.end_statement(format!("after {}", tok.lexing).as_ref())?;
+
+        Ok(expr::Stmt::new(kind, self.span_from(tok.span)))
+    }
+
+    fn del_statement(&mut self) -> Result<expr::Stmt, Error<'src>> {
+        let del_tok = self.previous().clone();
+        let target = self.expression_in("del statement")?;
+        if !matches!(target.kind, expr::ExprKind::Subscript { .. }) {
+            return Err(Error::InvalidDeleteTarget {
+                line: del_tok.line,
+                col: del_tok.col,
+            });
         }
+        self.end_statement("after del statement")?;
 
-        Ok(expr::Stmt::Return(
-            expr::SourceLocation {
-                line: prev_tok.line,
-                col: prev_tok.col,
-            },
-            maybe_retval,
+        Ok(expr::Stmt::new(
+            expr::StmtKind::Del(target),
+            self.span_from(del_tok.span),
+        ))
+    }
+
+    fn raise_statement(&mut self) -> Result<expr::Stmt, Error<'src>> {
+        let raise_tok = self.previous().clone();
+        let maybe_exception = if !self.at_statement_end() {
+            Some(self.expression_in("raise statement")?)
+        } else {
+            None
+        };
+        self.end_statement("after raise statement")?;
+
+        Ok(expr::Stmt::new(
+            expr::StmtKind::Raise(
+                expr::SourceLocation {
+                    line: raise_tok.line,
+                    col: raise_tok.col,
+                },
+                maybe_exception,
+            ),
+            self.span_from(raise_tok.span),
+        ))
+    }
+
+    // assert 조건, 메세지. no_asserts면 빈 블록이 되어 조건도 평가하지 않음
+    fn assert_statement(&mut self) -> Result<expr::Stmt, Error<'src>> {
+        let assert_tok = self.previous().clone();
+        let cond = self.expression_in("assert statement")?;
+        let message = if self.accept(lexer::TokenType::Comma) {
+            Some(self.expression_in("assert message")?)
+        } else {
+            None
+        };
+        self.end_statement("after assert statement")?;
+
+        let kind = if self.extensions.no_asserts {
+            expr::StmtKind::Block(Vec::new())
+        } else {
+            expr::StmtKind::Assert(expr::Assertion {
+                source: self.source[cond.span.start..cond.span.end].to_string(),
+                cond,
+                message,
+                line: assert_tok.line,
+                col: assert_tok.col,
+            })
+        };
+        Ok(expr::Stmt::new(kind, self.span_from(assert_tok.span)))
+    }
+
+    // try 뒤에는 except 절이나 finally 절이 하나 이상 있어야 함
+    fn try_statement(&mut self) -> Result<expr::Stmt, Error<'src>> {
+        let start = self.previous().span;
+        let body = Box::new(self.body()?);
+
+        let mut handlers: Vec<expr::ExceptHandler> = Vec::new();
+        while self.accept(lexer::TokenType::Except) {
+            let except_tok = self.previous().clone();
+            if handlers.last().is_some_and(|handler| handler.class.is_none()) {
+                return Err(Error::DefaultExceptNotLast {
+                    line: except_tok.line,
+                    col: except_tok.col,
+                });
+            }
+            let class = if self.check(lexer::TokenType::Colon)
+                || self.check(lexer::TokenType::LeftBrace)
+            {
+                None
+            } else {
+                Some(self.expression_in("except clause")?)
+            };
+            let name = if class.is_some() && self.matches(lexer::TokenType::As) {
+                let name_tok = self.consume(lexer::TokenType::Identifier, "after as")?;
+                Some(expr::Symbol {
+                    name: name_tok.lexing.to_string(),
+                    line: name_tok.line,
+                    col: name_tok.col,
+                })
+            } else {
+                None
+            };
+            let body = self.body()?;
+            handlers.push(expr::ExceptHandler { class, name, body });
+        }
+
+        let maybe_else = if !handlers.is_empty() && self.matches(lexer::TokenType::Else) {
+            Some(Box::new(self.body()?))
+        } else {
+            None
+        };
+
+        let maybe_finally = if handlers.is_empty() {
+            self.consume(lexer::TokenType::Finally, "after try block")?;
+            Some(Box::new(self.body()?))
+        } else if self.matches(lexer::TokenType::Finally) {
+            Some(Box::new(self.body()?))
+        } else {
+            None
+        };
+
+        Ok(expr::Stmt::new(
+            expr::StmtKind::Try(body, handlers, maybe_else, maybe_finally),
+            self.span_from(start),
         ))
     }
 
-    fn for_statement(&mut self) -> Result<expr::Stmt, Error> {
-        self.consume(lexer::TokenType::LeftParen, "Expected ( after for.")?;
+    // 루프 본문을 파싱하는 동안만 break/continue를 허용함
+    fn loop_body(&mut self) -> Result<expr::Stmt, Error<'src>> {
+        let saved_is_in_loop = self.in_loop;
+        self.in_loop = true;
+        let body = self.body();
+        self.in_loop = saved_is_in_loop;
+        body
+    }
+
+    fn for_statement(&mut self) -> Result<expr::Stmt, Error<'src>> {
+        let start = self.previous().span;
+        if self.check(lexer::TokenType::Identifier) || self.check(lexer::TokenType::Star) {
+            return self.for_in_statement(start);
+        }
+        self.consume(lexer::TokenType::LeftParen, "after for")?;
 
         let mut maybe_initializer: Option<expr::Stmt> = None;
         if self.matches(lexer::TokenType::Semicolon) {
-        } else if self.matches(lexer::TokenType::Equal) {
-            // statements.push(self.declvar());
-            maybe_initializer = Some(self.var_decl()?)
-            // maybe_initializer = Some(Ok);
+        } else if self.matches(lexer::TokenType::Var) {
+            let start = self.previous().span;
+            maybe_initializer = Some(self.var_decl(start)?)
         } else {
             maybe_initializer = Some(self.expression_statement()?)
         }
@@ -448,176 +911,557 @@ impl Parser {
 
         let mut maybe_condition: Option<expr::Expr> = None;
         if !self.check(lexer::TokenType::Semicolon) {
-            maybe_condition = Some(self.expression()?)
+            maybe_condition = Some(self.expression_in("loop condition")?)
         }
         let maybe_condition = maybe_condition;
 
         self.consume(
             lexer::TokenType::Semicolon,
-            "Expected ; after loop condition",
+            "after loop condition",
         )?;
 
         let maybe_increment = if !self.check(lexer::TokenType::RightParen) {
-            Some(self.expression()?)
+            Some(self.expression_in("loop increment")?)
         } else {
             None
         };
 
         self.consume(
             lexer::TokenType::RightParen,
-            "Expected ) after for clauses",
+            "after for clauses",
         )?;
 
-        let mut body = self.statement()?;
-
-        if let Some(increment) = maybe_increment {
-            body = expr::Stmt::Block(vec![body, expr::Stmt::Expr(increment)])
-        }
+        let mut body = self.loop_body()?;
+        let span = self.span_from(start);
 
         let condition = match maybe_condition {
             Some(cond) => cond,
-            None => expr::Expr::Literal(expr::Literal::True),
+            None => expr::Expr::new(expr::ExprKind::Literal(expr::Literal::True), span),
         };
-        body = expr::Stmt::While(condition, Box::new(body));
+        body = expr::Stmt::new(
+            expr::StmtKind::While(condition, Box::new(body), maybe_increment),
+            span,
+        );
 
         if let Some(initializer) = maybe_initializer {
-            body = expr::Stmt::Block(vec![initializer, body])
+            body = expr::Stmt::new(expr::StmtKind::Block(vec![initializer, body]), span)
         }
         let body = body;
 
         Ok(body)
     }
 
-    fn while_statement(&mut self) -> Result<expr::Stmt, Error> {
-        self.consume(lexer::TokenType::LeftParen, "Expected ( after while")?;
-        let cond = self.expression()?;
-        self.consume(
-            lexer::TokenType::RightParen,
-            "Expected ) after while condition",
-        )?;
-        let body = Box::new(self.statement()?);
-        Ok(expr::Stmt::While(cond, body))
+    // for x in iterable: 반복은 인터프리터가 직접 수행함
+    // 대상은 for k, v in ... 처럼 구조 분해할 수 있음
+    fn for_in_statement(&mut self, start: Span) -> Result<expr::Stmt, Error<'src>> {
+        let target = self.for_target()?;
+        let iterable = self.expression_in("for loop iterable")?;
+        let body = Box::new(self.loop_body()?);
+
+        Ok(expr::Stmt::new(
+            expr::StmtKind::ForIn(
+                target,
+                iterable,
+                body,
+            ),
+            self.span_from(start),
+        ))
     }
 
-    fn if_statement(&mut self) -> Result<expr::Stmt, Error> {
-        self.consume(lexer::TokenType::LeftParen, "Expected ( after if.")?;
-        let cond = self.expression()?;
-        self.consume(
-            lexer::TokenType::RightParen,
-            "Expected ) after if condition.",
-        )?;
-        let then_branch = Box::new(self.statement()?);
-        let maybe_else_branch = if self.matches(lexer::TokenType::Else) {
-            Some(Box::new(self.statement()?))
+    // for 바로 뒤부터 in까지. for 루프와 컴프리헨션이 같이 씀
+    fn for_target(&mut self) -> Result<expr::Pattern, Error<'src>> {
+        let for_tok = self.previous().clone();
+        let first = self.target_element()?;
+        let target = if self.check(lexer::TokenType::Comma) {
+            let mut elements = vec![first];
+            while self.accept(lexer::TokenType::Comma) {
+                if self.check(lexer::TokenType::In) {
+                    break;
+                }
+                elements.push(self.target_element()?);
+            }
+            let span = self.span_from(elements[0].span);
+            expr::Expr::new(expr::ExprKind::Tuple(elements), span)
+        } else {
+            first
+        };
+        let target = Self::to_pattern(target, &for_tok)?;
+        self.consume(lexer::TokenType::In, "after for loop variable")?;
+        Ok(target)
+    }
+
+    // 첫 for는 이미 읽은 상태. 반복 대상과 조건은 or 수준까지만 읽어서
+    // 파이썬의 if가 조건식으로 먹히지 않게 함
+    fn comp_clauses(&mut self) -> Result<Vec<expr::CompClause>, Error<'src>> {
+        let mut clauses = Vec::new();
+        loop {
+            let target = self.for_target()?;
+            let saved_construct = std::mem::replace(&mut self.construct, "comprehension");
+            let iterable = self.or();
+            self.construct = saved_construct;
+            clauses.push(expr::CompClause::For(target, iterable?));
+
+            while self.matches(lexer::TokenType::If) {
+                let saved_construct = std::mem::replace(&mut self.construct, "comprehension");
+                let cond = self.or();
+                self.construct = saved_construct;
+                clauses.push(expr::CompClause::If(cond?));
+            }
+            if !self.matches(lexer::TokenType::For) {
+                return Ok(clauses);
+            }
+        }
+    }
+
+    fn while_statement(&mut self) -> Result<expr::Stmt, Error<'src>> {
+        let start = self.previous().span;
+        let cond = self.condition("while")?;
+        let body = Box::new(self.loop_body()?);
+        Ok(expr::Stmt::new(
+            expr::StmtKind::While(cond, body, None),
+            self.span_from(start),
+        ))
+    }
+
+    // elif는 else 쪽에 중첩된 if로 만듦. 각 if는 자기 elif 토큰부터의 범위를 가짐
+    fn if_statement(&mut self) -> Result<expr::Stmt, Error<'src>> {
+        let start = self.previous().span;
+        let cond = self.condition("if")?;
+        let then_branch = Box::new(self.body()?);
+        let maybe_else_branch = if self.matches(lexer::TokenType::Elif) {
+            Some(Box::new(self.if_statement()?))
+        } else if self.matches(lexer::TokenType::Else) {
+            Some(Box::new(self.body()?))
         } else {
             None
         };
 
-        Ok(expr::Stmt::If(cond, then_branch, maybe_else_branch))
+        Ok(expr::Stmt::new(
+            expr::StmtKind::If(cond, then_branch, maybe_else_branch),
+            self.span_from(start),
+        ))
     }
 
-    fn block(&mut self) -> Result<Vec<expr::Stmt>, Error> {
+    fn block(&mut self) -> Result<Vec<expr::Stmt>, Error<'src>> {
         let mut stmts = Vec::new();
 
-        while !self.check(lexer::TokenType::RightBrace) && !self.is_end() {
-            stmts.push(self.declaration()?)
+        while !self.skip_newlines() && !self.check(lexer::TokenType::RightBrace) {
+            let start = self.peek().span;
+            let stmt = self.declaration();
+            stmts.extend(self.recover(start, stmt));
         }
 
-        self.consume(lexer::TokenType::RightBrace, "Expected } after block.")?;
+        self.consume(lexer::TokenType::RightBrace, "at end of block")?;
 
         Ok(stmts)
     }
 
-    fn print_statement(&mut self) -> Result<expr::Stmt, Error> {
-        let expr = self.expression()?;
-        self.consume(lexer::TokenType::Semicolon, "Expected ; after value")?;
-        Ok(expr::Stmt::Print(expr))
+    // 파이썬 방언의 `:` 뒤 블록. 같은 줄의 문장 하나 또는 들여쓴 여러 줄
+    fn suite(&mut self) -> Result<Vec<expr::Stmt>, Error<'src>> {
+        if !self.matches(lexer::TokenType::Newline) {
+            return Ok(vec![self.statement()?]);
+        }
+
+        self.consume(lexer::TokenType::Indent, "to start an indented block")?;
+        let mut stmts = Vec::new();
+        while !self.skip_newlines() && !self.check(lexer::TokenType::Dedent) {
+            let start = self.peek().span;
+            let stmt = self.declaration();
+            stmts.extend(self.recover(start, stmt));
+        }
+        self.consume(lexer::TokenType::Dedent, "at end of indented block")?;
+
+        Ok(stmts)
     }
 
-    fn expression_statement(&mut self) -> Result<expr::Stmt, Error> {
-        let expr = self.expression()?;
-        self.consume(lexer::TokenType::Semicolon, "Expected ; after value")?;
-        Ok(expr::Stmt::Expr(expr))
+    // if, while 등의 본문: `{ ... }` 또는 (파이썬 방언) `: ...`
+    fn body(&mut self) -> Result<expr::Stmt, Error<'src>> {
+        if self.is_python() && self.matches(lexer::TokenType::Colon) {
+            let start = self.previous().span;
+            let stmts = self.suite()?;
+            return Ok(expr::Stmt::new(
+                expr::StmtKind::Block(stmts),
+                self.span_from(start),
+            ));
+        }
+        self.statement()
     }
 
-    fn expression(&mut self) -> Result<expr::Expr, Error> {
-        self.assignment()
+    // 파이썬 방언에서는 조건식의 괄호를 생략할 수 있음
+    fn condition(&mut self, keyword: &str) -> Result<expr::Expr, Error<'src>> {
+        if self.is_python() {
+            return self.expression_in("condition");
+        }
+        self.consume(
+            lexer::TokenType::LeftParen,
+            format!("after {}", keyword).as_ref(),
+        )?;
+        let cond = self.expression_in("condition")?;
+        self.consume(
+            lexer::TokenType::RightParen,
+            format!("after {} condition", keyword).as_ref(),
+        )?;
+        Ok(cond)
     }
 
-    fn assignment(&mut self) -> Result<expr::Expr, Error> {
-        let expr = self.or()?;
+    fn is_python(&self) -> bool {
+        self.extensions.dialect == lexer::Dialect::Python
+    }
 
-        if self.matches(lexer::TokenType::Equal) {
-            let equals = self.previous().clone();
-            let new_value = self.assignment()?;
+    // 문장 끝. 파이썬 방언에서는 줄바꿈이나 블록 끝도 문장을 끝냄
+    fn at_statement_end(&self) -> bool {
+        self.check(lexer::TokenType::Semicolon)
+            || (self.is_python()
+                && (self.check(lexer::TokenType::Newline)
+                    || self.check(lexer::TokenType::Dedent)
+                    || self.is_end()))
+    }
 
-            if let expr::Expr::Variable(sym) = &expr {
-                return Ok(expr::Expr::Assign(sym.clone(), Box::new(new_value)));
-            } else if let expr::Expr::Get(e, attr) = expr {
-                return Ok(expr::Expr::Set(e, attr, Box::new(new_value)));
-            }
-            if let expr::Expr::Subscript {
-                value,
-                slice,
-                source_location,
-            } = expr
-            {
-                return Ok(expr::Expr::SetItem {
-                    lhs: value,
-                    slice,
-                    rhs: Box::new(new_value),
-                    source_location,
-                });
-            } else {
-                return Err(Error::InvalidAssignment {
-                    line: equals.line,
-                    col: equals.col,
-                });
+    fn end_statement(&mut self, context: &str) -> Result<(), Error<'src>> {
+        if self.is_python() {
+            if self.at_statement_end() {
+                self.matches(lexer::TokenType::Semicolon);
+                self.matches(lexer::TokenType::Newline);
+                return Ok(());
             }
+            self.accept(lexer::TokenType::Newline);
+        }
+        self.consume(lexer::TokenType::Semicolon, context)?;
+        Ok(())
+    }
+
+    // `}` 블록 뒤에 남는 줄바꿈은 건너뜀. 끝에 도달했으면 true
+    fn skip_newlines(&mut self) -> bool {
+        while self.matches(lexer::TokenType::Newline) {}
+        self.is_end()
+    }
+
+    fn print_statement(&mut self) -> Result<expr::Stmt, Error<'src>> {
+        let start = self.previous().span;
+        let expr = self.expression_in("print statement")?;
+        self.end_statement("after statement")?;
+        Ok(expr::Stmt::new(
+            expr::StmtKind::Print(expr),
+            self.span_from(start),
+        ))
+    }
+
+    fn expression_statement(&mut self) -> Result<expr::Stmt, Error<'src>> {
+        let start = self.peek().span;
+        let expr = self.expression_list_in("expression statement")?;
+        self.end_statement("after statement")?;
+        Ok(expr::Stmt::new(
+            expr::StmtKind::Expr(expr),
+            self.span_from(start),
+        ))
+    }
+
+    fn expression(&mut self) -> Result<expr::Expr, Error<'src>> {
+        self.assignment()
+    }
+
+    fn assignment(&mut self) -> Result<expr::Expr, Error<'src>> {
+        let expr = self.conditional()?;
+        self.finish_assignment(expr, false)
+    }
+
+    // 파이썬은 a if c else b, Lox는 c ? a : b. 둘 다 오른쪽으로 묶임
+    fn conditional(&mut self) -> Result<expr::Expr, Error<'src>> {
+        let expr = self.or()?;
+
+        if self.is_python() {
+            if !self.matches(lexer::TokenType::If) {
+                return Ok(expr);
+            }
+            let cond = self.or()?;
+            self.consume(lexer::TokenType::Else, "in conditional expression")?;
+            let otherwise = self.conditional()?;
+            let span = expr.span.to(otherwise.span);
+            return Ok(expr::Expr::new(
+                expr::ExprKind::Conditional(Box::new(cond), Box::new(expr), Box::new(otherwise)),
+                span,
+            ));
+        }
+
+        if !self.matches(lexer::TokenType::Question) {
+            return Ok(expr);
+        }
+        let then = self.expression_in("conditional expression")?;
+        self.consume(lexer::TokenType::Colon, "in conditional expression")?;
+        let otherwise = self.conditional()?;
+        let span = expr.span.to(otherwise.span);
+        Ok(expr::Expr::new(
+            expr::ExprKind::Conditional(Box::new(expr), Box::new(then), Box::new(otherwise)),
+            span,
+        ))
+    }
+
+    // 괄호 없는 튜플을 받는 식: return a, b 나 a, b = b, a
+    fn expression_list(&mut self) -> Result<expr::Expr, Error<'src>> {
+        let first = self.tuple_element()?;
+        if !self.check(lexer::TokenType::Comma) {
+            let first = Self::reject_starred(first)?;
+            return self.finish_assignment(first, true);
+        }
+
+        let mut elements = vec![first];
+        while self.accept(lexer::TokenType::Comma) {
+            // a, = xs 처럼 끝의 쉼표는 허용함
+            if self.at_statement_end()
+                || self.check(lexer::TokenType::Equal)
+                || self.check(lexer::TokenType::RightParen)
+            {
+                break;
+            }
+            elements.push(self.tuple_element()?);
+        }
+        let span = self.span_from(elements[0].span);
+        self.finish_assignment(expr::Expr::new(expr::ExprKind::Tuple(elements), span), true)
+    }
+
+    fn tuple_element(&mut self) -> Result<expr::Expr, Error<'src>> {
+        self.maybe_starred(Self::conditional)
+    }
+
+    // for 대상은 비교식까지 읽으면 in을 연산자로 먹어버리므로 호출식 수준까지만 읽음
+    fn target_element(&mut self) -> Result<expr::Expr, Error<'src>> {
+        self.maybe_starred(Self::call)
+    }
+
+    fn maybe_starred(
+        &mut self,
+        operand: fn(&mut Self) -> Result<expr::Expr, Error<'src>>,
+    ) -> Result<expr::Expr, Error<'src>> {
+        if !self.matches(lexer::TokenType::Star) {
+            return operand(self);
+        }
+        let star = self.previous().clone();
+        let inner = operand(self)?;
+        let span = star.span.to(inner.span);
+        Ok(expr::Expr::new(
+            expr::ExprKind::Starred(
+                Box::new(inner),
+                expr::SourceLocation {
+                    line: star.line,
+                    col: star.col,
+                },
+            ),
+            span,
+        ))
+    }
+
+    // 튜플 밖의 *xs는 에러
+    fn reject_starred(expr: expr::Expr) -> Result<expr::Expr, Error<'src>> {
+        match expr.kind {
+            expr::ExprKind::Starred(_, loc) => Err(Error::InvalidStarredExpression {
+                line: loc.line,
+                col: loc.col,
+            }),
+            _ => Ok(expr),
+        }
+    }
+
+    // 대입 대상이 될 수 있는 식을 패턴으로 바꿈. *는 한 단계에 하나만 올 수 있음
+    fn to_pattern(
+        target: expr::Expr,
+        at: &lexer::Token<'src>,
+    ) -> Result<expr::Pattern, Error<'src>> {
+        let invalid = || Error::InvalidAssignment {
+            line: at.line,
+            col: at.col,
+        };
+        match target.kind {
+            expr::ExprKind::Variable(_)
+            | expr::ExprKind::Get(..)
+            | expr::ExprKind::Subscript { .. } => Ok(expr::Pattern::Target(Box::new(target))),
+            expr::ExprKind::Grouping(inner) => Self::to_pattern(*inner, at),
+            expr::ExprKind::Tuple(elements) => {
+                let mut patterns = Vec::new();
+                for element in elements {
+                    patterns.push(match element.kind {
+                        expr::ExprKind::Starred(inner, _) => match Self::to_pattern(*inner, at)? {
+                            expr::Pattern::Target(inner) => expr::Pattern::Starred(inner),
+                            _ => return Err(invalid()),
+                        },
+                        _ => Self::to_pattern(element, at)?,
+                    });
+                }
+                let starred = patterns
+                    .iter()
+                    .filter(|pattern| matches!(pattern, expr::Pattern::Starred(_)))
+                    .count();
+                if starred > 1 {
+                    return Err(invalid());
+                }
+                Ok(expr::Pattern::Tuple(patterns))
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    // tuples면 오른쪽에도 괄호 없는 튜플을 받음
+    fn finish_assignment(
+        &mut self,
+        expr: expr::Expr,
+        tuples: bool,
+    ) -> Result<expr::Expr, Error<'src>> {
+        if self.match_one_of(vec![
+            lexer::TokenType::Equal,
+            lexer::TokenType::PlusEqual,
+            lexer::TokenType::MinusEqual,
+            lexer::TokenType::StarEqual,
+            lexer::TokenType::SlashEqual,
+            lexer::TokenType::PercentEqual,
+            lexer::TokenType::StarStarEqual,
+            lexer::TokenType::SlashSlashEqual,
+            lexer::TokenType::AmpEqual,
+            lexer::TokenType::PipeEqual,
+            lexer::TokenType::CaretEqual,
+            lexer::TokenType::LessLessEqual,
+            lexer::TokenType::GreaterGreaterEqual,
+        ]) {
+            let equals = self.previous().clone();
+            let maybe_op = match equals.toktype {
+                lexer::TokenType::Equal => None,
+                _ => Some(Self::op_token_to_binop(&equals)?),
+            };
+            let new_value = if tuples {
+                self.expression_list()?
+            } else {
+                self.assignment()?
+            };
+            let target_span = expr.span;
+            let span = target_span.to(new_value.span);
+            let binding = if self.is_python() {
+                expr::Binding::AssignOrDefine
+            } else {
+                expr::Binding::Assign
+            };
+
+            return match expr.kind {
+                expr::ExprKind::Variable(sym) => {
+                    // x += y 는 x = x + y 로 바꿈 (변수 읽기는 부작용이 없음)
+                    let new_value = match maybe_op {
+                        Some(op) => expr::Expr::new(
+                            expr::ExprKind::Binary(
+                                Box::new(expr::Expr::new(
+                                    expr::ExprKind::Variable(sym.clone()),
+                                    target_span,
+                                )),
+                                op,
+                                Box::new(new_value),
+                            ),
+                            span,
+                        ),
+                        None => new_value,
+                    };
+                    Ok(expr::Expr::new(
+                        expr::ExprKind::Assign(sym, Box::new(new_value), binding),
+                        span,
+                    ))
+                }
+                // 속성과 subscript는 대상 식을 한번만 평가하도록 연산자를 같이 넘김
+                expr::ExprKind::Get(e, attr) => Ok(expr::Expr::new(
+                    expr::ExprKind::Set(e, attr, maybe_op, Box::new(new_value)),
+                    span,
+                )),
+                expr::ExprKind::Subscript {
+                    value,
+                    slice,
+                    source_location,
+                } => Ok(expr::Expr::new(
+                    expr::ExprKind::SetItem {
+                        lhs: value,
+                        slice,
+                        op: maybe_op,
+                        rhs: Box::new(new_value),
+                        source_location,
+                    },
+                    span,
+                )),
+                expr::ExprKind::Tuple(_) | expr::ExprKind::Grouping(_) if maybe_op.is_none() => {
+                    Ok(expr::Expr::new(
+                        expr::ExprKind::Destructure(
+                            Self::to_pattern(expr, &equals)?,
+                            Box::new(new_value),
+                            binding,
+                        ),
+                        span,
+                    ))
+                }
+                _ => Err(Error::InvalidAssignment {
+                    line: equals.line,
+                    col: equals.col,
+                }),
+            };
         }
 
         Ok(expr)
     }
 
-    fn or(&mut self) -> Result<expr::Expr, Error> {
+    fn or(&mut self) -> Result<expr::Expr, Error<'src>> {
         let mut expr = self.and()?;
 
         while self.matches(lexer::TokenType::Or) {
             let right = self.and()?;
-            expr = expr::Expr::Logical(Box::new(expr), expr::LogicalOp::Or, Box::new(right));
+            let span = expr.span.to(right.span);
+            expr = expr::Expr::new(
+                expr::ExprKind::Logical(Box::new(expr), expr::LogicalOp::Or, Box::new(right)),
+                span,
+            );
         }
 
         Ok(expr)
     }
 
-    fn and(&mut self) -> Result<expr::Expr, Error> {
-        let mut expr = self.equality()?;
+    fn and(&mut self) -> Result<expr::Expr, Error<'src>> {
+        let mut expr = self.not_test()?;
 
         while self.matches(lexer::TokenType::And) {
-            let right = self.equality()?;
-            expr = expr::Expr::Logical(Box::new(expr), expr::LogicalOp::And, Box::new(right));
+            let right = self.not_test()?;
+            let span = expr.span.to(right.span);
+            expr = expr::Expr::new(
+                expr::ExprKind::Logical(Box::new(expr), expr::LogicalOp::And, Box::new(right)),
+                span,
+            );
         }
 
         Ok(expr)
     }
 
-    fn comparison(&mut self) -> Result<expr::Expr, Error> {
-        let mut expr = self.addition()?;
+    // 파이썬처럼 not은 비교보다 느슨하게 묶임: not a == b는 not (a == b)
+    fn not_test(&mut self) -> Result<expr::Expr, Error<'src>> {
+        if self.matches(lexer::TokenType::Not) {
+            let operator_token = self.previous().clone();
+            let right = Box::new(self.not_test()?);
+            let span = operator_token.span.to(right.span);
+            let unary_op = Self::op_token_to_unary_op(&operator_token)?;
+            return Ok(expr::Expr::new(expr::ExprKind::Unary(unary_op, right), span));
+        }
+        self.equality()
+    }
+
+    fn comparison(&mut self) -> Result<expr::Expr, Error<'src>> {
+        let mut expr = self.bit_or()?;
 
         while self.match_one_of(vec![
             lexer::TokenType::Greater,
             lexer::TokenType::GreaterEqual,
             lexer::TokenType::Less,
             lexer::TokenType::LessEqual,
+            lexer::TokenType::In,
+            lexer::TokenType::Not,
+            lexer::TokenType::Is,
         ]) {
             let operator_token = self.previous().clone();
-            let right = Box::new(self.addition()?);
-            let binop_maybe = Parser::op_token_to_binop(&operator_token);
+            let binop_maybe = self.two_word_binop(&operator_token);
+            let right = Box::new(self.bit_or()?);
 
             match binop_maybe {
                 Ok(binop) => {
+                    let span = expr.span.to(right.span);
                     let left = Box::new(expr);
-                    expr = expr::Expr::Binary(left, binop, right);
+                    expr = expr::Expr::new(expr::ExprKind::Binary(left, binop, right), span);
                 }
                 Err(err) => return Err(err),
             }
@@ -625,18 +1469,56 @@ impl Parser {
         Ok(expr)
     }
 
-    fn addition(&mut self) -> Result<expr::Expr, Error> {
+    // 비트 연산은 파이썬과 같은 우선순위: | < ^ < & < 시프트 < 덧셈
+    fn bit_or(&mut self) -> Result<expr::Expr, Error<'src>> {
+        self.left_assoc(vec![lexer::TokenType::Pipe], Self::bit_xor)
+    }
+
+    fn bit_xor(&mut self) -> Result<expr::Expr, Error<'src>> {
+        self.left_assoc(vec![lexer::TokenType::Caret], Self::bit_and)
+    }
+
+    fn bit_and(&mut self) -> Result<expr::Expr, Error<'src>> {
+        self.left_assoc(vec![lexer::TokenType::Amp], Self::shift)
+    }
+
+    fn shift(&mut self) -> Result<expr::Expr, Error<'src>> {
+        self.left_assoc(
+            vec![lexer::TokenType::LessLess, lexer::TokenType::GreaterGreater],
+            Self::addition,
+        )
+    }
+
+    fn left_assoc(
+        &mut self,
+        ops: Vec<lexer::TokenType>,
+        operand: fn(&mut Self) -> Result<expr::Expr, Error<'src>>,
+    ) -> Result<expr::Expr, Error<'src>> {
+        let mut expr = operand(self)?;
+
+        while self.match_one_of(ops.clone()) {
+            let operator_token = self.previous().clone();
+            let right = Box::new(operand(self)?);
+            let binop = Self::op_token_to_binop(&operator_token)?;
+            let span = expr.span.to(right.span);
+            expr = expr::Expr::new(expr::ExprKind::Binary(Box::new(expr), binop, right), span);
+        }
+        Ok(expr)
+    }
+
+    fn addition(&mut self) -> Result<expr::Expr, Error<'src>> {
         let mut expr = self.multiplication()?;
 
         while self.match_one_of(vec![lexer::TokenType::Minus, lexer::TokenType::Plus]) {
             let operator_token = self.previous().clone();
             let right = Box::new(self.multiplication()?);
-            let binop_maybe = Parser::op_token_to_binop(&operator_token);
+            let binop_maybe = Self::op_token_to_binop(&operator_token);
 
             match binop_maybe {
                 Ok(binop) => {
+                    let span = expr.span.to(right.span);
                     let left = Box::new(expr);
-                    expr = expr::Expr::Binary(left, binop, right);
+                    expr = expr::Expr::new(expr::ExprKind::Binary(left, binop, right), span);
                 }
                 Err(err) => return Err(err),
             }
@@ -644,18 +1526,24 @@ impl Parser {
         Ok(expr)
     }
 
-    fn multiplication(&mut self) -> Result<expr::Expr, Error> {
+    fn multiplication(&mut self) -> Result<expr::Expr, Error<'src>> {
         let mut expr = self.unary()?;
 
-        while self.match_one_of(vec![lexer::TokenType::Slash, lexer::TokenType::Star]) {
+        while self.match_one_of(vec![
+            lexer::TokenType::Slash,
+            lexer::TokenType::SlashSlash,
+            lexer::TokenType::Star,
+            lexer::TokenType::Percent,
+        ]) {
             let operator_token = self.previous().clone();
             let right = Box::new(self.unary()?);
-            let binop_maybe = Parser::op_token_to_binop(&operator_token);
+            let binop_maybe = Self::op_token_to_binop(&operator_token);
 
             match binop_maybe {
                 Ok(binop) => {
+                    let span = expr.span.to(right.span);
                     let left = Box::new(expr);
-                    expr = expr::Expr::Binary(left, binop, right);
+                    expr = expr::Expr::new(expr::ExprKind::Binary(left, binop, right), span);
                 }
                 Err(err) => return Err(err),
             }
@@ -663,130 +1551,126 @@ impl Parser {
         Ok(expr)
     }
 
-    fn unary(&mut self) -> Result<expr::Expr, Error> {
-        if self.match_one_of(vec![lexer::TokenType::Bang, lexer::TokenType::Minus]) {
+    fn unary(&mut self) -> Result<expr::Expr, Error<'src>> {
+        if self.match_one_of(vec![
+            lexer::TokenType::Bang,
+            lexer::TokenType::Minus,
+            lexer::TokenType::Tilde,
+        ]) {
             let operator_token = self.previous().clone();
             let right = Box::new(self.unary()?);
-            let unary_op_maybe = Parser::op_token_to_unary_op(&operator_token);
+            let unary_op_maybe = Self::op_token_to_unary_op(&operator_token);
+            let span = operator_token.span.to(right.span);
 
             return match unary_op_maybe {
-                Ok(unary_op) => Ok(expr::Expr::Unary(unary_op, right)),
+                Ok(unary_op) => Ok(expr::Expr::new(expr::ExprKind::Unary(unary_op, right), span)),
                 Err(err) => Err(err),
             };
         }
-        self.call()
+        self.exponent()
+    }
+
+    // 파이썬처럼 **는 단항 연산자보다 먼저 묶이고 오른쪽 결합임: -2 ** 2 == -4, 2 ** -1 == 0.5
+    fn exponent(&mut self) -> Result<expr::Expr, Error<'src>> {
+        let expr = self.call()?;
+
+        if self.matches(lexer::TokenType::StarStar) {
+            let operator_token = self.previous().clone();
+            let right = Box::new(self.unary()?);
+            let binop = Self::op_token_to_binop(&operator_token)?;
+            let span = expr.span.to(right.span);
+            return Ok(expr::Expr::new(
+                expr::ExprKind::Binary(Box::new(expr), binop, right),
+                span,
+            ));
+        }
+        Ok(expr)
     }
 
-    fn call(&mut self) -> Result<expr::Expr, Error> {
+    fn call(&mut self) -> Result<expr::Expr, Error<'src>> {
         let mut expr = self.primary()?;
 
         loop {
             if self.matches(lexer::TokenType::LeftParen) {
                 expr = self.finish_call(expr)?;
-            }
-
-
-
-            // else if self.matches(lexer::TokenType::Dot) {
-            //     let name_tok = self
-            //         .consume(
-            //             lexer::TokenType::Identifier,
-            //             "Expected property name after '.'.",
-            //         )?
-            //         .clone();
-            //     expr = expr::Expr::Get(
-            //         Box::new(expr),
-            //         expr::Symbol {
-            //             name: String::from_utf8(name_tok.lexing).unwrap(),
-            //             line: name_tok.line,
-            //             col: name_tok.col,
-            //         },
-            //     );
-            // }
-
-
-
-            else if self.extensions.lists && self.matches(lexer::TokenType::LeftBracket) {
-                let slice_expr = self.expression()?;
+            } else if self.matches(lexer::TokenType::Dot) {
+                let name_tok = self
+                    .consume(
+                        lexer::TokenType::Identifier,
+                        "as property name after `.`",
+                    )?
+                    .clone();
+                let span = expr.span.to(name_tok.span);
+                expr = expr::Expr::new(
+                    expr::ExprKind::Get(
+                        Box::new(expr),
+                        expr::Symbol {
+                            name: name_tok.lexing.to_string(),
+                            line: name_tok.line,
+                            col: name_tok.col,
+                        },
+                    ),
+                    span,
+                );
+            } else if self.extensions.lists && self.matches(lexer::TokenType::LeftBracket) {
+                let slice_expr = self.subscript_slice()?;
                 let token = self.consume(
                     lexer::TokenType::RightBracket,
-                    "Expected ] after subscript",
+                    "after subscript",
                 )?;
-                expr = expr::Expr::Subscript {
-                    value: Box::new(expr),
-                    slice: Box::new(slice_expr),
-                    source_location: expr::SourceLocation {
-                        line: token.line,
-                        col: token.col,
-                    },
+                let source_location = expr::SourceLocation {
+                    line: token.line,
+                    col: token.col,
                 };
-                // } else {
-                //     break;
-                // }
-
-////////////////////////////////////
-
-
-
-            // dot append test
-            } else if self.extensions.lists && self.matches(lexer::TokenType::Dot) {
-                println!("hhhh");
-                if self.matches(lexer::TokenType::Append) {
-                    if self.matches(lexer::TokenType::LeftParen) {
-                        // let mut list_elements: Vec<expr> = Vec::new();
-                        let slice_expr = self.expression()?;
-                        let token = self.consume(
-                            lexer::TokenType::RightParen,
-                            "Expected ] after subscript",
-                        )?;
-                        expr = expr::Expr::Subscript {
-                            value: Box::new(expr),
-                            slice: Box::new(slice_expr),
-                            source_location: expr::SourceLocation {
-                                line: token.line,
-                                col: token.col,
-                            },
-                        };
-                    }
-                }
-
-
-
-
-
-                println!("hi");
-                // let mut list_elements = Vec::new();
-                // println!("dot");
-                //
-                // if self.check(lexer::TokenType::Append) {
-                //     if self.check(lexer::TokenType::LeftParen) {
-                //         if !self.check(lexer::TokenType::RightParen) {
-                //             loop {
-                //                 list_elements.push(self.expression()?);
-                //             }
-                //         }
-                //     }
-                // }
+                let span = expr.span.to(token.span);
+                expr = expr::Expr::new(
+                    expr::ExprKind::Subscript {
+                        value: Box::new(expr),
+                        slice: Box::new(slice_expr),
+                        source_location,
+                    },
+                    span,
+                );
             } else {
                 break;
             }
+        }
+        Ok(expr)
+    }
 
+    // xs[i] 또는 xs[start:stop:step]. 슬라이스의 각 부분은 생략할 수 있음
+    fn subscript_slice(&mut self) -> Result<expr::Expr, Error<'src>> {
+        let start_span = self.peek().span;
+        let start = if self.matches(lexer::TokenType::Colon) {
+            None
+        } else {
+            let index = self.expression_in("subscript")?;
+            if !self.accept(lexer::TokenType::Colon) {
+                return Ok(index);
+            }
+            Some(Box::new(index))
+        };
 
+        let stop = self.slice_part()?;
+        let step = if self.accept(lexer::TokenType::Colon) {
+            self.slice_part()?
+        } else {
+            None
+        };
+        Ok(expr::Expr::new(
+            expr::ExprKind::Slice { start, stop, step },
+            self.span_from(start_span),
+        ))
+    }
 
-
-
-
-
-
-
-
-
-////////////////////////////////////////////////
+    fn slice_part(&mut self) -> Result<Option<Box<expr::Expr>>, Error<'src>> {
+        if self.check(lexer::TokenType::Colon) || self.check(lexer::TokenType::RightBracket) {
+            return Ok(None);
         }
-        Ok(expr)
+        Ok(Some(Box::new(self.expression_in("slice")?)))
     }
 
-    fn finish_call(&mut self, callee: expr::Expr) -> Result<expr::Expr, Error> {
+    fn finish_call(&mut self, callee: expr::Expr) -> Result<expr::Expr, Error<'src>> {
         let mut arguments = Vec::new();
 
         if !self.check(lexer::TokenType::RightParen) {
@@ -798,8 +1682,26 @@ impl Parser {
                         col: peek_tok.col,
                     });
                 }
-                arguments.push(self.expression()?);
-                if !self.matches(lexer::TokenType::Comma) {
+                let (line, col) = (self.peek().line, self.peek().col);
+                let argument = self.argument()?;
+                // f(*xs)는 키워드 인자 뒤에 와도 되지만 f(**d) 뒤에는 안 됨
+                let misplaced = match argument {
+                    expr::Argument::Positional(_) => arguments.iter().any(|arg| {
+                        matches!(
+                            arg,
+                            expr::Argument::Keyword(..) | expr::Argument::UnpackKeywords(_)
+                        )
+                    }),
+                    expr::Argument::Unpack(_) => arguments
+                        .iter()
+                        .any(|arg| matches!(arg, expr::Argument::UnpackKeywords(_))),
+                    _ => false,
+                };
+                if misplaced {
+                    return Err(Error::PositionalAfterKeyword { line, col });
+                }
+                arguments.push(argument);
+                if !self.accept(lexer::TokenType::Comma) {
                     break;
                 }
             }
@@ -807,52 +1709,95 @@ impl Parser {
 
         let token = self.consume(
             lexer::TokenType::RightParen,
-            "Expected ) after arguments.",
+            "after argument",
         )?;
+        let span = callee.span.to(token.span);
 
-        Ok(expr::Expr::Call(
-            Box::new(callee),
-            expr::SourceLocation {
-                line: token.line,
-                col: token.col,
-            },
-            arguments,
+        Ok(expr::Expr::new(
+            expr::ExprKind::Call(
+                Box::new(callee),
+                expr::SourceLocation {
+                    line: token.line,
+                    col: token.col,
+                },
+                arguments,
+            ),
+            span,
         ))
     }
 
-    fn primary(&mut self) -> Result<expr::Expr, Error> {
+    // name=value 형태면 키워드 인자
+    fn argument(&mut self) -> Result<expr::Argument, Error<'src>> {
+        if self.matches(lexer::TokenType::Star) {
+            return Ok(expr::Argument::Unpack(self.expression_in("call argument")?));
+        }
+        if self.matches(lexer::TokenType::StarStar) {
+            return Ok(expr::Argument::UnpackKeywords(
+                self.expression_in("call argument")?,
+            ));
+        }
+        if self.check(lexer::TokenType::Identifier) && self.check_next(lexer::TokenType::Equal) {
+            let name_tok = self.nexting().clone();
+            self.nexting();
+            let value = self.expression_in("keyword argument")?;
+            return Ok(expr::Argument::Keyword(
+                expr::Symbol {
+                    name: name_tok.lexing.to_string(),
+                    line: name_tok.line,
+                    col: name_tok.col,
+                },
+                value,
+            ));
+        }
+        Ok(expr::Argument::Positional(self.expression_in("call argument")?))
+    }
+
+    fn primary(&mut self) -> Result<expr::Expr, Error<'src>> {
         if self.matches(lexer::TokenType::False) {
-            return Ok(expr::Expr::Literal(expr::Literal::False));
+            return Ok(self.literal(expr::Literal::False));
         }
         if self.matches(lexer::TokenType::True) {
-            return Ok(expr::Expr::Literal(expr::Literal::True));
+            return Ok(self.literal(expr::Literal::True));
         }
         if self.matches(lexer::TokenType::Nil) {
-            return Ok(expr::Expr::Literal(expr::Literal::Nil));
+            return Ok(self.literal(expr::Literal::Nil));
         }
         if self.matches(lexer::TokenType::Super) {
             let super_tok = self.previous().clone();
-            self.consume(lexer::TokenType::Dot, "Expected '.' after 'super'.")?;
+            self.consume(lexer::TokenType::Dot, "after `super`")?;
             let method_tok = self.consume(
                 lexer::TokenType::Identifier,
-                "Expected superclass method name.",
+                "as superclass method name",
             )?;
-            return Ok(expr::Expr::Super(
-                expr::SourceLocation {
-                    line: super_tok.line,
-                    col: super_tok.col,
-                },
-                expr::Symbol {
-                    name: String::from_utf8(method_tok.lexing.clone()).unwrap(),
-                    line: method_tok.line,
-                    col: method_tok.col,
-                },
+            let method_sym = expr::Symbol {
+                name: method_tok.lexing.to_string(),
+                line: method_tok.line,
+                col: method_tok.col,
+            };
+            return Ok(expr::Expr::new(
+                expr::ExprKind::Super(
+                    expr::SourceLocation {
+                        line: super_tok.line,
+                        col: super_tok.col,
+                    },
+                    method_sym,
+                ),
+                self.span_from(super_tok.span),
             ));
         }
         if self.matches(lexer::TokenType::Number) {
             match &self.previous().literal {
                 Some(lexer::Literal::Number(n)) => {
-                    return Ok(expr::Expr::Literal(expr::Literal::Number(*n)))
+                    let n = *n;
+                    return Ok(self.literal(expr::Literal::Number(n)))
+                }
+                Some(lexer::Literal::Int(n)) => {
+                    let n = *n;
+                    return Ok(self.literal(expr::Literal::Int(n)))
+                }
+                Some(lexer::Literal::BigInt(digits)) => {
+                    let n = BigInt::parse(digits).unwrap();
+                    return Ok(self.literal(expr::Literal::BigInt(n)))
                 }
                 Some(l) => panic!(
                     "internal error in parser: when parsing number, found literal {:?}",
@@ -864,7 +1809,8 @@ impl Parser {
         if self.matches(lexer::TokenType::String) {
             match &self.previous().literal {
                 Some(lexer::Literal::Str(s)) => {
-                    return Ok(expr::Expr::Literal(expr::Literal::String(s.clone())))
+                    let s = s.to_string();
+                    return Ok(self.literal(expr::Literal::String(s)))
                 }
                 Some(l) => panic!(
                     "internal error in parser: when parsing string, found literal {:?}",
@@ -875,19 +1821,26 @@ impl Parser {
         }
         if self.matches(lexer::TokenType::This) {
             let prev = self.previous();
-            return Ok(expr::Expr::This(expr::SourceLocation {
-                line: prev.line,
-                col: prev.col,
-            }));
+            return Ok(expr::Expr::new(
+                expr::ExprKind::This(expr::SourceLocation {
+                    line: prev.line,
+                    col: prev.col,
+                }),
+                prev.span,
+            ));
         }
         if self.matches(lexer::TokenType::Identifier) {
-            match &self.previous().literal {
+            let prev = self.previous();
+            match &prev.literal {
                 Some(lexer::Literal::Identifier(s)) => {
-                    return Ok(expr::Expr::Variable(expr::Symbol {
-                        name: s.clone(),
-                        line: self.previous().line,
-                        col: self.previous().col,
-                    }))
+                    return Ok(expr::Expr::new(
+                        expr::ExprKind::Variable(expr::Symbol {
+                            name: s.to_string(),
+                            line: prev.line,
+                            col: prev.col,
+                        }),
+                        prev.span,
+                    ))
                 }
                 Some(l) => panic!(
                     "internal error in parser: when parsing identifier, found literal {:?}",
@@ -899,70 +1852,216 @@ impl Parser {
             }
         }
         if self.matches(lexer::TokenType::LeftParen) {
-            let expr = Box::new(self.expression()?);
-            if let Err(err) = self.consume(
-                lexer::TokenType::RightParen,
-                "Expected ')' after expression.",
-            ) {
-                return Err(err);
+            let start = self.previous().span;
+            // () 와 (a,) 는 튜플
+            if self.matches(lexer::TokenType::RightParen) {
+                return Ok(expr::Expr::new(
+                    expr::ExprKind::Tuple(Vec::new()),
+                    self.span_from(start),
+                ));
+            }
+            let first = self.paren_element("parenthesized expression")?;
+            if self.accept(lexer::TokenType::Comma) {
+                let mut elements = vec![first];
+                while !self.check(lexer::TokenType::RightParen) {
+                    elements.push(self.paren_element("tuple element")?);
+                    if !self.accept(lexer::TokenType::Comma) {
+                        break;
+                    }
+                }
+                self.consume(lexer::TokenType::RightParen, "after tuple element")?;
+                return Ok(expr::Expr::new(
+                    expr::ExprKind::Tuple(elements),
+                    self.span_from(start),
+                ));
             }
-            return Ok(expr::Expr::Grouping(expr));
+            let expr = Box::new(Self::reject_starred(first)?);
+            self.consume(
+                lexer::TokenType::RightParen,
+                "after expression",
+            )?;
+            return Ok(expr::Expr::new(
+                expr::ExprKind::Grouping(expr),
+                self.span_from(start),
+            ));
         }
         if self.extensions.lists && self.matches(lexer::TokenType::LeftBracket) {
+            let start = self.previous().span;
             let mut list_elements = Vec::new();
 
             if !self.check(lexer::TokenType::RightBracket) {
                 loop {
-                    list_elements.push(self.expression()?);
-                    if !self.matches(lexer::TokenType::Comma) {
+                    list_elements.push(self.expression_in("list element")?);
+                    if list_elements.len() == 1 && self.matches(lexer::TokenType::For) {
+                        let clauses = self.comp_clauses()?;
+                        self.consume(lexer::TokenType::RightBracket, "after comprehension")?;
+                        return Ok(expr::Expr::new(
+                            expr::ExprKind::ListComp(Box::new(list_elements.remove(0)), clauses),
+                            self.span_from(start),
+                        ));
+                    }
+                    if !self.accept(lexer::TokenType::Comma) {
                         break;
                     }
                 }
             }
 
-            self.consume(lexer::TokenType::RightBracket, "Expected ].")?;
+            self.consume(lexer::TokenType::RightBracket, "after list element")?;
 
-            return Ok(expr::Expr::List(list_elements));
+            return Ok(expr::Expr::new(
+                expr::ExprKind::List(list_elements),
+                self.span_from(start),
+            ));
+        }
+        // {k: v, ...} 또는 {a, b}. 문장 맨 앞의 {는 블록이므로 여기까지 오지 않음
+        if self.extensions.lists && self.matches(lexer::TokenType::LeftBrace) {
+            let start = self.previous().span;
+            // {}는 빈 딕셔너리
+            if self.matches(lexer::TokenType::RightBrace) {
+                return Ok(expr::Expr::new(
+                    expr::ExprKind::Dict(Vec::new()),
+                    self.span_from(start),
+                ));
+            }
+            let first = self.expression_in("dict key")?;
+            let kind = if self.accept(lexer::TokenType::Colon) {
+                self.dict_display(first)?
+            } else {
+                self.set_display(first)?
+            };
+            return Ok(expr::Expr::new(kind, self.span_from(start)));
         }
         if self.extensions.lambdas && self.matches(lexer::TokenType::Lambda) {
+            let start = self.previous().span;
             let (params, body) = self.params_and_body(FunctionKind::Lambda)?;
-            return Ok(expr::Expr::Lambda(expr::LambdaDecl { params, body }));
+            return Ok(expr::Expr::new(
+                expr::ExprKind::Lambda(expr::LambdaDecl { params, body }),
+                self.span_from(start),
+            ));
         }
 
         Err(Error::ExpectedExpression {
-            token_type: self.peek().toktype,
-            line: self.peek().line,
-            col: self.peek().col,
+            found: self.peek().clone(),
+            construct: self.construct,
         })
     }
 
+    // 첫 키와 :는 이미 읽은 상태
+    fn dict_display(&mut self, first_key: expr::Expr) -> Result<expr::ExprKind, Error<'src>> {
+        let first_value = self.expression_in("dict value")?;
+        if self.matches(lexer::TokenType::For) {
+            let clauses = self.comp_clauses()?;
+            self.consume(lexer::TokenType::RightBrace, "after comprehension")?;
+            return Ok(expr::ExprKind::DictComp(
+                Box::new(first_key),
+                Box::new(first_value),
+                clauses,
+            ));
+        }
+
+        let mut entries = vec![(first_key, first_value)];
+        while self.accept(lexer::TokenType::Comma) {
+            let key = self.expression_in("dict key")?;
+            self.consume(lexer::TokenType::Colon, "after dict key")?;
+            let value = self.expression_in("dict value")?;
+            entries.push((key, value));
+        }
+        self.consume(lexer::TokenType::RightBrace, "after dict entry")?;
+        Ok(expr::ExprKind::Dict(entries))
+    }
+
+    fn set_display(&mut self, first: expr::Expr) -> Result<expr::ExprKind, Error<'src>> {
+        if self.matches(lexer::TokenType::For) {
+            let clauses = self.comp_clauses()?;
+            self.consume(lexer::TokenType::RightBrace, "after comprehension")?;
+            return Ok(expr::ExprKind::SetComp(Box::new(first), clauses));
+        }
+
+        let mut elements = vec![first];
+        while self.accept(lexer::TokenType::Comma) {
+            elements.push(self.expression_in("set element")?);
+        }
+        self.consume(lexer::TokenType::RightBrace, "after set element")?;
+        Ok(expr::ExprKind::SetLiteral(elements))
+    }
+
+    fn literal(&self, literal: expr::Literal) -> expr::Expr {
+        expr::Expr::new(expr::ExprKind::Literal(literal), self.previous().span)
+    }
+
+    // context는 "after arguments"처럼 어디서 기대했는지를 설명함
     fn consume(
         &mut self,
         tok: lexer::TokenType,
-        on_err_str: &str,
-    ) -> Result<&lexer::Token, Error> {
+        context: &str,
+    ) -> Result<&lexer::Token<'src>, Error<'src>> {
         if self.check(tok) {
             return Ok(self.nexting());
         }
+        let mut expected = std::mem::take(&mut self.expected);
+        if !expected.contains(&tok) {
+            expected.push(tok);
+        }
         Err(Error::TokenMismatch {
-            expected: tok,
+            expected: expected.into_boxed_slice(),
             found: self.peek().clone(),
-            maybe_on_err_string: Some(on_err_str.into()),
+            context: context.to_string(),
         })
     }
 
-    fn op_token_to_unary_op(tok: &lexer::Token) -> Result<expr::UnaryOp, Error> {
+    // matches와 같지만, 실패하면 다음 에러 메세지의 후보 토큰으로 남김
+    fn accept(&mut self, toktype: lexer::TokenType) -> bool {
+        if self.matches(toktype) {
+            return true;
+        }
+        if !self.expected.contains(&toktype) {
+            self.expected.push(toktype);
+        }
+        false
+    }
+
+    fn expression_list_in(&mut self, construct: &'static str) -> Result<expr::Expr, Error<'src>> {
+        let saved_construct = std::mem::replace(&mut self.construct, construct);
+        let expr = self.expression_list();
+        self.construct = saved_construct;
+        expr
+    }
+
+    fn paren_element(&mut self, construct: &'static str) -> Result<expr::Expr, Error<'src>> {
+        let saved_construct = std::mem::replace(&mut self.construct, construct);
+        let expr = if self.check(lexer::TokenType::Star) {
+            self.tuple_element()
+        } else {
+            self.expression()
+        };
+        self.construct = saved_construct;
+        expr
+    }
+
+    fn expression_in(&mut self, construct: &'static str) -> Result<expr::Expr, Error<'src>> {
+        let saved_construct = std::mem::replace(&mut self.construct, construct);
+        let expr = self.expression();
+        self.construct = saved_construct;
+        expr
+    }
+
+    fn op_token_to_unary_op(tok: &lexer::Token<'src>) -> Result<expr::UnaryOp, Error<'src>> {
         match tok.toktype {
             lexer::TokenType::Minus => Ok(expr::UnaryOp {
                 toktype: expr::UnaryOpTy::Minus,
                 line: tok.line,
                 col: tok.col,
             }),
-            lexer::TokenType::Bang => Ok(expr::UnaryOp {
+            lexer::TokenType::Bang | lexer::TokenType::Not => Ok(expr::UnaryOp {
                 toktype: expr::UnaryOpTy::Bang,
                 line: tok.line,
                 col: tok.col,
             }),
+            lexer::TokenType::Tilde => Ok(expr::UnaryOp {
+                toktype: expr::UnaryOpTy::Tilde,
+                line: tok.line,
+                col: tok.col,
+            }),
             _ => Err(Error::InvalidTokenInUnaryOp {
                 token_type: tok.toktype,
                 line: tok.line,
@@ -971,7 +2070,10 @@ impl Parser {
         }
     }
 
-    fn equality(&mut self) -> Result<expr::Expr, Error> {
+    fn equality(&mut self) -> Result<expr::Expr, Error<'src>> {
+        if self.is_python() {
+            return self.comparison_chain();
+        }
         let mut expr = self.comparison()?;
 
         while self.match_one_of(vec![
@@ -981,12 +2083,13 @@ impl Parser {
             let operator_token = self.previous().clone();
             let right = Box::new(self.comparison()?);
 
-            let binop_maybe = Parser::op_token_to_binop(&operator_token);
+            let binop_maybe = Self::op_token_to_binop(&operator_token);
 
             match binop_maybe {
                 Ok(binop) => {
+                    let span = expr.span.to(right.span);
                     let left = Box::new(expr);
-                    expr = expr::Expr::Binary(left, binop, right);
+                    expr = expr::Expr::new(expr::ExprKind::Binary(left, binop, right), span);
                 }
                 Err(err) => return Err(err),
             }
@@ -994,7 +2097,60 @@ impl Parser {
         Ok(expr)
     }
 
-    fn op_token_to_binop(tok: &lexer::Token) -> Result<expr::BinaryOp, Error> {
+    // 파이썬 방언에서는 비교 연산자가 모두 같은 우선순위이고 이어 쓸 수 있음: 0 <= i < n
+    fn comparison_chain(&mut self) -> Result<expr::Expr, Error<'src>> {
+        let first = self.bit_or()?;
+        let mut rest = Vec::new();
+
+        while self.match_one_of(vec![
+            lexer::TokenType::BangEqual,
+            lexer::TokenType::EqualEqual,
+            lexer::TokenType::Greater,
+            lexer::TokenType::GreaterEqual,
+            lexer::TokenType::Less,
+            lexer::TokenType::LessEqual,
+            lexer::TokenType::In,
+            lexer::TokenType::Not,
+            lexer::TokenType::Is,
+        ]) {
+            let operator_token = self.previous().clone();
+            let binop = self.two_word_binop(&operator_token)?;
+            rest.push((binop, self.bit_or()?));
+        }
+
+        let span = match rest.last() {
+            Some((_, last)) => first.span.to(last.span),
+            None => return Ok(first),
+        };
+        if rest.len() == 1 {
+            let (binop, right) = rest.pop().unwrap();
+            return Ok(expr::Expr::new(
+                expr::ExprKind::Binary(Box::new(first), binop, Box::new(right)),
+                span,
+            ));
+        }
+        Ok(expr::Expr::new(expr::ExprKind::Compare(Box::new(first), rest), span))
+    }
+
+    // not in, is not은 두 토큰으로 된 연산자. 식 뒤의 not은 not in일 수밖에 없음
+    fn two_word_binop(&mut self, tok: &lexer::Token<'src>) -> Result<expr::BinaryOp, Error<'src>> {
+        let toktype = match tok.toktype {
+            lexer::TokenType::Not => {
+                self.consume(lexer::TokenType::In, "after 'not'")?;
+                expr::BinaryOpTy::NotIn
+            }
+            lexer::TokenType::Is if self.accept(lexer::TokenType::Not) => expr::BinaryOpTy::IsNot,
+            lexer::TokenType::Is => expr::BinaryOpTy::Is,
+            _ => return Self::op_token_to_binop(tok),
+        };
+        Ok(expr::BinaryOp {
+            toktype,
+            line: tok.line,
+            col: tok.col,
+        })
+    }
+
+    fn op_token_to_binop(tok: &lexer::Token<'src>) -> Result<expr::BinaryOp, Error<'src>> {
         match tok.toktype {
             lexer::TokenType::EqualEqual => Ok(expr::BinaryOp {
                 toktype: expr::BinaryOpTy::EqualEqual,
@@ -1026,26 +2182,73 @@ impl Parser {
                 line: tok.line,
                 col: tok.col,
             }),
-            lexer::TokenType::Plus => Ok(expr::BinaryOp {
+            lexer::TokenType::In => Ok(expr::BinaryOp {
+                toktype: expr::BinaryOpTy::In,
+                line: tok.line,
+                col: tok.col,
+            }),
+            lexer::TokenType::Plus | lexer::TokenType::PlusEqual => Ok(expr::BinaryOp {
                 toktype: expr::BinaryOpTy::Plus,
                 line: tok.line,
                 col: tok.col,
             }),
-            lexer::TokenType::Minus => Ok(expr::BinaryOp {
+            lexer::TokenType::Minus | lexer::TokenType::MinusEqual => Ok(expr::BinaryOp {
                 toktype: expr::BinaryOpTy::Minus,
                 line: tok.line,
                 col: tok.col,
             }),
-            lexer::TokenType::Star => Ok(expr::BinaryOp {
+            lexer::TokenType::Star | lexer::TokenType::StarEqual => Ok(expr::BinaryOp {
                 toktype: expr::BinaryOpTy::Star,
                 line: tok.line,
                 col: tok.col,
             }),
-            lexer::TokenType::Slash => Ok(expr::BinaryOp {
+            lexer::TokenType::Slash | lexer::TokenType::SlashEqual => Ok(expr::BinaryOp {
                 toktype: expr::BinaryOpTy::Slash,
                 line: tok.line,
                 col: tok.col,
             }),
+            lexer::TokenType::SlashSlash | lexer::TokenType::SlashSlashEqual => Ok(expr::BinaryOp {
+                toktype: expr::BinaryOpTy::SlashSlash,
+                line: tok.line,
+                col: tok.col,
+            }),
+            lexer::TokenType::Percent | lexer::TokenType::PercentEqual => Ok(expr::BinaryOp {
+                toktype: expr::BinaryOpTy::Percent,
+                line: tok.line,
+                col: tok.col,
+            }),
+            lexer::TokenType::StarStar | lexer::TokenType::StarStarEqual => Ok(expr::BinaryOp {
+                toktype: expr::BinaryOpTy::StarStar,
+                line: tok.line,
+                col: tok.col,
+            }),
+            lexer::TokenType::Amp | lexer::TokenType::AmpEqual => Ok(expr::BinaryOp {
+                toktype: expr::BinaryOpTy::Amp,
+                line: tok.line,
+                col: tok.col,
+            }),
+            lexer::TokenType::Pipe | lexer::TokenType::PipeEqual => Ok(expr::BinaryOp {
+                toktype: expr::BinaryOpTy::Pipe,
+                line: tok.line,
+                col: tok.col,
+            }),
+            lexer::TokenType::Caret | lexer::TokenType::CaretEqual => Ok(expr::BinaryOp {
+                toktype: expr::BinaryOpTy::Caret,
+                line: tok.line,
+                col: tok.col,
+            }),
+            lexer::TokenType::LessLess | lexer::TokenType::LessLessEqual => Ok(expr::BinaryOp {
+                toktype: expr::BinaryOpTy::LessLess,
+                line: tok.line,
+                col: tok.col,
+            }),
+            lexer::TokenType::GreaterGreater | lexer::TokenType::GreaterGreaterEqual => {
+                Ok(expr::BinaryOp {
+                    toktype: expr::BinaryOpTy::GreaterGreater,
+                    line: tok.line,
+                    col: tok.col,
+                })
+            }
             _ => Err(Error::InvalidTokenInBinaryOp {
                 token_type: tok.toktype,
                 line: tok.line,
@@ -1079,27 +2282,389 @@ impl Parser {
         self.peek().toktype == toktype
     }
 
-    fn nexting(&mut self) -> &lexer::Token {
+    // current 다음 토큰을 미리 봄
+    fn check_next(&mut self, toktype: lexer::TokenType) -> bool {
+        matches!(self.tokens.peek(), Some(Ok(tok)) if tok.toktype == toktype)
+    }
+
+    fn nexting(&mut self) -> &lexer::Token<'src> {
         if !self.is_end() {
-            self.cursor += 1
+            let next = self.pull();
+            self.previous = std::mem::replace(&mut self.current, next);
+            self.expected.clear();
         }
 
         self.previous()
     }
 
+    fn pull(&mut self) -> lexer::Token<'src> {
+        match self.tokens.next() {
+            Some(Ok(tok)) => tok,
+            Some(Err(err)) => {
+                // 에러 이후로는 Eof처럼 취급하고, 에러는 finish에서 보고함
+                let eof = lexer::Token {
+                    toktype: lexer::TokenType::Eof,
+                    lexing: "",
+                    literal: None,
+                    line: err.line,
+                    col: err.col,
+                    span: Span::new(self.current.span.end, self.current.span.end),
+                };
+                self.lex_err = Some(err);
+                eof
+            }
+            None => self.current.clone(),
+        }
+    }
+
     fn is_end(&self) -> bool {
         self.peek().toktype == lexer::TokenType::Eof
     }
 
-    fn is_equal(&self) -> bool {                                // is_equal 함수 추가
-        self.peek().toktype == lexer::TokenType::Equal
+    fn peek(&self) -> &lexer::Token<'src> {
+        &self.current
+    }
+
+    fn previous(&self) -> &lexer::Token<'src> {
+        &self.previous
+    }
+
+    // start부터 직전 토큰까지의 범위
+    fn span_from(&self, start: Span) -> Span {
+        start.to(self.previous().span)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_extensions(dialect: lexer::Dialect) -> extensions::Extensions {
+        extensions::Extensions {
+            lists: true,
+            lambdas: true,
+            dialect,
+            ..Default::default()
+        }
+    }
+
+    fn parse_source(source: &str, dialect: lexer::Dialect) -> (Vec<expr::Stmt>, Vec<Error<'_>>) {
+        let extensions = test_extensions(dialect);
+        parse(extensions, source, lexer::Lexer::new(source, dialect))
+    }
+
+    fn parse_lox(source: &str) -> Vec<expr::Stmt> {
+        let (stmts, errors) = parse_source(source, lexer::Dialect::Lox);
+        if let Some(err) = errors.first() {
+            panic!("{}", err);
+        }
+        stmts
+    }
+
+    fn text(source: &str, span: Span) -> &str {
+        &source[span.start..span.end]
+    }
+
+    #[test]
+    fn statement_spans_cover_the_whole_statement() {
+        let source = "var x = 1;\nif (x) {\n  print x;\n} else {\n  print -x;\n}";
+        let stmts = parse_lox(source);
+        assert_eq!(text(source, stmts[0].span), "var x = 1;");
+        assert_eq!(
+            text(source, stmts[1].span),
+            "if (x) {\n  print x;\n} else {\n  print -x;\n}"
+        );
+    }
+
+    #[test]
+    fn elif_nests_ifs_with_their_own_conditions() {
+        let source = "if (a) print 1;\nelif (b) print 2;\nelif (c) print 3;\nelse print 4;";
+        let stmts = parse_lox(source);
+        assert_eq!(stmts.len(), 1);
+        let mut stmt = &stmts[0];
+        for cond in ["a", "b", "c"] {
+            match &stmt.kind {
+                expr::StmtKind::If(expr, _, Some(else_branch)) => {
+                    assert_eq!(text(source, expr.span), cond);
+                    stmt = else_branch;
+                }
+                kind => panic!("unexpected {:?}", kind),
+            }
+        }
+        assert_eq!(text(source, stmt.span), "print 4;");
+        assert_eq!(text(source, stmts[0].span), source);
+    }
+
+    #[test]
+    fn elif_after_else_is_an_error() {
+        let source = "if x:\n    pass\nelse:\n    pass\nelif y:\n    pass\n";
+        let (_, errors) = parse_source(source, lexer::Dialect::Python);
+        assert_eq!(
+            errors[0].to_string(),
+            "expected an expression in expression statement, found `elif` at line=5,col=4"
+        );
+    }
+
+    fn error_messages(errors: &[Error]) -> Vec<String> {
+        errors.iter().map(|err| err.to_string()).collect()
+    }
+
+    #[test]
+    fn recovery_reports_every_bad_statement() {
+        let source = "var a = ;\n\
+                      print 1;\n\
+                      var b = 2 +;\n\
+                      def f() { print (; print 2; }\n\
+                      class C { m( {} }\n\
+                      print 3;\n";
+        let (stmts, errors) = parse_source(source, lexer::Dialect::Lox);
+        assert_eq!(
+            error_messages(&errors),
+            [
+                "expected an expression in variable initializer, found `;` at line=1,col=8",
+                "expected an expression in variable initializer, found `;` at line=3,col=12",
+                "expected an expression in parenthesized expression, found `;` at line=4,col=18",
+                "expected identifier as parameter name, found `{` at line=5,col=14",
+            ]
+        );
+        // 성공한 문장들은 AST에 남음
+        let texts: Vec<&str> = stmts.iter().map(|stmt| text(source, stmt.span)).collect();
+        assert_eq!(
+            texts,
+            [
+                "print 1;",
+                "def f() { print (; print 2; }",
+                "class C { m( {} }",
+                "print 3;",
+            ]
+        );
+        match &stmts[1].kind {
+            expr::StmtKind::FunDecl(decl) => {
+                assert_eq!(decl.body.len(), 1);
+                assert_eq!(text(source, decl.body[0].span), "print 2;");
+            }
+            kind => panic!("unexpected {:?}", kind),
+        }
+        match &stmts[2].kind {
+            expr::StmtKind::ClassDecl(decl) => assert!(decl.methods.is_empty()),
+            kind => panic!("unexpected {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn recovery_skips_the_block_of_a_bad_header() {
+        let source = "if x +:\n    print(1 +)\n    print(2)\nprint(3 +)\nprint(4)\n";
+        let (stmts, errors) = parse_source(source, lexer::Dialect::Python);
+        assert_eq!(
+            error_messages(&errors),
+            [
+                "expected an expression in condition, found `:` at line=1,col=6",
+                "expected an expression in parenthesized expression, found `)` at line=4,col=10",
+            ]
+        );
+        assert_eq!(stmts.len(), 1);
+        assert_eq!(text(source, stmts[0].span), "print(4)\n");
+    }
+
+    #[test]
+    fn errors_after_a_lexer_error_are_dropped() {
+        let source = "var a = ;\nvar s = \"abc\nprint )\n";
+        let (_, errors) = parse_source(source, lexer::Dialect::Lox);
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(errors[1], Error::Lexer(_)));
+    }
+
+    fn first_error(source: &str) -> String {
+        let (_, errors) = parse_source(source, lexer::Dialect::Lox);
+        errors[0].to_string()
+    }
+
+    #[test]
+    fn mismatch_errors_list_every_expected_token() {
+        assert_eq!(
+            first_error("f(1 2);"),
+            "expected one of `,`, `)` after argument, found `2` at line=1,col=4"
+        );
+        assert_eq!(
+            first_error("print [1 2];"),
+            "expected one of `,`, `]` after list element, found `2` at line=1,col=9"
+        );
+        assert_eq!(
+            first_error("def f(a b) {}"),
+            "expected one of `,`, `)` after parameter, found `b` at line=1,col=8"
+        );
+        assert_eq!(
+            first_error("print \"a\" \"b\";"),
+            "expected `;` after statement, found `\"b\"` at line=1,col=12"
+        );
+        assert_eq!(
+            first_error("{ print 1;\n"),
+            "expected `}` at end of block, found end of input at line=2,col=0"
+        );
+    }
+
+    #[test]
+    fn expected_expression_names_the_construct() {
+        assert_eq!(
+            first_error("var a = ;"),
+            "expected an expression in variable initializer, found `;` at line=1,col=8"
+        );
+        assert_eq!(
+            first_error("print (1 + ;"),
+            "expected an expression in parenthesized expression, found `;` at line=1,col=11"
+        );
+        assert_eq!(
+            first_error("var 3 = 1;"),
+            "expected identifier as variable name, found `3` at line=1,col=4"
+        );
+    }
+
+    #[test]
+    fn parse_errors_are_std_errors() {
+        let (_, errors) = parse_source("var s = \"abc", lexer::Dialect::Lox);
+        let err: &dyn std::error::Error = &errors[0];
+        let lex_err: &dyn std::error::Error = match &errors[0] {
+            Error::Lexer(err) => err,
+            err => panic!("unexpected {}", err),
+        };
+        assert_eq!(err.to_string(), lex_err.to_string());
+    }
+
+    #[test]
+    fn expression_spans_cover_their_operands() {
+        let source = "print (1 + 2) * f(3, [4, 5]);";
+        let stmts = parse_lox(source);
+        let expr = match &stmts[0].kind {
+            expr::StmtKind::Print(expr) => expr,
+            kind => panic!("unexpected {:?}", kind),
+        };
+        assert_eq!(text(source, expr.span), "(1 + 2) * f(3, [4, 5])");
+        let (lhs, rhs) = match &expr.kind {
+            expr::ExprKind::Binary(lhs, _, rhs) => (lhs, rhs),
+            kind => panic!("unexpected {:?}", kind),
+        };
+        assert_eq!(text(source, lhs.span), "(1 + 2)");
+        assert_eq!(text(source, rhs.span), "f(3, [4, 5])");
+        match &rhs.kind {
+            expr::ExprKind::Call(_, _, args) => match &args[1] {
+                expr::Argument::Positional(list) => assert_eq!(text(source, list.span), "[4, 5]"),
+                arg => panic!("unexpected {:?}", arg),
+            },
+            kind => panic!("unexpected {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn literals_logicals_and_lambdas_have_spans() {
+        let source = "var f = lambda (a) { return a or \"é\"; };";
+        let stmts = parse_lox(source);
+        let lambda = match &stmts[0].kind {
+            expr::StmtKind::VarDecl(_, Some(lambda)) => lambda,
+            kind => panic!("unexpected {:?}", kind),
+        };
+        assert_eq!(text(source, lambda.span), "lambda (a) { return a or \"é\"; }");
+        let body = match &lambda.kind {
+            expr::ExprKind::Lambda(decl) => &decl.body,
+            kind => panic!("unexpected {:?}", kind),
+        };
+        let logical = match &body[0].kind {
+            expr::StmtKind::Return(Some(logical)) => logical,
+            kind => panic!("unexpected {:?}", kind),
+        };
+        assert_eq!(text(source, body[0].span), "return a or \"é\";");
+        assert_eq!(text(source, logical.span), "a or \"é\"");
+        match &logical.kind {
+            expr::ExprKind::Logical(_, _, literal) => {
+                assert_eq!(text(source, literal.span), "\"é\"")
+            }
+            kind => panic!("unexpected {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn lexer_errors_surface_as_parse_errors() {
+        let (_, errors) = parse_source("print 1;\nprint \"abc", lexer::Dialect::Lox);
+        assert!(matches!(
+            errors.as_slice(),
+            [Error::Lexer(err)] if err.what == "Unterminated string"
+        ));
+    }
+
+    // 클래스, 함수, 반복문, 문자열, 주석이 섞인 Lox 스크립트를 size 바이트 넘게 만듦
+    fn large_script(size: usize) -> String {
+        let mut source = String::new();
+        let mut n = 0;
+        while source.len() < size {
+            source.push_str(&format!(
+                "// chunk {n}\n\
+                 class Point{n} {{\n  norm(x, y) {{ return x * x + y * y; }}\n}}\n\
+                 def fib{n}(k) {{\n  if (k < 2) {{ return k; }}\n  \
+                 return fib{n}(k - 1) + fib{n}(k - 2);\n}}\n\
+                 var total{n} = 0;\n\
+                 var i{n} = 0;\n\
+                 while (i{n} < 10) {{\n  \
+                 total{n} = total{n} + fib{n}(i{n}) * 3.5;\n  i{n} = i{n} + 1;\n}}\n\
+                 print \"chunk {n}: \" + \"done\";\n"
+            ));
+            n += 1;
+        }
+        source
+    }
+
+    // 걸린 시간과 그동안의 피크 RSS를 출력함. 피크 RSS는 /proc이 있는 리눅스에서만 잼
+    fn measure<T>(label: &str, f: impl FnOnce() -> T) -> T {
+        let _ = std::fs::write("/proc/self/clear_refs", "5");
+        let start = std::time::Instant::now();
+        let res = f();
+        let elapsed = start.elapsed();
+        let peak_rss = std::fs::read_to_string("/proc/self/status")
+            .ok()
+            .and_then(|status| {
+                let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+                line.split_whitespace().nth(1)?.parse::<u64>().ok()
+            });
+        match peak_rss {
+            Some(kb) => println!("{:<10} {:>12?} {:>8} MB peak RSS", label, elapsed, kb >> 10),
+            None => println!("{:<10} {:>12?}", label, elapsed),
+        }
+        res
     }
 
-    fn peek(&self) -> &lexer::Token {
-        &self.tokens[self.cursor]
+    // 예전 check_tokens처럼 토큰을 전부 Vec에 모은 뒤 쓰는 경로(buffered)와
+    // Lexer를 바로 흘려 쓰는 경로(streaming)를 비교하는 벤치. 릴리즈 빌드로 돌림:
+    // cargo test --release large_script -- --ignored --nocapture --test-threads=1
+    #[test]
+    #[ignore]
+    fn lex_large_script() {
+        let source = large_script(10 << 20);
+        println!("{} bytes", source.len());
+        let buffered = measure("buffered", || {
+            let tokens: Vec<_> = lexer::Lexer::new(&source, lexer::Dialect::Lox).collect();
+            tokens.len()
+        });
+        let streaming = measure("streaming", || {
+            lexer::Lexer::new(&source, lexer::Dialect::Lox).count()
+        });
+        assert_eq!(buffered, streaming);
     }
 
-    fn previous(&self) -> &lexer::Token {
-        &self.tokens[self.cursor - 1]
+    #[test]
+    #[ignore]
+    fn lex_and_parse_large_script() {
+        let source = large_script(10 << 20);
+        println!("{} bytes", source.len());
+        let buffered = measure("buffered", || {
+            let tokens: Vec<_> = lexer::Lexer::new(&source, lexer::Dialect::Lox).collect();
+            let extensions = test_extensions(lexer::Dialect::Lox);
+            let (stmts, errors) = parse(extensions, &source, tokens.into_iter());
+            assert!(errors.is_empty());
+            stmts.len()
+        });
+        let streaming = measure("streaming", || {
+            let (stmts, errors) = parse_source(&source, lexer::Dialect::Lox);
+            assert!(errors.is_empty());
+            stmts.len()
+        });
+        assert_eq!(buffered, streaming);
     }
 }
@@ -1,7 +1,6 @@
 use crate::expr;
 use crate::extensions;
 use crate::input;
-use crate::line_reader;
 use crate::parser;
 use crate::lexer;
 use crate::interpreter;
@@ -23,20 +22,101 @@ pub(crate) fn mk_interpreter() -> interpreter::Interpreter {
 }
 
 
+// 스크립트 전체를 한번에 파싱하고 실행함. 성공하면 true
+pub(crate) fn eval_input(
+    interpreter: &mut interpreter::Interpreter,
+    input: &input::Input,
+    extensions: extensions::Extensions,
+) -> bool {
+    let (stmts, errors) = parser::parse(extensions, &input.content, lex(&input.content, extensions.dialect, false));
+    if !errors.is_empty() {
+        for err in errors {
+            match err {
+                parser::Error::Lexer(err) => {
+                    print!("{}: ", input.name());
+                    print_lexer_error(&err);
+                }
+                err => println!("{}: Parse error: {}", input.name(), err),
+            }
+        }
+        return false;
+    }
+
+    match interpreter.interpret(&stmts) {
+        Ok(()) => true,
+        Err(err) => {
+            println!("Runtime error: {}\n\n{}", err, err.format_backtrace());
+            false
+        }
+    }
+}
+
+// 문장 끝의 ;를 빠뜨린 입력을 다시 파싱할 때는 Eof 바로 앞에 ;를 끼워 넣음
+fn lex(
+    source: &str,
+    dialect: lexer::Dialect,
+    insert_semicolon: bool,
+) -> impl Iterator<Item = Result<lexer::Token<'_>, lexer::Error>> {
+    lexer::Lexer::new(source, dialect).flat_map(move |res| {
+        let semicolon = match &res {
+            Ok(tok) if insert_semicolon && tok.toktype == lexer::TokenType::Eof => {
+                Some(Ok(lexer::Token {
+                    toktype: lexer::TokenType::Semicolon,
+                    lexing: "",
+                    literal: None,
+                    line: 0,
+                    col: -1,
+                    span: tok.span,
+                }))
+            }
+            _ => None,
+        };
+        semicolon.into_iter().chain(std::iter::once(res))
+    })
+}
+
+// REPL은 한 줄씩 처리하므로 첫 에러만 봄
+fn first_error<'src>(
+    (stmts, errors): (Vec<expr::Stmt>, Vec<parser::Error<'src>>),
+) -> Result<Vec<expr::Stmt>, parser::Error<'src>> {
+    match errors.into_iter().next() {
+        Some(err) => Err(err),
+        None => Ok(stmts),
+    }
+}
+
+fn print_lexer_error(err: &lexer::Error) {
+    println!("Tokenizer failure: {}", err)
+}
+
+// 입력 끝에서 ;만 빠진 경우. 이때는 ;를 붙여서 다시 파싱함
+fn missing_final_semicolon(err: &parser::Error) -> bool {
+    matches!(
+        err,
+        parser::Error::TokenMismatch {
+            expected,
+            found: lexer::Token {
+                toktype: lexer::TokenType::Eof,
+                ..
+            },
+            ..
+        } if expected.contains(&lexer::TokenType::Semicolon)
+    )
+}
+
 pub(crate) fn check_eval_tokens(
     interpreter: &mut interpreter::Interpreter,
-    mut tokens: Vec<lexer::Token>,
+    source: &str,
     recursion_depth: i64,
     extensions: extensions::Extensions,
-    line: &str,
 ) -> bool {
-    match parser::parse(extensions, tokens.clone()) {
+    match first_error(parser::parse(extensions, source, lex(source, extensions.dialect, recursion_depth > 0))) {
         Ok(stmts) => {
             let stmts2: Vec<expr::Stmt> = stmts
                 .iter()
                 .enumerate()
-                .map(|(idx, stmt)| match stmt {
-                    expr::Stmt::Expr(expr) => {
+                .map(|(idx, stmt)| match &stmt.kind {
+                    expr::StmtKind::Expr(expr) => {
                         let var_sym = expr::Symbol {
                             // hack!!! we should find a fresh varname from somewhere
                             name: format!("freshnewvarname{}", idx),
@@ -44,23 +124,43 @@ pub(crate) fn check_eval_tokens(
                             col: 0,
                         };
                         // println!("name:{}\nline:{}\ncol:{} ", &var_sym.name, &var_sym.line, &var_sym.col);
-                        let var_expr = expr::Expr::Variable(var_sym.clone());
-                        expr::Stmt::Block(vec![
-                            expr::Stmt::VarDecl(var_sym, Some(expr.clone())),
-                            expr::Stmt::If(
-                                expr::Expr::Binary(
-                                    Box::new(var_expr.clone()),
-                                    expr::BinaryOp {
-                                        toktype: expr::BinaryOpTy::NotEqual,
-                                        line: 0,
-                                        col: 0,
-                                    },
-                                    Box::new(expr::Expr::Literal(expr::Literal::Nil)),
-                                ),
-                                Box::new(expr::Stmt::Print(var_expr)),
-                                None,
+                        let span = stmt.span;
+                        let var_expr =
+                            expr::Expr::new(expr::ExprKind::Variable(var_sym.clone()), span);
+                        let nil_expr =
+                            expr::Expr::new(expr::ExprKind::Literal(expr::Literal::Nil), span);
+                        let is_not_nil = expr::Expr::new(
+                            expr::ExprKind::Binary(
+                                Box::new(var_expr.clone()),
+                                expr::BinaryOp {
+                                    toktype: expr::BinaryOpTy::NotEqual,
+                                    line: 0,
+                                    col: 0,
+                                },
+                                Box::new(nil_expr),
                             ),
-                        ])
+                            span,
+                        );
+                        expr::Stmt::new(
+                            expr::StmtKind::Block(vec![
+                                expr::Stmt::new(
+                                    expr::StmtKind::VarDecl(var_sym, Some(expr.clone())),
+                                    span,
+                                ),
+                                expr::Stmt::new(
+                                    expr::StmtKind::If(
+                                        is_not_nil,
+                                        Box::new(expr::Stmt::new(
+                                            expr::StmtKind::Print(var_expr),
+                                            span,
+                                        )),
+                                        None,
+                                    ),
+                                    span,
+                                ),
+                            ]),
+                            span,
+                        )
                     }
                     _ => stmt.clone(),
                 })
@@ -68,50 +168,23 @@ pub(crate) fn check_eval_tokens(
             match interpreter.interpret(&stmts2) {
                 Ok(()) => {}
                 Err(err) => {
-                    if err[0..40] == "attempting to assign to undeclared variable at".to_string()[0..40] {
+                    if err.message.starts_with("NameError: attempting to assign to undeclared variable") {
                         return true
                     } else {
-                        println!(
-                            "Runtime error: {}\n\n{}",
-                            err,
-                            interpreter.format_backtrace());
+                        println!("Runtime error: {}\n\n{}", err, err.format_backtrace());
                     }
                 },
             } false
         }
-        Err(
-            err
-            @
-            parser::Error::TokenMismatch {
-                expected: lexer::TokenType::Semicolon,
-                found:
-                lexer::Token {
-                    toktype: lexer::TokenType::Eof,
-                    ..
-                },
-                ..
-            },
-        ) => {
-            let expected_eof = tokens.pop().unwrap();
-
-            tokens.push(lexer::Token {
-                toktype: lexer::TokenType::Semicolon,
-                lexing: Vec::new(),
-                literal: None,
-                line: 0,
-                col: -1,
-            });
-            tokens.push(expected_eof);
-
-            if recursion_depth > 0 {
-                println!("Errrr2");
-            } else {
-                return check_eval_tokens(interpreter, tokens, recursion_depth + 1, extensions, line)
-            }
+        Err(err) if recursion_depth == 0 && missing_final_semicolon(&err) => {
+            check_eval_tokens(interpreter, source, recursion_depth + 1, extensions)
+        }
+        Err(parser::Error::Lexer(err)) => {
+            print_lexer_error(&err);
             false
         }
         Err(err) => {
-            println!("Errrrr3");
+            println!("Parse error: {}", err);
             false
         },
     }
@@ -126,42 +199,61 @@ pub(crate) fn check_eval_tokens(
 
 pub(crate) fn eval_tokens2(
     interpreter: &mut interpreter::Interpreter,
-    mut tokens: Vec<lexer::Token>,
+    source: &str,
     recursion_depth: i64,
     extensions: extensions::Extensions,
-    line: &str,
 ) {
 
-    match parser::parse_varerr(extensions, tokens.clone()) {
+    match first_error(parser::parse_varerr(extensions, source, lex(source, extensions.dialect, recursion_depth > 0))) {
         Ok(stmts) => {
             let stmts2: Vec<expr::Stmt> = stmts
                 .iter()
                 .enumerate()
-                .map(|(idx, stmt)| match stmt {
-                    expr::Stmt::Expr(expr) => {
+                .map(|(idx, stmt)| match &stmt.kind {
+                    expr::StmtKind::Expr(expr) => {
                         let var_sym = expr::Symbol {
                             // hack!!! we should find a fresh varname from somewhere
                             name: format!("isurehopethisisntusedelsewhere{}", idx),
                             line: 0,
                             col: 0,
                         };
-                        let var_expr = expr::Expr::Variable(var_sym.clone());
-                        expr::Stmt::Block(vec![
-                            expr::Stmt::VarDecl(var_sym, Some(expr.clone())),
-                            expr::Stmt::If(
-                                expr::Expr::Binary(
-                                    Box::new(var_expr.clone()),
-                                    expr::BinaryOp {
-                                        toktype: expr::BinaryOpTy::NotEqual,
-                                        line: 0,
-                                        col: 0,
-                                    },
-                                    Box::new(expr::Expr::Literal(expr::Literal::Nil)),
-                                ),
-                                Box::new(expr::Stmt::Print(var_expr)),
-                                None,
+                        let span = stmt.span;
+                        let var_expr =
+                            expr::Expr::new(expr::ExprKind::Variable(var_sym.clone()), span);
+                        let nil_expr =
+                            expr::Expr::new(expr::ExprKind::Literal(expr::Literal::Nil), span);
+                        let is_not_nil = expr::Expr::new(
+                            expr::ExprKind::Binary(
+                                Box::new(var_expr.clone()),
+                                expr::BinaryOp {
+                                    toktype: expr::BinaryOpTy::NotEqual,
+                                    line: 0,
+                                    col: 0,
+                                },
+                                Box::new(nil_expr),
                             ),
-                        ])
+                            span,
+                        );
+                        expr::Stmt::new(
+                            expr::StmtKind::Block(vec![
+                                expr::Stmt::new(
+                                    expr::StmtKind::VarDecl(var_sym, Some(expr.clone())),
+                                    span,
+                                ),
+                                expr::Stmt::new(
+                                    expr::StmtKind::If(
+                                        is_not_nil,
+                                        Box::new(expr::Stmt::new(
+                                            expr::StmtKind::Print(var_expr),
+                                            span,
+                                        )),
+                                        None,
+                                    ),
+                                    span,
+                                ),
+                            ]),
+                            span,
+                        )
                     }
                     _ => stmt.clone(),
                 })
@@ -169,47 +261,18 @@ pub(crate) fn eval_tokens2(
             match interpreter.interpret(&stmts2) {
                 Ok(()) => {}
                 Err(err) => {
-                    if err[0..40] == "attempting to assign to undeclared variable at".to_string()[0..40] {
+                    if err.message.starts_with("NameError: attempting to assign to undeclared variable") {
                         println!("hi");
                     } else {
-                    println!(
-                    "Runtime error: {}\n\n{}",
-                    err,
-                    interpreter.format_backtrace())
+                        println!("Runtime error: {}\n\n{}", err, err.format_backtrace());
                     }
                 },
             }
         }
-        Err(
-            err
-            @
-            parser::Error::TokenMismatch {
-                expected: lexer::TokenType::Semicolon,
-                found:
-                lexer::Token {
-                    toktype: lexer::TokenType::Eof,
-                    ..
-                },
-                ..
-            },
-        ) => {
-            let expected_eof = tokens.pop().unwrap();
-
-            tokens.push(lexer::Token {
-                toktype: lexer::TokenType::Semicolon,
-                lexing: Vec::new(),
-                literal: None,
-                line: 0,
-                col: -1,
-            });
-            tokens.push(expected_eof);
-
-            if recursion_depth > 0 {
-                println!("Errrr2");
-            } else {
-                eval_tokens2(interpreter, tokens, recursion_depth + 1, extensions, line)
-            }
+        Err(err) if recursion_depth == 0 && missing_final_semicolon(&err) => {
+            eval_tokens2(interpreter, source, recursion_depth + 1, extensions)
         }
-        Err(err) => println!("Errrrr3"),
+        Err(parser::Error::Lexer(err)) => print_lexer_error(&err),
+        Err(err) => println!("Parse error: {}", err),
     }
 }
\ No newline at end of file
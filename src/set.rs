@@ -0,0 +1,94 @@
+use crate::dict::Dict;
+use crate::interpreter::{Arity, Interpreter, MethodDef, RuntimeError, Value};
+
+// 삽입 순서를 유지하는 집합. 값이 모두 nil인 딕셔너리로 구현함
+#[derive(Debug, Default, Clone)]
+pub struct Set(Dict);
+
+impl Set {
+    pub fn items(&self) -> impl Iterator<Item = &Value> {
+        self.0.entries().iter().map(|(item, _)| item)
+    }
+
+    // 삽입 순서로 index번째 원소
+    pub fn item_at(&self, index: usize) -> Option<&Value> {
+        self.0.entries().get(index).map(|(item, _)| item)
+    }
+
+    pub fn contains(&self, item: &Value) -> Result<bool, RuntimeError> {
+        self.0.contains(item)
+    }
+
+    pub fn insert(&mut self, item: Value) -> Result<(), RuntimeError> {
+        self.0.insert(item, Value::Nil)
+    }
+
+    // 있었으면 true
+    pub fn remove(&mut self, item: &Value) -> Result<bool, RuntimeError> {
+        Ok(self.0.remove(item)?.is_some())
+    }
+}
+
+// 파이썬 집합 메소드
+pub static METHODS: &[MethodDef] = &[
+    MethodDef {
+        name: "add",
+        arity: Arity::exactly(1),
+        keywords: &[],
+        callable: add,
+    },
+    MethodDef {
+        name: "remove",
+        arity: Arity::exactly(1),
+        keywords: &[],
+        callable: remove,
+    },
+    MethodDef {
+        name: "discard",
+        arity: Arity::exactly(1),
+        keywords: &[],
+        callable: discard,
+    },
+];
+
+fn set_id(receiver: &Value) -> u64 {
+    match receiver {
+        Value::Set(set_id) => *set_id,
+        _ => panic!("Internal interpreter error! set method called on {:?}.", receiver),
+    }
+}
+
+fn add(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    args: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    interpreter
+        .get_set_mut(set_id(receiver))
+        .insert(args[0].clone())?;
+    Ok(Value::Nil)
+}
+
+fn remove(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    args: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    if interpreter.get_set_mut(set_id(receiver)).remove(&args[0])? {
+        Ok(Value::Nil)
+    } else {
+        Err(interpreter.key_error(&args[0]))
+    }
+}
+
+fn discard(
+    interpreter: &mut Interpreter,
+    receiver: &Value,
+    args: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    interpreter.get_set_mut(set_id(receiver)).remove(&args[0])?;
+    Ok(Value::Nil)
+}
@@ -0,0 +1,42 @@
+use crate::interpreter::{Arity, Interpreter, MethodDef, RuntimeError, Value};
+
+// 파이썬 문자열 메소드
+pub static METHODS: &[MethodDef] = &[
+    MethodDef {
+        name: "upper",
+        arity: Arity::exactly(0),
+        keywords: &[],
+        callable: upper,
+    },
+    MethodDef {
+        name: "lower",
+        arity: Arity::exactly(0),
+        keywords: &[],
+        callable: lower,
+    },
+];
+
+fn as_str(receiver: &Value) -> &str {
+    match receiver {
+        Value::String(s) => s,
+        _ => panic!("Internal interpreter error! string method called on {:?}.", receiver),
+    }
+}
+
+fn upper(
+    _: &mut Interpreter,
+    receiver: &Value,
+    _: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    Ok(Value::String(as_str(receiver).to_uppercase()))
+}
+
+fn lower(
+    _: &mut Interpreter,
+    receiver: &Value,
+    _: &[Value],
+    _: &[(String, Value)],
+) -> Result<Value, RuntimeError> {
+    Ok(Value::String(as_str(receiver).to_lowercase()))
+}
@@ -12,7 +12,6 @@
 use std::collections::VecDeque;
 use std::boxed::Box;
 use std::io::stdin;
-use std::io::Write;
 
 
 // 다양한 타입(struct)을 벡터에 넣기
@@ -31,19 +30,13 @@ impl Cage {
     fn push(&mut self, animal: Box<dyn Animal>) {
         self.lane.push_back(animal);
         let len = self.lane.len();
-        if len > 1 {
-            if self.lane[len - 1].animal_type() == self.lane[len - 2].animal_type() {
-                println!("{} and {} fight",
-                         self.lane[len - 2].animal_name(),
-                         self.lane[len - 1].animal_name())
-            }
+        if len > 1 && self.lane[len - 1].animal_type() == self.lane[len - 2].animal_type() {
+            println!("{} and {} fight",
+                     self.lane[len - 2].animal_name(),
+                     self.lane[len - 1].animal_name())
         }
         println!("{}", len);
     }
-
-    fn pop(&mut self) {
-        self.lane.pop_back().unwrap();
-    }
 }
 
 pub trait Animal {
@@ -58,10 +51,10 @@ struct Elephant {
 
 impl Animal for Elephant {
     fn animal_name(&self) -> String {
-        format!("{}", self.name)
+        self.name.clone()
     }
     fn animal_type(&self) -> String {
-        format!("{}", self.types)
+        self.types.clone()
     }
 }
 
@@ -83,10 +76,10 @@ struct Lion {
 
 impl Animal for Lion {
     fn animal_name(&self) -> String {
-        format!("{}", self.name)
+        self.name.clone()
     }
     fn animal_type(&self) -> String {
-        format!("{}", self.types)
+        self.types.clone()
     }
 }
 
@@ -108,10 +101,10 @@ struct Hippo {
 
 impl Animal for Hippo {
     fn animal_name(&self) -> String {
-        format!("{}", self.name)
+        self.name.clone()
     }
     fn animal_type(&self) -> String {
-        format!("{}", self.types)
+        self.types.clone()
     }
 }
 
@@ -137,13 +130,6 @@ fn main() {
     // let e1 = Elephant { name: "Elephant1".to_string(), types: "Elephant".to_string()};
     // let e2 = Elephant { name: "Elephant2".to_string(), types: "Elephant".to_string()};
 
-    let mut l1 = Lion::new();
-    let mut l2 = Lion::new();
-    let mut h1 = Hippo::new();
-    let mut h2 = Hippo::new();
-    let mut e1 = Elephant::new();
-    let mut e2 = Elephant::new();
-
     // cage.push(Box::new(a));
     // cage.push(Box::new(d));
     // cage.push(Box::new(h2));